@@ -0,0 +1,238 @@
+//
+// Draw calls and frame time of a single cube and of a grid of cubes with their R layer turning,
+// drawn with the same lights as the demo into an offscreen target. The pieces are drawn both as
+// instances of one mesh, like [Cube] does, and the way cubes were drawn before that: a mesh and
+// a draw call for every piece. Run with `cargo bench --bench instancing`.
+//
+// With Mesa's software renderer (llvmpipe) on one core at 800x600, times vary by 10-20% between runs:
+//
+//     1 cube per piece: 27 draw calls, 15.71 ms per frame
+//     1 cube instanced: 1 draw calls, 14.26 ms per frame
+//     64 cubes per piece: 1728 draw calls, 125.11 ms per frame
+//     64 cubes instanced: 64 draw calls, 52.78 ms per frame
+//
+// Most of the time of a single cube goes to shading the lit fragments, not to draw calls. three-d
+// draws opaque objects nearest first so the depth test skips the pieces behind, and instances
+// drawn in a fixed order made a single cube 20-30% slower until they were sorted the same way.
+//
+use std::cell::Cell;
+use std::time::Instant;
+
+use eyecandy::rubik::{Cube, CubeAnimationOptions, CubeAppearance, LightRig, Move, RubikMaterial};
+use three_d::context::HasContext;
+use three_d::*;
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 600;
+
+/// Frames drawn before measuring, and then measured.
+const WARMUP_FRAMES: u32 = 10;
+const FRAMES: u32 = 100;
+
+/// Distance between the centers of neighbouring cubes, like the grid scene.
+const GRID_SPACING: f32 = 4.0;
+
+const MOVE_TIME: f32 = 300.0;
+
+/// Face normals and in-plane axes in the cube's face order (L, U, F, D, R, B), like the pieces of [Cube].
+const FACE_AXES: [(Vec3, Vec3, Vec3); 6] = [
+    (vec3(-1.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0), vec3(0.0, 1.0, 0.0)),
+    (vec3(0.0, 1.0, 0.0), vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, -1.0)),
+    (vec3(0.0, 0.0, 1.0), vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+    (vec3(0.0, -1.0, 0.0), vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0)),
+    (vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, -1.0), vec3(0.0, 1.0, 0.0)),
+    (vec3(0.0, 0.0, -1.0), vec3(-1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+];
+
+/// Cubes that can be animated and drawn.
+trait Cubes {
+    fn animate(&mut self, time: f32);
+    fn objects(&self) -> Vec<&dyn Object>;
+}
+
+/// The cubes of the crate, each drawn with one call.
+struct Instanced(Vec<Cube>);
+
+impl Cubes for Instanced {
+    fn animate(&mut self, time: f32) {
+        for cube in self.0.iter_mut() {
+            if cube.is_idle() {
+                cube.queue([Move::R; 4].into_iter());
+            }
+            cube.animate(time).unwrap();
+        }
+    }
+
+    fn objects(&self) -> Vec<&dyn Object> {
+        self.0.iter().flatten().collect()
+    }
+}
+
+/// Every piece of every cube as its own mesh, with the position of its cube.
+struct PerPiece(Vec<(Gm<Mesh, RubikMaterial>, Vec3, Mat4)>);
+
+impl Cubes for PerPiece {
+    fn animate(&mut self, time: f32) {
+        // The same turns of R as the instanced cubes, without pausing between them
+        let angle = Mat4::from_angle_x(degrees(-90.0 * time / MOVE_TIME));
+        for (piece, position, placement) in self.0.iter_mut() {
+            if position.x > 0.5 {
+                piece.set_transformation(*placement * angle);
+            }
+        }
+    }
+
+    fn objects(&self) -> Vec<&dyn Object> {
+        self.0.iter().map(|(piece, _, _)| piece as &dyn Object).collect()
+    }
+}
+
+///
+/// A piece of the default appearance at `position`, with the sticker colors of a solved cube on
+/// the faces on the outside. The same mesh [Cube] draws, built the way it was for every piece.
+///
+fn piece_mesh(position: Vec3, appearance: &CubeAppearance) -> CpuMesh {
+    let (mut positions, mut normals, mut uvs, mut colors, mut indices) = (vec![], vec![], vec![], vec![], vec![]);
+    for (face, &(normal, u_axis, v_axis)) in FACE_AXES.iter().enumerate() {
+        let color = if position.dot(normal) > 0.5 { appearance.colors[face] } else { Srgba::new(0, 0, 0, 0) };
+        let base = positions.len() as u32;
+        for (u, v) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+            positions.push(position + (normal + u_axis * u + v_axis * v) * 0.5);
+            normals.push(normal);
+            uvs.push(vec2((u + 1.0) / 2.0, (v + 1.0) / 2.0));
+            colors.push(color);
+        }
+        indices.extend([base, base + 1, base + 3, base, base + 3, base + 2]);
+    }
+    CpuMesh {
+        positions: Positions::F32(positions),
+        indices: Indices::U32(indices),
+        normals: Some(normals),
+        uvs: Some(uvs),
+        colors: Some(colors),
+        ..Default::default()
+    }
+}
+
+/// Counts the objects three-d draws after leaving out the ones the camera can't see.
+struct Counted<'a> {
+    object: &'a dyn Object,
+    draws: &'a Cell<usize>,
+}
+
+impl Geometry for Counted<'_> {
+    fn draw(&self, camera: &Camera, program: &Program, render_states: RenderStates, attributes: FragmentAttributes) {
+        self.object.draw(camera, program, render_states, attributes)
+    }
+
+    fn vertex_shader_source(&self, required_attributes: FragmentAttributes) -> String {
+        self.object.vertex_shader_source(required_attributes)
+    }
+
+    fn id(&self, required_attributes: FragmentAttributes) -> u16 {
+        self.object.id(required_attributes)
+    }
+
+    fn render_with_material(&self, material: &dyn Material, camera: &Camera, lights: &[&dyn Light]) {
+        self.object.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.object.render_with_effect(material, camera, lights, color_texture, depth_texture)
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.object.aabb()
+    }
+}
+
+impl Object for Counted<'_> {
+    /// Both the pieces and the cubes are drawn with a single call each.
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        self.draws.set(self.draws.get() + 1);
+        self.object.render(camera, lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.object.material_type()
+    }
+}
+
+fn main() {
+    let Some(context) = common::context() else { return };
+    let appearance = CubeAppearance::default();
+    let anim = CubeAnimationOptions { move_time: MOVE_TIME, ..Default::default() };
+    for size in [1, 8] {
+        let offset = (size as f32 - 1.0) * GRID_SPACING / 2.0;
+        let placements: Vec<Mat4> = (0..size * size)
+            .map(|i| Mat4::from_translation(vec3((i % size) as f32 * GRID_SPACING - offset, 0.0, (i / size) as f32 * GRID_SPACING - offset)))
+            .collect();
+
+        let mut per_piece = PerPiece(vec![]);
+        for &placement in &placements {
+            for cubelet in 0..27 {
+                let position = vec3((cubelet / 9) as f32 - 1.0, (cubelet / 3 % 3) as f32 - 1.0, (cubelet % 3) as f32 - 1.0);
+                let mut piece = Gm::new(Mesh::new(&context, &piece_mesh(position, &appearance)), RubikMaterial::from(&appearance));
+                piece.set_transformation(placement);
+                per_piece.0.push((piece, position, placement));
+            }
+        }
+        bench(&context, "per piece", size, &mut per_piece);
+
+        let mut instanced = Instanced(placements.iter().map(|&placement| {
+            let mut cube = Cube::solved(anim, &context);
+            cube.set_transformation(placement);
+            cube
+        }).collect());
+        bench(&context, "instanced", size, &mut instanced);
+    }
+}
+
+fn bench(context: &Context, name: &str, size: usize, cubes: &mut impl Cubes) {
+    let camera = Camera::new_perspective(
+        Viewport::new_at_origo(WIDTH, HEIGHT),
+        vec3(0.0, 2.0 + 4.0 * size as f32, 4.0 + 5.0 * size as f32),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        200.0,
+    );
+    let lights = LightRig::new(context);
+    let mut color = Texture2D::new_empty::<[u8; 4]>(
+        context, WIDTH, HEIGHT, Interpolation::Nearest, Interpolation::Nearest, None, Wrapping::ClampToEdge, Wrapping::ClampToEdge,
+    );
+    let mut depth = DepthTexture2D::new::<f32>(context, WIDTH, HEIGHT, Wrapping::ClampToEdge, Wrapping::ClampToEdge);
+
+    let draws = Cell::new(0);
+    let mut start = Instant::now();
+    for frame in 0..WARMUP_FRAMES + FRAMES {
+        if frame == WARMUP_FRAMES {
+            start = Instant::now();
+            draws.set(0);
+        }
+        // Every frame moves the animations on by 1/60 s, whatever the time it took
+        cubes.animate(frame as f32 * 1000.0 / 60.0);
+        let objects = cubes.objects();
+        let counted = objects.iter().map(|&object| Counted { object, draws: &draws });
+        RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+            .clear(ClearState::color_and_depth(0.2, 0.2, 0.2, 1.0, 1.0))
+            .render(&camera, counted, &lights.lights());
+        unsafe { context.finish() };
+    }
+    let frame_time = start.elapsed().as_secs_f64() * 1000.0 / FRAMES as f64;
+    println!(
+        "{} {} {}: {} draw calls, {:.2} ms per frame",
+        size * size, if size == 1 { "cube" } else { "cubes" }, name, draws.get() / FRAMES as usize, frame_time,
+    );
+}
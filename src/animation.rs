@@ -1,5 +1,6 @@
 use cgmath::num_traits::{NumOps, Zero};
 use cgmath::{Angle, InnerSpace, Quaternion, Rad, Vector3};
+use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 use three_d::Camera;
 
@@ -34,6 +35,7 @@ pub struct SecondOrderSystem<T> {
 /// - If you want to change the initial response of the system, change **r**.
 /// - If you want to speed up or slow down the system as a whole, change **freq**.
 ///
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SecondOrderSystemParameters {
     /// The natural frequency of the system in cycles per second.
     /// it controls the general speed of the system.
@@ -95,6 +97,11 @@ where
     pub fn value(&self) -> T {
         self.y
     }
+
+    /// Get how fast the state of the system is currently changing.
+    pub fn velocity(&self) -> T {
+        self.dy
+    }
 }
 
 /// Rotate the camera around a given point by two angles (in radians),
@@ -122,6 +129,5 @@ pub fn rotate_around_axis(vector: Vector3<f32>, axis: Vector3<f32>, angle: f32)
     let angle = Rad(angle / 2.0);
     let q = Quaternion::from_sv(angle.cos(), angle.sin() * axis).normalize();
     let p = Quaternion::from_sv(0.0, vector).normalize();
-    let rotated = (q * p * q.conjugate()).v;
-    rotated
+    (q * p * q.conjugate()).v
 }
@@ -1,5 +1,6 @@
 use three_d::{Window, WindowSettings};
-use eyecandy::rubik;
+use eyecandy::scene::{self, SceneRegistry};
+use eyecandy::Error;
 
 use env_logger::Env;
 
@@ -8,11 +9,20 @@ pub fn main() {
         Env::default()
             .default_filter_or("trace")
     ).format_timestamp(None).init();
+    let registry = SceneRegistry::default();
+    let name = std::env::args().nth(1).unwrap_or("rubik".to_string());
+    if !registry.names().any(|n| n == name) {
+        eprintln!("Unknown scene '{}', available scenes: {}", name, registry.names().collect::<Vec<_>>().join(", "));
+        std::process::exit(1);
+    }
     let window = Window::new(WindowSettings {
         title: "Demo".to_string(),
         max_size: Some((800, 600)),
         ..Default::default()
-    }).unwrap();
-    let closure = rubik::run(&window);
-    window.render_loop(closure);
-}
\ No newline at end of file
+    }).unwrap_or_else(|e| {
+        eprintln!("{}", Error::Context(e.to_string()));
+        std::process::exit(1);
+    });
+    let scene = registry.create(&name, &window).unwrap();
+    window.render_loop(scene::run(scene));
+}
@@ -1,5 +1,6 @@
 use cgmath::{InnerSpace, MetricSpace};
 use cgmath::num_traits::abs;
+use serde::{Deserialize, Serialize};
 use three_d::{Camera, Event, MouseButton, Vec2, Vec3, Zero};
 use crate::animation::{rotate_camera_around_target, SecondOrderSystem, SecondOrderSystemParameters};
 
@@ -18,6 +19,8 @@ pub struct SmoothOrbitControl {
     curr_zoom: f32,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SmoothOrbitControlSettings {
     pub orbit_decay_rate: f32,
     pub sensitivity: f32,
@@ -80,7 +83,7 @@ impl SmoothOrbitControl {
                     button: Some(MouseButton::Left),
                     delta: (x, y),
                     handled, ..
-                } if *handled == false => {
+                } if !*handled => {
                     mouse_delta = Some(Vec2::new(*x, *y));
                     *handled = true;
                     change = true;
@@ -88,7 +91,7 @@ impl SmoothOrbitControl {
                 Event::MousePress {
                     button: MouseButton::Left,
                     handled, ..
-                } if *handled == false => {
+                } if !*handled => {
                     self.mouse_pressed = true;
                     *handled = true;
                     change = true;
@@ -96,14 +99,14 @@ impl SmoothOrbitControl {
                 Event::MouseRelease {
                     button: MouseButton::Left,
                     handled, ..
-                } if *handled == false => {
+                } if !*handled => {
                     self.mouse_pressed = false;
                     *handled = true;
                 }
                 Event::MouseWheel {
                     delta: (x, y),
                     handled, ..
-                } if *handled == false => {
+                } if !*handled => {
                     // self.handle_action(camera, event);
                     *handled = true;
                     scroll_delta = Some(Vec2::new(*x, *y));
@@ -116,6 +119,13 @@ impl SmoothOrbitControl {
         change
     }
 
+    /// Whether the camera has stopped moving: no orbit momentum left and the zoom has caught up.
+    pub fn is_settled(&self) -> bool {
+        self.rotation_speed.magnitude() <= 0.001
+            && abs(self.soft_zoom.value() - self.hard_zoom) <= 0.005
+            && abs(self.soft_zoom.velocity()) <= 0.005
+    }
+
     fn frame(
         &mut self,
         camera: &mut Camera,
@@ -0,0 +1,39 @@
+use three_d::Vec3;
+
+///
+/// Everything that can go wrong in eyecandy.
+///
+/// The messages are meant for people, so they can be shown as they are. In the browser errors
+/// are thrown as JavaScript `Error`s with the same message.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// Text that isn't in the expected format, like a move, a facelet string or an exported session.
+    Parse(String),
+    /// A well formed state that a real cube can't be in, or that doesn't fit what it's used for.
+    Validation(String),
+    /// A piece was turned by something other than a quarter turn around one of the axes.
+    InvalidRotation { before: Vec3, after: Vec3 },
+    /// The solver was stopped before it found a solution.
+    Cancelled,
+    /// The WebGL or OpenGL context couldn't be created.
+    Context(String),
+    /// A scene couldn't be bound to a canvas.
+    Canvas(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parse(message) | Error::Validation(message) => write!(f, "{}", message),
+            Error::InvalidRotation { before, after } => {
+                write!(f, "Can't turn a piece from {:?} to {:?}", before, after)
+            }
+            Error::Cancelled => write!(f, "The search was cancelled"),
+            Error::Context(message) => write!(f, "Can't create the graphics context: {}", message),
+            Error::Canvas(message) => write!(f, "Can't bind the canvas: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,10 +1,15 @@
 pub mod animation;
 pub mod control;
+mod error;
+pub mod postprocessing;
 pub mod rubik;
+pub mod scene;
 pub mod wasm;
 
 use three_d::{Context, Viewport};
 
+pub use error::Error;
+
 pub trait WindowLike {
     fn gl(&self) -> Context;
     fn viewport(&self) -> Viewport;
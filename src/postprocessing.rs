@@ -0,0 +1,332 @@
+use three_d::*;
+
+use crate::WindowLike;
+
+///
+/// A single step of the post-processing chain.
+/// Effects are applied in the order they're given, every one of them reads the output of the previous one.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PostEffect {
+    /// Screen space ambient occlusion. Darkens creases and corners using the depth of the scene.
+    Ssao {
+        /// Radius of the sampled area in world units.
+        radius: f32,
+        intensity: f32,
+    },
+    /// Makes the bright parts of the image glow.
+    Bloom {
+        /// Brightness above which a pixel starts glowing.
+        threshold: f32,
+        intensity: f32,
+        /// Spread of the glow in pixels.
+        radius: f32,
+    },
+    /// Maps the HDR colors of the scene to the displayable range.
+    /// Without it colors brighter than white are simply clipped.
+    ToneMapping(ToneMapping),
+    /// Fast approximate anti-aliasing. Works best after tone mapping.
+    Fxaa,
+    /// Darkens the edges of the screen.
+    Vignette {
+        /// How dark the corners get, from 0 to 1.
+        strength: f32,
+        /// Distance from the center where the darkening starts, from 0 (center) to 1 (corners).
+        radius: f32,
+    },
+}
+
+impl PostEffect {
+    /// A chain that looks good on the cube: subtle ambient occlusion and glow, ACES tone mapping,
+    /// anti-aliasing and a soft vignette.
+    pub fn defaults() -> Vec<PostEffect> {
+        vec![
+            PostEffect::Ssao { radius: 0.3, intensity: 0.6 },
+            PostEffect::Bloom { threshold: 0.9, intensity: 0.35, radius: 2.0 },
+            PostEffect::ToneMapping(ToneMapping::Aces),
+            PostEffect::Fxaa,
+            PostEffect::Vignette { strength: 0.35, radius: 0.45 },
+        ]
+    }
+}
+
+///
+/// Renders a scene offscreen and runs it through a chain of [PostEffect]s before it reaches the screen.
+///
+/// The scene is drawn in linear HDR colors into a floating point color texture and a depth texture,
+/// then every effect is applied as a full screen pass, ping-ponging between two buffers.
+/// The final copy to the screen applies the color mapping of the camera.
+///
+pub struct PostProcessing {
+    context: Context,
+    pub effects: Vec<PostEffect>,
+    size: (u32, u32),
+    buffers: [Texture2D; 2],
+    depth: DepthTexture2D,
+    bloom: [Texture2D; 2],
+}
+
+impl PostProcessing {
+    /// Create the offscreen targets with the size of the window.
+    pub fn new(window: &impl WindowLike, effects: Vec<PostEffect>) -> Self {
+        Self::with_context(&window.gl(), window.viewport(), effects)
+    }
+
+    /// Create the offscreen targets with the size of the given viewport.
+    pub fn with_context(context: &Context, viewport: Viewport, effects: Vec<PostEffect>) -> Self {
+        let context = context.clone();
+        let size = (viewport.width.max(1), viewport.height.max(1));
+        Self {
+            buffers: [color_texture(&context, size), color_texture(&context, size)],
+            depth: depth_texture(&context, size),
+            bloom: [color_texture(&context, half(size)), color_texture(&context, half(size))],
+            context,
+            effects,
+            size,
+        }
+    }
+
+    ///
+    /// Render a frame. `draw` renders the scene into the given target using the given camera,
+    /// which is a copy of `camera` without tone and color mapping.
+    /// The processed image is then written to `screen`.
+    ///
+    pub fn render(
+        &mut self,
+        screen: &RenderTarget,
+        camera: &Camera,
+        draw: impl FnOnce(&RenderTarget, &Camera),
+    ) {
+        self.resize((screen.width(), screen.height()));
+        let mut scene_camera = camera.clone();
+        scene_camera.set_viewport(Viewport::new_at_origo(self.size.0, self.size.1));
+        scene_camera.tone_mapping = ToneMapping::None;
+        scene_camera.color_mapping = ColorMapping::None;
+        {
+            let [color, _] = &mut self.buffers;
+            let target = RenderTarget::new(color.as_color_target(None), self.depth.as_depth_target());
+            draw(&target, &scene_camera);
+        }
+
+        let mut current = 0;
+        for effect in self.effects.clone() {
+            self.apply(effect, &scene_camera, &mut current);
+        }
+
+        let mut blit_camera = Camera::new_2d(screen.viewport());
+        blit_camera.tone_mapping = ToneMapping::None;
+        blit_camera.color_mapping = camera.color_mapping;
+        screen.write(|| {
+            apply_screen_effect(
+                &self.context,
+                ScreenEffect { write_mask: WriteMask::COLOR },
+                &blit_camera,
+                &[],
+                Some(ColorTexture::Single(&self.buffers[current])),
+                None,
+            );
+        });
+    }
+
+    fn resize(&mut self, size: (u32, u32)) {
+        let size = (size.0.max(1), size.1.max(1));
+        if size != self.size {
+            self.buffers = [color_texture(&self.context, size), color_texture(&self.context, size)];
+            self.depth = depth_texture(&self.context, size);
+            self.bloom = [color_texture(&self.context, half(size)), color_texture(&self.context, half(size))];
+            self.size = size;
+        }
+    }
+
+    fn apply(&mut self, effect: PostEffect, camera: &Camera, current: &mut usize) {
+        let context = &self.context;
+        let full = Camera::new_2d(Viewport::new_at_origo(self.size.0, self.size.1));
+        let (first, second) = self.buffers.split_at_mut(1);
+        let (src, dst) = if *current == 0 {
+            (&first[0], &mut second[0])
+        } else {
+            (&second[0], &mut first[0])
+        };
+        match effect {
+            PostEffect::Ssao { radius, intensity } => {
+                let projection = camera.projection();
+                let resolution = vec2(self.size.0 as f32, self.size.1 as f32);
+                let pass = Pass {
+                    id: 0,
+                    source: include_str!("postprocessing/ssao.frag"),
+                    uniforms: &|program: &Program| {
+                        program.use_uniform("projectionInverse", projection.invert().unwrap_or(Mat4::identity()));
+                        program.use_uniform("projectionScale", projection.y.y);
+                        program.use_uniform("resolution", resolution);
+                        program.use_uniform("radius", radius);
+                        program.use_uniform("intensity", intensity);
+                    },
+                };
+                dst.as_color_target(None).write(|| {
+                    apply_screen_effect(
+                        context,
+                        &pass,
+                        &full,
+                        &[],
+                        Some(ColorTexture::Single(src)),
+                        Some(DepthTexture::Single(&self.depth)),
+                    );
+                });
+            }
+            PostEffect::Bloom { threshold, intensity, radius } => {
+                let [bright, blurred] = &mut self.bloom;
+                let small = Camera::new_2d(Viewport::new_at_origo(bright.width(), bright.height()));
+                let texel = vec2(1.0 / bright.width() as f32, 1.0 / bright.height() as f32);
+                let threshold_pass = Pass {
+                    id: 1,
+                    source: include_str!("postprocessing/bloom_threshold.frag"),
+                    uniforms: &|program: &Program| program.use_uniform("threshold", threshold),
+                };
+                bright.as_color_target(None).write(|| {
+                    apply_screen_effect(context, &threshold_pass, &small, &[], Some(ColorTexture::Single(src)), None);
+                });
+                for direction in [vec2(texel.x, 0.0), vec2(0.0, texel.y)] {
+                    let (from, to) = if direction.y == 0.0 { (&*bright, &mut *blurred) } else { (&*blurred, &mut *bright) };
+                    let blur_pass = Pass {
+                        id: 2,
+                        source: include_str!("postprocessing/bloom_blur.frag"),
+                        uniforms: &|program: &Program| program.use_uniform("direction", direction * radius / 2.0),
+                    };
+                    to.as_color_target(None).write(|| {
+                        apply_screen_effect(context, &blur_pass, &small, &[], Some(ColorTexture::Single(from)), None);
+                    });
+                }
+                let bloom = &*bright;
+                let combine_pass = Pass {
+                    id: 3,
+                    source: include_str!("postprocessing/bloom_combine.frag"),
+                    uniforms: &|program: &Program| {
+                        program.use_texture("bloomMap", bloom);
+                        program.use_uniform("intensity", intensity);
+                    },
+                };
+                dst.as_color_target(None).write(|| {
+                    apply_screen_effect(context, &combine_pass, &full, &[], Some(ColorTexture::Single(src)), None);
+                });
+            }
+            PostEffect::ToneMapping(tone_mapping) => {
+                let pass = Pass {
+                    id: 4,
+                    source: concat!("{tone_mapping}", include_str!("postprocessing/tone_mapping.frag")),
+                    uniforms: &|program: &Program| tone_mapping.use_uniforms(program),
+                };
+                dst.as_color_target(None).write(|| {
+                    apply_screen_effect(context, &pass, &full, &[], Some(ColorTexture::Single(src)), None);
+                });
+            }
+            PostEffect::Fxaa => {
+                dst.as_color_target(None).write(|| {
+                    apply_screen_effect(context, FxaaEffect {}, &full, &[], Some(ColorTexture::Single(src)), None);
+                });
+            }
+            PostEffect::Vignette { strength, radius } => {
+                let pass = Pass {
+                    id: 5,
+                    source: include_str!("postprocessing/vignette.frag"),
+                    uniforms: &|program: &Program| {
+                        program.use_uniform("strength", strength);
+                        program.use_uniform("radius", radius);
+                    },
+                };
+                dst.as_color_target(None).write(|| {
+                    apply_screen_effect(context, &pass, &full, &[], Some(ColorTexture::Single(src)), None);
+                });
+            }
+        }
+        *current = 1 - *current;
+    }
+}
+
+fn half(size: (u32, u32)) -> (u32, u32) {
+    ((size.0 / 2).max(1), (size.1 / 2).max(1))
+}
+
+fn color_texture(context: &Context, size: (u32, u32)) -> Texture2D {
+    Texture2D::new_empty::<[f16; 4]>(
+        context,
+        size.0,
+        size.1,
+        Interpolation::Linear,
+        Interpolation::Linear,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    )
+}
+
+fn depth_texture(context: &Context, size: (u32, u32)) -> DepthTexture2D {
+    DepthTexture2D::new::<f32>(context, size.0, size.1, Wrapping::ClampToEdge, Wrapping::ClampToEdge)
+}
+
+///
+/// A full screen pass with a shader from the postprocessing folder.
+/// The shader gets `sample_color` (and `sample_depth` if a depth texture is given) and
+/// the `{tone_mapping}` placeholder is replaced by the tone mapping functions.
+///
+struct Pass<'a> {
+    id: u16,
+    source: &'static str,
+    uniforms: &'a dyn Fn(&Program),
+}
+
+impl Effect for Pass<'_> {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}",
+            color_texture.map(|t| t.fragment_shader_source()).unwrap_or_default(),
+            depth_texture.map(|t| t.fragment_shader_source()).unwrap_or_default(),
+            self.source.replace("{tone_mapping}", ToneMapping::fragment_shader_source()),
+        )
+    }
+
+    fn id(&self, color_texture: Option<ColorTexture>, depth_texture: Option<DepthTexture>) -> u16 {
+        // The builtin effects use the bits from 11 upwards, so bit 10 keeps these apart
+        0b1u16 << 10
+            | self.id << 7
+            | color_texture.map(|t| t.id()).unwrap_or(0u16)
+            | depth_texture.map(|t| t.id()).unwrap_or(0u16)
+    }
+
+    fn fragment_attributes(&self) -> FragmentAttributes {
+        FragmentAttributes {
+            uv: true,
+            ..FragmentAttributes::NONE
+        }
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _camera: &Camera,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Some(color_texture) = color_texture {
+            color_texture.use_uniforms(program);
+        }
+        if let Some(depth_texture) = depth_texture {
+            depth_texture.use_uniforms(program);
+        }
+        (self.uniforms)(program);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
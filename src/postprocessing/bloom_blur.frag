@@ -0,0 +1,14 @@
+uniform vec2 direction;
+
+in vec2 uvs;
+layout (location = 0) out vec4 outColor;
+
+void main() {
+    // 9 tap gaussian, direction is already scaled to the texel size and blur radius
+    vec3 sum = sample_color(uvs).rgb * 0.2270270270;
+    sum += sample_color(uvs + direction * 1.3846153846).rgb * 0.3162162162;
+    sum += sample_color(uvs - direction * 1.3846153846).rgb * 0.3162162162;
+    sum += sample_color(uvs + direction * 3.2307692308).rgb * 0.0702702703;
+    sum += sample_color(uvs - direction * 3.2307692308).rgb * 0.0702702703;
+    outColor = vec4(sum, 1.0);
+}
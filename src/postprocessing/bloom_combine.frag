@@ -0,0 +1,10 @@
+uniform sampler2D bloomMap;
+uniform float intensity;
+
+in vec2 uvs;
+layout (location = 0) out vec4 outColor;
+
+void main() {
+    vec4 color = sample_color(uvs);
+    outColor = vec4(color.rgb + texture(bloomMap, uvs).rgb * intensity, color.a);
+}
@@ -0,0 +1,12 @@
+uniform float threshold;
+
+in vec2 uvs;
+layout (location = 0) out vec4 outColor;
+
+void main() {
+    vec3 color = sample_color(uvs).rgb;
+    float brightness = max(color.r, max(color.g, color.b));
+    // Soft knee so pixels right at the threshold don't pop in and out
+    float weight = clamp((brightness - threshold) / max(threshold, 0.0001), 0.0, 1.0);
+    outColor = vec4(color * weight, 1.0);
+}
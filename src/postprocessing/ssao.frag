@@ -0,0 +1,40 @@
+uniform mat4 projectionInverse;
+uniform float projectionScale;
+uniform vec2 resolution;
+uniform float radius;
+uniform float intensity;
+
+in vec2 uvs;
+layout (location = 0) out vec4 outColor;
+
+const int SAMPLES = 16;
+
+vec3 view_position(vec2 uv) {
+    vec4 p = projectionInverse * vec4(uv * 2.0 - 1.0, sample_depth(uv) * 2.0 - 1.0, 1.0);
+    return p.xyz / p.w;
+}
+
+void main() {
+    vec4 color = sample_color(uvs);
+    if (sample_depth(uvs) >= 1.0) {
+        outColor = color;
+        return;
+    }
+    vec3 p = view_position(uvs);
+    vec3 n = normalize(cross(dFdx(p), dFdy(p)));
+
+    // Rotate the sample spiral per pixel so the banding turns into fine noise
+    float angle = fract(52.9829189 * fract(dot(gl_FragCoord.xy, vec2(0.06711056, 0.00583715)))) * 6.2831853;
+    float screen_radius = 0.5 * radius * projectionScale / max(-p.z, 0.001);
+    vec2 aspect = vec2(resolution.y / resolution.x, 1.0);
+
+    float occlusion = 0.0;
+    for (int i = 0; i < SAMPLES; i++) {
+        float a = angle + float(i) * 2.3999632;
+        float r = screen_radius * sqrt((float(i) + 0.5) / float(SAMPLES));
+        vec3 v = view_position(uvs + vec2(cos(a), sin(a)) * r * aspect) - p;
+        occlusion += max(0.0, dot(v, n) - 0.01 * -p.z) / (dot(v, v) + 0.01);
+    }
+    float ao = clamp(1.0 - 2.0 * intensity * occlusion / float(SAMPLES), 0.0, 1.0);
+    outColor = vec4(color.rgb * ao, color.a);
+}
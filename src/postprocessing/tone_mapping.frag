@@ -0,0 +1,7 @@
+in vec2 uvs;
+layout (location = 0) out vec4 outColor;
+
+void main() {
+    vec4 color = sample_color(uvs);
+    outColor = vec4(tone_mapping(color.rgb), color.a);
+}
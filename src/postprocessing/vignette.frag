@@ -0,0 +1,13 @@
+uniform float strength;
+uniform float radius;
+
+in vec2 uvs;
+layout (location = 0) out vec4 outColor;
+
+void main() {
+    vec4 color = sample_color(uvs);
+    // 0 at the center, 1 at the corners
+    float d = length(uvs - vec2(0.5)) * 1.41421356;
+    float darken = smoothstep(radius, 1.0, d) * strength;
+    outColor = vec4(color.rgb * (1.0 - darken), color.a);
+}
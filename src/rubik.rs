@@ -1,12 +1,42 @@
 use std::collections::VecDeque;
 
 use cgmath::{Rad, SquareMatrix};
-pub use graphics::run;
+pub use algs::{apply_moves, cases, find_case, parse_alg, recognize, recognize_masked, AlgCase, AlgSet, CaseMatch};
+pub use cubie::{analyze, AlgAnalysis, Corner, Cubie, CubieCube, Cycle, Edge};
+pub use editor::{check_facelets, facelet_at, FaceletEditor};
+pub use formats::{alg_cubing_url, from_kociemba, parse_alg_url, to_kociemba, twizzle_url};
+pub use graphics::{LightRig, RubikMaterial, RubikScene};
+pub use grid::CubeGridScene;
+pub use mask::{home_facelets, MaskPreset, StickerMask, StickerStyle};
+pub use optimal::{CancelToken, Metric, OptimalSolver, Progress};
+pub use recorder::{stage, Reconstruction, Replay, Split, Stage, TimedMove};
+pub use remote::{CubeCommand, CubeRemote};
+pub use scan::{cube_from_scans, facelets_from_scans, scan_face, FaceGrid, FaceScan, Lab};
+pub use symmetry::{canonical, find_symmetry, symmetries_of, Symmetry};
+pub use timer::{average, Penalty, Session, Solve, SolveTime, Stats, Timer, TimerPhase};
 use log::trace;
-use three_d::{ColorMaterial, CpuMesh, Geometry, Gm, Mat3, Mat4, Material, Mesh, Object, Srgba, CpuTexture, Texture2DRef, Vec3};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use three_d::{Geometry, Gm, InnerSpace, Mat3, Mat4, Object, Srgba, Vec3};
 
-use self::graphics::{cube_uvs, RubikMaterial};
+use self::algs::OPPOSITE;
+use self::graphics::{hint_mesh, piece_mesh, styled_sticker, FACE_AXES};
+use self::instances::{PieceInstance, PieceInstances};
+use self::recorder::Recorder;
+use crate::Error;
+mod algs;
+mod cubie;
+mod editor;
+mod formats;
 mod graphics;
+mod grid;
+mod instances;
+mod mask;
+mod optimal;
+mod recorder;
+mod remote;
+mod scan;
+mod symmetry;
+mod timer;
 
 const COLORS: [Srgba; 6] = [
     Srgba::new(31, 68, 166, 255), // blue
@@ -26,6 +56,17 @@ const FACELETS: [usize; 54] = [
     18,  9,  0, 21, 12,  3, 24, 15,  6
 ];
 
+/// The cubelets of each face, in the order of its facelets.
+const FACE_CUBELETS: [[usize; 9]; 6] = {
+    let mut faces = [[0; 9]; 6];
+    let mut facelet = 0;
+    while facelet < 54 {
+        faces[facelet / 9][facelet % 9] = FACELETS[facelet];
+        facelet += 1;
+    }
+    faces
+};
+
 pub const ROT_XY_CW: Mat3 = Mat3::new(
     1.0, 0.0, 0.0,
     0.0, 0.0, 1.0,
@@ -62,6 +103,15 @@ pub const ROT_YZ_CCW: Mat3 = Mat3::new(
     0.0, 0.0, 1.0
 );
 
+/// Faces the default camera doesn't see, which get hint facelets: L, D and B.
+const HINT_FACES: [usize; 3] = [0, 3, 5];
+
+/// Gray of the ignored stickers, like on alg.cubing.net.
+const MASKED_COLOR: Srgba = Srgba::new(68, 68, 68, 255);
+
+/// Facelet string of a solved cube.
+pub const SOLVED: &str = "BBBBBBBBBYYYYYYYYYRRRRRRRRRWWWWWWWWWGGGGGGGGGOOOOOOOOO";
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Move {
     L, LP, L2,
@@ -72,7 +122,7 @@ pub enum Move {
     B, BP, B2,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
 enum Color {
     Blue,
     Yellow,
@@ -80,12 +130,13 @@ enum Color {
     White,
     Green,
     Orange,
+    #[default]
     None
 }
 
-impl Into<Srgba> for Color {
-    fn into(self) -> Srgba {
-        match self {
+impl From<Color> for Srgba {
+    fn from(color: Color) -> Srgba {
+        match color {
             Color::Blue => COLORS[0],
             Color::Yellow => COLORS[1],
             Color::Red => COLORS[2],
@@ -97,31 +148,98 @@ impl Into<Srgba> for Color {
     }
 }
 
-impl Default for Color {
-    fn default() -> Self {
-        Color::None
+impl Color {
+    /// The color of a face in the given palette, or nothing if it's a face without a sticker.
+    fn sticker(self, palette: &[Srgba; 6]) -> Option<Srgba> {
+        match self {
+            Color::None => None,
+            color => Some(palette[color as usize]),
+        }
+    }
+
+    fn from_char(c: char) -> Option<Color> {
+        match c {
+            'B' => Some(Color::Blue),
+            'Y' => Some(Color::Yellow),
+            'R' => Some(Color::Red),
+            'W' => Some(Color::White),
+            'G' => Some(Color::Green),
+            'O' => Some(Color::Orange),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Color::Blue => 'B',
+            Color::Yellow => 'Y',
+            Color::Red => 'R',
+            Color::White => 'W',
+            Color::Green => 'G',
+            Color::Orange => 'O',
+            Color::None => '-',
+        }
     }
 }
 
+/// Colors in the order of their index in a palette.
+const COLOR_ORDER: [Color; 6] = [Color::Blue, Color::Yellow, Color::Red, Color::White, Color::Green, Color::Orange];
+
 type PieceMaterial = RubikMaterial;
 
 pub(crate) struct Piece {
     position: (i32, i32, i32),
     color: (Color, Color, Color),
+    /// Position the mesh was built at, before any move was applied.
+    origin: (i32, i32, i32),
+    /// Colors of the mesh faces in L, U, F, D, R, B order.
+    faces: [Color; 6],
+    /// How the mesh faces are drawn, see [StickerMask].
+    styles: [StickerStyle; 6],
+    /// The moves applied to the piece since it was at its origin.
     transform: Mat4,
-    gm: Gm<Mesh, PieceMaterial>
+    /// Where the piece is drawn, in the middle of a move.
+    transformation: Mat4,
 }
 
 pub struct Cube {
+    /// The pieces in the order of the cubelets they started at.
     pub(crate) pieces: Vec<Piece>,
-    current_move: Option<Move>,
-    current_face: Option<[usize; 9]>,
+    /// Index of the piece at every cubelet, updated as moves are applied.
+    by_cubelet: [usize; 27],
+    context: three_d::Context,
+    /// All the pieces, drawn at once.
+    meshes: Gm<PieceInstances, PieceMaterial>,
+    /// Copies of the stickers on the hidden faces, floating away from the cube. See [CubeAppearance::hint_facelets].
+    hints: Option<Gm<PieceInstances, PieceMaterial>>,
+    /// Where the whole cube is placed in the scene.
+    transformation: Mat4,
+    appearance: CubeAppearance,
+    mask: StickerMask,
+    current_move: Option<MoveGroup>,
     move_start: f32,
-    move_queue: VecDeque<Move>,
+    move_queue: VecDeque<MoveGroup>,
     move_time: f32,
     move_slope: f32,
+    /// Multiplier for the animation speed, 2.0 plays moves twice as fast.
+    speed: f32,
+    playback: Playback,
+    /// Speed up of the current move from [Playback::pace], set when it starts and reset with the playback.
+    move_pace: f32,
+    paused: bool,
+    /// Whether the cube was paused at the last call to [Cube::animate].
+    was_paused: bool,
+    last_time: f32,
+    /// Moves applied since the cube was last solved, simplified as they're pushed.
+    /// Only kept once the cube has been solved, and cleared whenever it's solved again.
+    history: Vec<Move>,
+    /// Whether the cube was solved at some point since it was created or its state was last set.
+    solved_once: bool,
+    recorder: Option<Recorder>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CubeAnimationOptions {
     pub move_time: f32,
     pub move_smoothing: f32,
@@ -136,46 +254,217 @@ impl Default for CubeAnimationOptions {
     }
 }
 
+///
+/// The physical look of a cube.
+/// The default is a classic black cube with rounded stickers and no gaps between the pieces.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubeAppearance {
+    /// Radius of the rounded edges of each piece. Zero gives sharp edges.
+    pub bevel: f32,
+    /// How many segments are used to approximate each rounded edge.
+    pub bevel_segments: u32,
+    /// Distance between two neighbouring pieces.
+    pub gap: f32,
+    /// Color the whole face of a piece instead of drawing a sticker on it.
+    pub stickerless: bool,
+    /// Color of the plastic around the stickers and on the inner faces.
+    pub body_color: Srgba,
+    /// Distance between the sticker and the edge of the face, relative to the face size.
+    pub sticker_margin: f32,
+    /// Corner radius of the stickers, relative to the face size.
+    pub sticker_radius: f32,
+    /// How far the stickers stick out from the face. Negative values inset them instead.
+    pub sticker_depth: f32,
+    /// Sticker colors, in the order of the facelet letters: blue, yellow, red, white, green, orange.
+    pub colors: [Srgba; 6],
+    /// Color of the stickers a [StickerMask] ignores.
+    pub masked_color: Srgba,
+    /// Roughness and metallic factors of the stickers when the cube is lit.
+    pub sticker_roughness: f32,
+    pub sticker_metallic: f32,
+    /// Roughness and metallic factors of the plastic when the cube is lit.
+    pub body_roughness: f32,
+    pub body_metallic: f32,
+    ///
+    /// Show the stickers of the L, D and B faces floating behind them, like the hint facelets of
+    /// alg.cubing.net, so the whole state can be seen without turning the camera around.
+    ///
+    pub hint_facelets: bool,
+    /// How far the hint facelets float from their face.
+    pub hint_distance: f32,
+    pub hint_opacity: f32,
+}
+
+impl Default for CubeAppearance {
+    fn default() -> Self {
+        CubeAppearance {
+            bevel: 0.0,
+            bevel_segments: 4,
+            gap: 0.0,
+            stickerless: false,
+            body_color: Srgba::BLACK,
+            sticker_margin: 0.2,
+            sticker_radius: 0.1,
+            sticker_depth: 0.0,
+            colors: COLORS,
+            masked_color: MASKED_COLOR,
+            sticker_roughness: 0.6,
+            sticker_metallic: 0.0,
+            body_roughness: 0.35,
+            body_metallic: 0.0,
+            hint_facelets: false,
+            hint_distance: 1.5,
+            hint_opacity: 0.6,
+        }
+    }
+}
+
 impl Piece {
     pub fn vec(&self) -> Vec3 {
         Vec3::new(self.position.0 as f32, self.position.1 as f32, self.position.2 as f32)
     }
 
     fn cubelet(&self) -> usize {
-        let out = ((self.position.0 + 1) * 9 + (1 - self.position.1) * 3 + (self.position.2 + 1)).try_into().unwrap();
-        out
+        ((self.position.0 + 1) * 9 + (1 - self.position.1) * 3 + (self.position.2 + 1)) as usize
     }
 
     // Rotate the piece using a matrix
     // will only affect the internal position, mesh will not be changed
-    pub fn rotate(&mut self, mat: Mat3) -> Result<(Vec3, Vec3), String> {
+    pub fn rotate(&mut self, mat: Mat3) -> Result<(Vec3, Vec3), Error> {
         let prev = self.vec();
         let new = mat * prev;
         let mut rot = new - prev;
-        let unchanged_axes = (0..3).filter(|&i| rot[i] == 0.0).collect::<Vec<_>>();
-        if unchanged_axes.len() == 3 {
+        let unchanged_axes = (0..3).filter(|&i| rot[i] == 0.0).count();
+        if unchanged_axes == 3 {
             return Ok((prev, new));
-        } else if unchanged_axes.len() == 2 {
+        } else if unchanged_axes == 2 {
             rot += mat * rot;
         }
 
         if (0..3).filter(|&i| rot[i] == 0.0).count() != 1 {
-            return Err(format!("before: {:?}, after: {:?}, rot:{:?}", prev, new, rot));
+            return Err(Error::InvalidRotation { before: prev, after: new });
         }
-        let diff = (0..3).into_iter().filter(|&i| rot[i] != 0.0).collect::<Vec<_>>();
+        // Runs for every piece of every move, so without allocating
+        let mut diff = (0..3).filter(|&i| rot[i] != 0.0);
+        let (a, b) = (diff.next().unwrap(), diff.next().unwrap());
         let mut color_arr = [self.color.0, self.color.1, self.color.2];
-        color_arr.swap(diff[0], diff[1]);
+        color_arr.swap(a, b);
         self.color = (color_arr[0], color_arr[1], color_arr[2]);
         self.position = (new.x as i32, new.y as i32, new.z as i32);
         Ok((prev, new))
     }
 
     pub fn transform(&mut self, mat: Mat4) {
-        self.gm.set_transformation(mat * self.transform);
+        self.transformation = mat * self.transform;
+    }
+
+    fn sticker_colors(&self, appearance: &CubeAppearance) -> [Option<Srgba>; 6] {
+        std::array::from_fn(|i| self.faces[i].sticker(&appearance.colors).map(|color| styled_sticker(color, self.styles[i])))
+    }
+
+    /// The piece as an instance of the mesh all pieces share, which is centered at the origin.
+    fn instance(&self, appearance: &CubeAppearance) -> PieceInstance {
+        let origin = Vec3::new(self.origin.0 as f32, self.origin.1 as f32, self.origin.2 as f32);
+        PieceInstance {
+            transformation: self.transformation * Mat4::from_translation(origin),
+            colors: self.sticker_colors(appearance),
+        }
+    }
+
+    ///
+    /// The faces of the mesh that the moves so far turned to the L, D or B side of the cube,
+    /// with the face of the cube they are on.
+    ///
+    fn hidden_faces(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..6).filter_map(move |face| {
+            let normal = (self.transform * FACE_AXES[face].0.extend(0.0)).truncate();
+            HINT_FACES.iter().find(|&&hidden| FACE_AXES[hidden].0.dot(normal) > 0.5).map(|&hidden| (face, hidden))
+        })
+    }
+
+    /// The same piece with only the stickers that get hint facelets.
+    fn hint_instance(&self, appearance: &CubeAppearance) -> PieceInstance {
+        let mut instance = self.instance(appearance);
+        let mut hinted = [None; 6];
+        for (face, _) in self.hidden_faces() {
+            hinted[face] = instance.colors[face];
+        }
+        instance.colors = hinted;
+        instance
     }
 }
 
 impl Move {
+    pub const ALL: [Move; 18] = [
+        Move::L, Move::LP, Move::L2,
+        Move::R, Move::RP, Move::R2,
+        Move::U, Move::UP, Move::U2,
+        Move::D, Move::DP, Move::D2,
+        Move::F, Move::FP, Move::F2,
+        Move::B, Move::BP, Move::B2,
+    ];
+
+    /// Quarter turns clockwise this move makes: 1, 2 or 3 (a counter clockwise turn).
+    pub fn amount(&self) -> u8 {
+        match self {
+            Move::L | Move::R | Move::U | Move::D | Move::F | Move::B => 1,
+            Move::L2 | Move::R2 | Move::U2 | Move::D2 | Move::F2 | Move::B2 => 2,
+            Move::LP | Move::RP | Move::UP | Move::DP | Move::FP | Move::BP => 3,
+        }
+    }
+
+    /// The move that turns `face` clockwise `amount` quarter turns, if the amount isn't a full turn.
+    pub fn from_face(face: usize, amount: u8) -> Option<Move> {
+        let moves = match face {
+            0 => [Move::L, Move::L2, Move::LP],
+            1 => [Move::U, Move::U2, Move::UP],
+            2 => [Move::F, Move::F2, Move::FP],
+            3 => [Move::D, Move::D2, Move::DP],
+            4 => [Move::R, Move::R2, Move::RP],
+            5 => [Move::B, Move::B2, Move::BP],
+            _ => return None,
+        };
+        match amount % 4 {
+            0 => None,
+            a => Some(moves[a as usize - 1]),
+        }
+    }
+
+    pub fn inverse(&self) -> Move {
+        Move::from_face(self.face(), 4 - self.amount()).unwrap()
+    }
+
+    /// Parse a sequence in standard notation like `R U R' U2`.
+    /// Spaces are optional and parentheses are ignored.
+    pub fn parse_sequence(s: &str) -> Result<Vec<Move>, Error> {
+        let mut moves = vec![];
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            let face = match c {
+                'L' => 0,
+                'U' => 1,
+                'F' => 2,
+                'D' => 3,
+                'R' => 4,
+                'B' => 5,
+                c if c.is_whitespace() || c == '(' || c == ')' => continue,
+                c => return Err(Error::Parse(format!("Invalid move {}", c))),
+            };
+            let mut amount = 1;
+            if chars.peek() == Some(&'2') {
+                chars.next();
+                amount = 2;
+            }
+            if matches!(chars.peek(), Some('\'') | Some('’')) {
+                chars.next();
+                amount = 4 - amount;
+            }
+            moves.push(Move::from_face(face, amount).unwrap());
+        }
+        Ok(moves)
+    }
+
     pub fn face(&self) -> usize {
         match self {
             Move::L | Move::LP | Move::L2 => 0,
@@ -205,24 +494,213 @@ impl Move {
     }
 }
 
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let face = ['L', 'U', 'F', 'D', 'R', 'B'][self.face()];
+        match self.amount() {
+            1 => write!(f, "{}", face),
+            2 => write!(f, "{}2", face),
+            _ => write!(f, "{}'", face),
+        }
+    }
+}
+
+impl std::str::FromStr for Move {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Move::parse_sequence(s)?.as_slice() {
+            [mv] => Ok(*mv),
+            _ => Err(Error::Parse(format!("Expected a single move, got {}", s))),
+        }
+    }
+}
+
+/// Moves are saved in standard notation, like `"R'"`.
+impl Serialize for Move {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Move {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+///
+/// Moves that turn at the same time, like `R L'` done in one motion: a single move, or turns of
+/// two opposite faces. Those commute, so a group ends up the same as its moves one after the other.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveGroup {
+    first: Move,
+    second: Option<Move>,
+}
+
+impl MoveGroup {
+    /// Group moves to turn together. Fails unless there are one or two of them, on opposite faces.
+    pub fn new(moves: &[Move]) -> Result<MoveGroup, Error> {
+        match *moves {
+            [first] => Ok(first.into()),
+            [first, second] if OPPOSITE[first.face()] == second.face() => Ok(MoveGroup { first, second: Some(second) }),
+            _ => {
+                let notation = moves.iter().map(|mv| mv.to_string()).collect::<Vec<_>>().join(" ");
+                Err(Error::Validation(format!("Can't turn {} at the same time, only opposite faces can", notation)))
+            }
+        }
+    }
+
+    /// Parse a group in standard notation, like `R L'`.
+    pub fn parse(s: &str) -> Result<MoveGroup, Error> {
+        Self::new(&Move::parse_sequence(s)?)
+    }
+
+    pub fn moves(&self) -> impl Iterator<Item = Move> {
+        std::iter::once(self.first).chain(self.second)
+    }
+}
+
+impl From<Move> for MoveGroup {
+    fn from(mv: Move) -> Self {
+        MoveGroup { first: mv, second: None }
+    }
+}
+
+impl std::fmt::Display for MoveGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.second {
+            Some(second) => write!(f, "{} {}", self.first, second),
+            None => write!(f, "{}", self.first),
+        }
+    }
+}
+
+///
+/// How a [Cube] paces the moves in its queue, see [Cube::set_playback].
+///
+/// Scrambles, algorithms and replays want every move to take the same time so they can be
+/// followed. Turns typed on the keyboard want the cube to keep up with the fingers instead.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Playback {
+    /// Every move takes the move time of the cube.
+    #[default]
+    Fixed,
+    ///
+    /// Once more than `max_pending` moves are waiting, the move that is turning snaps to its end.
+    /// Moves also play faster the more of them are waiting when they start.
+    ///
+    Responsive { max_pending: usize },
+}
+
+impl Playback {
+    /// Whether the turning move should be finished right away with `pending` moves waiting.
+    pub fn snaps(&self, pending: usize) -> bool {
+        match *self {
+            Playback::Fixed => false,
+            Playback::Responsive { max_pending } => pending > max_pending,
+        }
+    }
+
+    /// How many times faster than the move time a move plays, with `pending` moves waiting behind it.
+    pub fn pace(&self, pending: usize) -> f32 {
+        match self {
+            Playback::Fixed => 1.0,
+            Playback::Responsive { .. } => 1.0 + pending as f32,
+        }
+    }
+}
+
+/// How every face of the pieces of a facelet string is drawn with `mask`, by cubelet and face.
+/// Pieces whose colors don't make sense are drawn as they are.
+fn sticker_styles(fstr: &str, mask: &StickerMask) -> [[StickerStyle; 6]; 27] {
+    let mut styles = [[StickerStyle::Regular; 6]; 27];
+    if mask == &StickerMask::FULL {
+        return styles;
+    }
+    if let Ok(homes) = home_facelets(fstr) {
+        for (facelet, &cubelet) in FACELETS.iter().enumerate() {
+            styles[cubelet][facelet / 9] = mask.style(homes[facelet]);
+        }
+    }
+    styles
+}
+
+/// Merge consecutive turns of the same face, dropping the ones that cancel out.
+pub fn simplify(moves: &[Move]) -> Vec<Move> {
+    let mut out: Vec<Move> = vec![];
+    for &mv in moves {
+        push_simplified(&mut out, mv);
+    }
+    out
+}
+
+/// Push `mv`, merged with the last move if it turns the same face.
+fn push_simplified(moves: &mut Vec<Move>, mv: Move) {
+    match moves.last() {
+        Some(last) if last.face() == mv.face() => {
+            let merged = Move::from_face(mv.face(), last.amount() + mv.amount());
+            moves.pop();
+            moves.extend(merged);
+        }
+        _ => moves.push(mv),
+    }
+}
+
+/// A random scramble of `length` moves that never turns the same face twice in a row.
+/// The same seed always gives the same scramble.
+pub fn scramble(seed: u64, length: usize) -> Vec<Move> {
+    // xorshift64*, plenty for scrambles and doesn't need a dependency
+    let mut state = seed.max(1);
+    let mut next = move || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        state.wrapping_mul(0x2545F4914F6CDD1D)
+    };
+    let mut moves: Vec<Move> = Vec::with_capacity(length);
+    while moves.len() < length {
+        let mv = Move::ALL[(next() >> 32) as usize % Move::ALL.len()];
+        if moves.last().map(|last| last.face()) != Some(mv.face()) {
+            moves.push(mv);
+        }
+    }
+    moves
+}
+
 impl Cube {
-    pub fn from_facelet_str(fstr: String, anim: CubeAnimationOptions, ctx: &three_d::Context) -> Result<Cube, String> {
+    /// Check that a facelet string describes a cube with the right amount of each color.
+    pub fn validate_facelet_str(fstr: &str) -> Result<(), Error> {
+        let mut counts = [0; 6];
+        for (i, c) in fstr.chars().enumerate() {
+            match Color::from_char(c) {
+                Some(color) if i < 54 => counts[color as usize] += 1,
+                Some(_) => return Err(Error::Parse("Invalid string length".to_string())),
+                None => return Err(Error::Parse(format!("Invalid char {}", c))),
+            }
+        }
+        if fstr.chars().count() != 54 {
+            return Err(Error::Parse("Invalid string length".to_string()));
+        }
+        if let Some(i) = counts.iter().position(|&n| n != 9) {
+            let color = Color::to_char(COLOR_ORDER[i]);
+            return Err(Error::Validation(format!("Expected 9 stickers of {}, found {}", color, counts[i])));
+        }
+        Ok(())
+    }
+
+    fn pieces_from_facelet_str(fstr: &str, mask: &StickerMask) -> Result<Vec<Piece>, Error> {
         let mut pieces = vec![];
-        for i in 0..27 {
-            let position = (i as i32 / 9 - 1, 2 - (i as i32 / 3) % 3 - 1, i as i32 % 3 - 1);
+        for i in 0..27i32 {
+            let position = (i / 9 - 1, 2 - (i / 3) % 3 - 1, i % 3 - 1);
             pieces.push((position, (Color::None, Color::None, Color::None)));
         }
-        for facelet in 0..54 {
-            let cubelet = FACELETS[facelet];
+        for (facelet, &cubelet) in FACELETS.iter().enumerate() {
             let color = match fstr.chars().nth(facelet) {
-                Some('B') => Color::Blue,
-                Some('Y') => Color::Yellow,
-                Some('R') => Color::Red,
-                Some('W') => Color::White,
-                Some('G') => Color::Green,
-                Some('O') => Color::Orange,
-                Some(c) => return Err(format!("Invalid char {}", c)),
-                None => return Err("Invalid string length".to_string())
+                Some(c) => Color::from_char(c).ok_or(Error::Parse(format!("Invalid char {}", c)))?,
+                None => return Err(Error::Parse("Invalid string length".to_string()))
             };
             match facelet / 9 {
                 0 | 4 => {
@@ -234,229 +712,451 @@ impl Cube {
                 2 | 5 => {
                     pieces[cubelet].1.2 = color;
                 },
-                _ => { return Err(format!("Error in facelet conversion: {} / 9 = {}", facelet, facelet/9)) }
+                _ => { return Err(Error::Parse(format!("Error in facelet conversion: {} / 9 = {}", facelet, facelet/9))) }
             }
         }
-        let pieces = pieces.into_iter().enumerate().map(|(_i, (position, color))| {
-            // let calculated_index = (position.0 + 1) * 9 + (1 - position.1) * 3 + (position.2 + 1);
-            // println!("{} -> {}: {:?}, {:?}", _i, calculated_index, position, color);
-            let mut mesh = CpuMesh::cube();
-            mesh.uvs = Some(cube_uvs());
-            mesh.transform(&Mat4::from_scale(0.5)).unwrap();
-            // mesh.transform(&Mat4::from_scale(0.98)).unwrap();
-            mesh.transform(
-                &Mat4::from_translation(Vec3::new(position.0 as f32, position.1 as f32, position.2 as f32))
-            ).unwrap();
-            let mut face_colors = vec![Srgba::BLACK; 36];
-            for i in 0..6 {
-                let face_color = match i {
-                    0 => if position.0 == -1 { color.0 } else { Color::None },
-                    1 => if position.1 ==  1 { color.1 } else { Color::None },
-                    2 => if position.2 ==  1 { color.2 } else { Color::None },
-                    3 => if position.1 == -1 { color.1 } else { Color::None },
-                    4 => if position.0 ==  1 { color.0 } else { Color::None },
-                    5 => if position.2 == -1 { color.2 } else { Color::None },
-                    _ => Color::None,
-                };
-                for j in 0..6 {
-                    // CpuMesh::cube() has a different face order
-                    face_colors[[5,0,3,1,4,2][i] * 6 + j] = face_color.into();
-                }
-            }
-            mesh.colors = Some(face_colors);
+        let styles = sticker_styles(fstr, mask);
+        let pieces = pieces.into_iter().enumerate().map(|(cubelet, (position, color))| {
+            let faces = [
+                if position.0 == -1 { color.0 } else { Color::None },
+                if position.1 ==  1 { color.1 } else { Color::None },
+                if position.2 ==  1 { color.2 } else { Color::None },
+                if position.1 == -1 { color.1 } else { Color::None },
+                if position.0 ==  1 { color.0 } else { Color::None },
+                if position.2 == -1 { color.2 } else { Color::None },
+            ];
             Piece {
                 position,
                 color,
+                origin: position,
+                faces,
+                styles: styles[cubelet],
                 transform: Mat4::identity(),
-                gm: Gm::new(Mesh::new(ctx, &mesh), PieceMaterial::default())
+                transformation: Mat4::identity(),
             }
         }).collect::<Vec<_>>();
-        Ok(Cube {
+        Ok(pieces)
+    }
+
+    pub fn from_facelet_str(fstr: String, anim: CubeAnimationOptions, ctx: &three_d::Context) -> Result<Cube, Error> {
+        let appearance = CubeAppearance::default();
+        let pieces = Self::pieces_from_facelet_str(&fstr, &StickerMask::FULL)?;
+        let (meshes, hints) = Self::meshes(&pieces, &appearance, Mat4::identity(), ctx);
+        let mut cube = Cube {
             pieces,
+            by_cubelet: std::array::from_fn(|cubelet| cubelet),
+            context: ctx.clone(),
+            meshes,
+            hints,
+            transformation: Mat4::identity(),
+            appearance,
+            mask: StickerMask::FULL,
             current_move: None,
-            current_face: None,
             move_start: 0.0,
             move_queue: VecDeque::new(),
             move_time: anim.move_time,
             move_slope: anim.move_smoothing,
-        })
+            speed: 1.0,
+            playback: Playback::Fixed,
+            move_pace: 1.0,
+            paused: false,
+            was_paused: false,
+            last_time: 0.0,
+            history: vec![],
+            solved_once: false,
+            recorder: None,
+        };
+        cube.solved_once = cube.is_solved();
+        Ok(cube)
+    }
+
+    /// Replace the state of the cube with the one in the facelet string.
+    /// Queued moves, the move history and the recording are dropped.
+    pub fn set_state(&mut self, fstr: &str) -> Result<(), Error> {
+        self.pieces = Self::pieces_from_facelet_str(fstr, &self.mask)?;
+        self.by_cubelet = std::array::from_fn(|cubelet| cubelet);
+        (self.meshes, self.hints) = Self::meshes(&self.pieces, &self.appearance, self.transformation, &self.context);
+        self.current_move = None;
+        self.move_queue.clear();
+        self.history.clear();
+        self.recorder = None;
+        self.solved_once = self.is_solved();
+        Ok(())
+    }
+
+    /// The current state as a facelet string, in the same format [Cube::from_facelet_str] takes.
+    /// A move that is still animating isn't applied yet.
+    pub fn facelet_str(&self) -> String {
+        FACELETS.iter().enumerate().map(|(facelet, &cubelet)| {
+            let piece = &self.pieces[self.by_cubelet[cubelet]];
+            let color = match facelet / 9 {
+                0 | 4 => piece.color.0,
+                1 | 3 => piece.color.1,
+                _ => piece.color.2,
+            };
+            color.to_char()
+        }).collect()
+    }
+
+    /// Whether every face has a single color.
+    pub fn is_solved(&self) -> bool {
+        let state = self.facelet_str();
+        state.as_bytes().chunks(9).all(|face| face.iter().all(|&c| c == face[0]))
+    }
+
+    /// Moves that bring the cube back to the solved state, counting the queued ones.
+    /// Only known if the cube was solved at some point since it was created or its state was
+    /// last set, as it's worked out by undoing the moves since then.
+    pub fn solution(&self) -> Option<Vec<Move>> {
+        if !self.solved_once {
+            return None;
+        }
+        let pending = self.current_move.iter().chain(self.move_queue.iter()).flat_map(MoveGroup::moves);
+        let done = self.history.iter().copied().chain(pending).collect::<Vec<_>>();
+        let undo = done.iter().rev().map(Move::inverse).collect::<Vec<_>>();
+        Some(simplify(&undo))
+    }
+
+    ///
+    /// Start recording the moves from the current state, with the time each one finishes.
+    /// A recording that was going on is dropped.
+    ///
+    pub fn record(&mut self) {
+        self.recorder = Some(Recorder::new(self.facelet_str(), self.last_time));
+    }
+
+    /// Whether moves are being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Stop recording and get the moves finished since [Cube::record].
+    pub fn stop_recording(&mut self) -> Option<Reconstruction> {
+        self.recorder.take().map(Recorder::finish)
+    }
+
+    /// Stop the animation where it is. Queued moves stay in the queue.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
     }
 
-    fn face_iter(&mut self, face: usize) -> impl Iterator<Item=&mut Piece> {
-        let face_cis: Vec<&usize> = FACELETS.iter().skip(face * 9).take(9).collect();
-        self.pieces.iter_mut()
-            .filter(move |p| face_cis.contains(&&p.cubelet()))
+    /// Set how fast moves are animated, relative to the move time in [CubeAnimationOptions].
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.01);
     }
 
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// How long a move takes to animate at the current speed, in milliseconds.
+    pub fn move_time(&self) -> f32 {
+        self.move_time / self.speed
+    }
+
+    ///
+    /// Set how queued moves are paced. [Playback::Responsive] is meant for moves made by hand.
+    /// A move that is turning goes on at the move time of the cube.
+    ///
+    pub fn set_playback(&mut self, playback: Playback) {
+        self.playback = playback;
+        self.move_pace = 1.0;
+    }
+
+    pub fn playback(&self) -> Playback {
+        self.playback
+    }
+
+    /// Whether there are no moves animating or waiting in the queue.
+    pub fn is_idle(&self) -> bool {
+        self.current_move.is_none() && self.move_queue.is_empty()
+    }
+
+    /// The current physical look of the cube.
+    pub fn appearance(&self) -> CubeAppearance {
+        self.appearance
+    }
+
+    /// Change the physical look of the cube. All piece meshes are rebuilt,
+    /// so it's fine to call this in the middle of a move but not every frame.
+    pub fn set_appearance(&mut self, appearance: CubeAppearance) {
+        (self.meshes, self.hints) = Self::meshes(&self.pieces, &appearance, self.transformation, &self.context);
+        self.appearance = appearance;
+    }
+
+    pub fn mask(&self) -> StickerMask {
+        self.mask
+    }
+
+    /// Gray out or dim stickers, see [StickerMask]. Ignored stickers are drawn with [CubeAppearance::masked_color].
+    pub fn set_mask(&mut self, mask: StickerMask) {
+        // Meshes keep the colors they were built with, so the styles follow from those
+        let built: String = FACELETS.iter().enumerate()
+            .map(|(facelet, &cubelet)| self.pieces[cubelet].faces[facelet / 9].to_char())
+            .collect();
+        let styles = sticker_styles(&built, &mask);
+        for (piece, styles) in self.pieces.iter_mut().zip(styles) {
+            piece.styles = styles;
+        }
+        self.mask = mask;
+        let instances: Vec<PieceInstance> = self.pieces.iter().map(|p| p.instance(&self.appearance)).collect();
+        self.meshes.geometry.set_colors(&instances);
+        self.update_hint_colors();
+    }
+
+    /// Show the stickers that moves turned to the hidden faces on the hint facelets.
+    fn update_hint_colors(&mut self) {
+        if let Some(hints) = &mut self.hints {
+            let instances: Vec<PieceInstance> = self.pieces.iter().map(|p| p.hint_instance(&self.appearance)).collect();
+            hints.geometry.set_colors(&instances);
+        }
+    }
+
+    ///
+    /// The stickers the hint facelets show, as the L, D and B faces of a facelet string with the
+    /// other faces left out. Stickers that move away from the hidden faces during a move keep their
+    /// hint until it's done. `None` if the appearance has no hint facelets.
+    ///
+    pub fn hint_facelets(&self) -> Option<String> {
+        self.hints.as_ref()?;
+        let mut facelets = ['-'; 27];
+        for piece in &self.pieces {
+            let colors = piece.hint_instance(&self.appearance).colors;
+            for (face, hidden) in piece.hidden_faces() {
+                // Faces inside the cube point the same ways as the outside ones
+                let Some(facelet) = FACE_CUBELETS[hidden].iter().position(|&cubelet| cubelet == piece.cubelet()) else { continue };
+                if colors[face].is_some() {
+                    facelets[HINT_FACES.iter().position(|&f| f == hidden).unwrap() * 9 + facelet] = piece.faces[face].to_char();
+                }
+            }
+        }
+        Some(facelets.iter().collect())
+    }
+
+    /// The pieces, and the hint facelets if the appearance has them.
+    fn meshes(
+        pieces: &[Piece],
+        appearance: &CubeAppearance,
+        transformation: Mat4,
+        ctx: &three_d::Context,
+    ) -> (Gm<PieceInstances, PieceMaterial>, Option<Gm<PieceInstances, PieceMaterial>>) {
+        let instances: Vec<PieceInstance> = pieces.iter().map(|p| p.instance(appearance)).collect();
+        let mut meshes = Gm::new(PieceInstances::new(ctx, &piece_mesh(appearance), &instances), PieceMaterial::from(appearance));
+        meshes.geometry.set_transformation(transformation);
+        let hints = appearance.hint_facelets.then(|| {
+            let instances: Vec<PieceInstance> = pieces.iter().map(|p| p.hint_instance(appearance)).collect();
+            let mut hints = Gm::new(PieceInstances::new(ctx, &hint_mesh(appearance), &instances), PieceMaterial::hints(appearance));
+            hints.geometry.set_transformation(transformation);
+            hints
+        });
+        (meshes, hints)
+    }
+
+    /// Place the whole cube in the scene, for scenes with more than one. Cubes are at the origin by default.
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+        self.meshes.geometry.set_transformation(transformation);
+        if let Some(hints) = &mut self.hints {
+            hints.geometry.set_transformation(transformation);
+        }
+    }
+
+    /// Move the meshes to where the pieces are, after they moved.
+    fn update_transformations(&mut self) {
+        let instances: Vec<PieceInstance> = self.pieces.iter().map(|p| p.instance(&self.appearance)).collect();
+        self.meshes.geometry.set_transformations(&instances);
+        if let Some(hints) = &mut self.hints {
+            hints.geometry.set_transformations(&instances);
+        }
+    }
+
+    /// Draw calls needed to render the cube, one for every object it's drawn with: the pieces and the hint facelets.
+    pub fn draw_calls(&self) -> usize {
+        self.into_iter().count()
+    }
+
+    /// The geometry of the pieces, for things like shadow maps that don't need the materials.
+    pub fn geometries(&self) -> impl Iterator<Item = &dyn Geometry> + Clone {
+        std::iter::once(&self.meshes as &dyn Geometry)
+    }
+
+    /// Indices of the pieces on a face.
     fn face(&self, face: usize) -> [usize; 9] {
-        let face_cis: Vec<&usize> = FACELETS.iter().skip(face * 9).take(9).collect();
-        self.pieces.iter().enumerate()
-            .filter_map(|(i, p)| if face_cis.contains(&&p.cubelet()) { Some(i) } else { None })
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap()
+        FACE_CUBELETS[face].map(|cubelet| self.by_cubelet[cubelet])
     }
 
-    fn rotate_face(&mut self, face: usize, mat: Mat3) {
-        self.face_iter(face).for_each(|piece| {
+    fn rotate_face(&mut self, face: usize, mat: Mat3) -> Result<(), Error> {
+        for i in self.face(face) {
+            let piece = &mut self.pieces[i];
             let prev = piece.position;
-            piece.rotate(mat).unwrap();
+            piece.rotate(mat)?;
             trace!("rot {}: {:?} -> {:?}", face, prev, piece.position);
-        });
+            self.by_cubelet[piece.cubelet()] = i;
+        }
+        Ok(())
     }
 
-    fn apply_move(&mut self, mv: Move) {
+    fn apply_move(&mut self, mv: Move) -> Result<(), Error> {
         match mv {
             Move::L => self.rotate_face(0, ROT_XY_CW),
             Move::LP => self.rotate_face(0, ROT_XY_CCW),
             Move::L2 => {
-                self.rotate_face(0, ROT_XY_CW);
-                self.rotate_face(0, ROT_XY_CW);
+                self.rotate_face(0, ROT_XY_CW)?;
+                self.rotate_face(0, ROT_XY_CW)
             },
             Move::R => self.rotate_face(4, ROT_XY_CCW),
             Move::RP => self.rotate_face(4, ROT_XY_CW),
             Move::R2 => {
-                self.rotate_face(4, ROT_XY_CCW);
-                self.rotate_face(4, ROT_XY_CCW);
+                self.rotate_face(4, ROT_XY_CCW)?;
+                self.rotate_face(4, ROT_XY_CCW)
             },
             Move::U => self.rotate_face(1, ROT_XZ_CW),
             Move::UP => self.rotate_face(1, ROT_XZ_CCW),
             Move::U2 => {
-                self.rotate_face(1, ROT_XZ_CW);
-                self.rotate_face(1, ROT_XZ_CW);
+                self.rotate_face(1, ROT_XZ_CW)?;
+                self.rotate_face(1, ROT_XZ_CW)
             },
             Move::D => self.rotate_face(3, ROT_XZ_CCW),
             Move::DP => self.rotate_face(3, ROT_XZ_CW),
             Move::D2 => {
-                self.rotate_face(3, ROT_XZ_CCW);
-                self.rotate_face(3, ROT_XZ_CCW);
+                self.rotate_face(3, ROT_XZ_CCW)?;
+                self.rotate_face(3, ROT_XZ_CCW)
             },
             Move::F => self.rotate_face(2, ROT_YZ_CW),
             Move::FP => self.rotate_face(2, ROT_YZ_CCW),
             Move::F2 => {
-                self.rotate_face(2, ROT_YZ_CW);
-                self.rotate_face(2, ROT_YZ_CW);
+                self.rotate_face(2, ROT_YZ_CW)?;
+                self.rotate_face(2, ROT_YZ_CW)
             },
             Move::B => self.rotate_face(5, ROT_YZ_CCW),
             Move::BP => self.rotate_face(5, ROT_YZ_CW),
             Move::B2 => {
-                self.rotate_face(5, ROT_YZ_CCW);
-                self.rotate_face(5, ROT_YZ_CCW);
+                self.rotate_face(5, ROT_YZ_CCW)?;
+                self.rotate_face(5, ROT_YZ_CCW)
             },
         }
     }
 
     pub fn queue(&mut self, mv: impl Iterator<Item = Move>) {
-        self.move_queue.extend(mv);
-    }
-
-    pub fn animate(&mut self, time: f32) {
-        if let (Some(mv), Some(cface)) = (self.current_move, self.current_face) {
-            let elapsed = time - self.move_start;
-            if elapsed > self.move_time {
-                for ci in cface {
-                    let piece = &mut self.pieces[ci];
-                    piece.transform = mv.transform(1.0) * piece.transform;
-                    piece.transform(Mat4::identity());
-                }
-                self.apply_move(mv);
-                trace!("Applied move {:?}", mv);
-                self.current_move = None;
-            } else {
-                let x = crate::animation::ease(elapsed / self.move_time, self.move_slope);
-                for ci in cface {
-                    let piece = &mut self.pieces[ci];
-                    piece.transform(mv.transform(x));
-                }
-            }
-        } else if let Some(nmv) = self.move_queue.pop_front() {
-            self.current_move = Some(nmv);
-            self.current_face = Some(self.face(nmv.face()));
-            self.move_start = time;
-            trace!("New move {:?} will affect {:?}", nmv, self.face_iter(nmv.face()).map(|c| c.cubelet()).collect::<Vec<_>>());
-        }
-    }
-
-    pub fn solved(anim: CubeAnimationOptions, ctx: &three_d::Context) -> Cube {
-        Self::from_facelet_str("BBBBBBBBBYYYYYYYYYRRRRRRRRRWWWWWWWWWGGGGGGGGGOOOOOOOOO".to_string(), anim, ctx).unwrap()
-    }
-}
-
-impl Geometry for Piece {
-    fn aabb(&self) -> three_d::prelude::AxisAlignedBoundingBox {
-        self.gm.aabb()
+        self.move_queue.extend(mv.map(MoveGroup::from));
     }
 
-    fn animate(&mut self, _time: f32) {
-        self.gm.animate(_time)
+    /// Queue moves that turn at the same time, see [MoveGroup].
+    pub fn queue_group(&mut self, group: MoveGroup) {
+        self.move_queue.push_back(group);
     }
 
-    fn draw(
-            &self,
-            camera: &three_d::Camera,
-            program: &three_d::Program,
-            render_states: three_d::RenderStates,
-            attributes: three_d::FragmentAttributes,
-        ) {
-        self.gm.draw(camera, program, render_states, attributes)
-    }
-
-    fn id(&self, required_attributes: three_d::FragmentAttributes) -> u16 {
-        self.gm.id(required_attributes)
-    }
-
-    fn render_with_effect(
-            &self,
-            material: &dyn three_d::Effect,
-            camera: &three_d::Camera,
-            lights: &[&dyn three_d::Light],
-            color_texture: Option<three_d::ColorTexture>,
-            depth_texture: Option<three_d::DepthTexture>,
-        ) {
-        self.gm.render_with_effect(material, camera, lights, color_texture, depth_texture)
-    }
-
-    fn render_with_material(&self, material: &dyn three_d::Material, camera: &three_d::Camera, lights: &[&dyn three_d::Light]) {
-        self.gm.render_with_material(material, camera, lights)
+    ///
+    /// Apply moves right away without animating them, like for playing back a long scramble.
+    /// A move that is animating is finished first, queued moves still play afterwards.
+    ///
+    pub fn apply_moves(&mut self, moves: impl IntoIterator<Item = Move>) -> Result<(), Error> {
+        if let Some(group) = self.current_move.take() {
+            self.finish_group(group, self.last_time)?;
+        }
+        for mv in moves {
+            self.finish_move(mv, self.last_time)?;
+        }
+        self.update_transformations();
+        self.update_hint_colors();
+        Ok(())
     }
 
-    fn vertex_shader_source(&self, required_attributes: three_d::FragmentAttributes) -> String {
-        self.gm.vertex_shader_source(required_attributes)
+    /// Apply all the moves of a group together, see [Cube::finish_move].
+    fn finish_group(&mut self, group: MoveGroup, time: f32) -> Result<(), Error> {
+        // The moves turn different pieces, so their order doesn't matter
+        for mv in group.moves() {
+            self.finish_move(mv, time)?;
+        }
+        Ok(())
     }
-}
 
-impl Object for Piece {
-    fn material_type(&self) -> three_d::MaterialType {
-        self.gm.material_type()
+    /// Turn the pieces of a move all the way and apply it to the state. The meshes aren't updated.
+    fn finish_move(&mut self, mv: Move, time: f32) -> Result<(), Error> {
+        for i in self.face(mv.face()) {
+            let piece = &mut self.pieces[i];
+            piece.transform = mv.transform(1.0) * piece.transform;
+            piece.transform(Mat4::identity());
+        }
+        self.apply_move(mv)?;
+        trace!("Applied move {:?}", mv);
+        if self.is_solved() {
+            self.history.clear();
+            self.solved_once = true;
+        } else if self.solved_once {
+            push_simplified(&mut self.history, mv);
+        }
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(mv, time);
+        }
+        Ok(())
     }
 
-    fn render(&self, camera: &three_d::Camera, lights: &[&dyn three_d::Light]) {
-        self.gm.render(camera, lights)
+    /// Advance the animation to `time`, in milliseconds.
+    /// Returns the moves that finished during this call, if any.
+    pub fn animate(&mut self, time: f32) -> Result<Option<MoveGroup>, Error> {
+        let last_time = std::mem::replace(&mut self.last_time, time);
+        let was_paused = std::mem::replace(&mut self.was_paused, self.paused);
+        if self.paused || was_paused {
+            // Keep the current move frozen at the same progress, frames may be far apart while paused
+            self.move_start += time - last_time;
+        }
+        if self.paused {
+            return Ok(None);
+        }
+        let move_time = self.move_time / self.speed / self.move_pace;
+        if let Some(group) = self.current_move {
+            let elapsed = time - self.move_start;
+            if elapsed > move_time || self.playback.snaps(self.move_queue.len()) {
+                self.current_move = None;
+                self.finish_group(group, time)?;
+                self.update_transformations();
+                self.update_hint_colors();
+                return Ok(Some(group));
+            } else {
+                // Every move of the group turns its own face through its own angle
+                let x = crate::animation::ease(elapsed / move_time, self.move_slope);
+                for mv in group.moves() {
+                    for i in self.face(mv.face()) {
+                        self.pieces[i].transform(mv.transform(x));
+                    }
+                }
+                self.update_transformations();
+            }
+        } else if let Some(group) = self.move_queue.pop_front() {
+            self.current_move = Some(group);
+            self.move_start = time;
+            self.move_pace = self.playback.pace(self.move_queue.len());
+            for mv in group.moves() {
+                trace!("New move {:?} will affect {:?}", mv, FACE_CUBELETS[mv.face()]);
+            }
+        }
+        Ok(None)
     }
-}
 
-impl<'a> IntoIterator for &'a Piece {
-    type Item = &'a dyn Object;
-    type IntoIter = std::vec::IntoIter<&'a dyn Object>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        vec![self as &dyn Object].into_iter()
+    pub fn solved(anim: CubeAnimationOptions, ctx: &three_d::Context) -> Cube {
+        Self::from_facelet_str(SOLVED.to_string(), anim, ctx).unwrap()
     }
 }
 
 impl<'a> IntoIterator for &'a Cube {
     type Item = &'a dyn Object;
-    type IntoIter = std::vec::IntoIter<&'a dyn Object>;
+    type IntoIter = std::iter::Chain<std::iter::Once<&'a dyn Object>, std::option::IntoIter<&'a dyn Object>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.pieces.iter()
-            .map(|p| p as &dyn Object)
-            .collect::<Vec<_>>()
-            .into_iter()
+        std::iter::once(&self.meshes as &dyn Object).chain(self.hints.as_ref().map(|h| h as &dyn Object))
     }
 }
 
-impl <'a> IntoIterator for Move {
+impl IntoIterator for Move {
     type Item = Move;
     type IntoIter = std::vec::IntoIter<Move>;
 
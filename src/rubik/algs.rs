@@ -0,0 +1,403 @@
+use std::sync::OnceLock;
+
+use three_d::{InnerSpace, Mat3, Vec3};
+
+use crate::Error;
+use super::{facelet_at, home_facelets, simplify, Cube, Move, StickerMask, StickerStyle, FACELETS, ROT_XY_CCW, ROT_XY_CW, ROT_XZ_CCW, ROT_XZ_CW, ROT_YZ_CCW, ROT_YZ_CW, SOLVED};
+
+mod cases;
+
+/// Outward direction of every face, in L, U, F, D, R, B order.
+pub(super) const NORMALS: [(i32, i32, i32); 6] = [(-1, 0, 0), (0, 1, 0), (0, 0, 1), (0, -1, 0), (1, 0, 0), (0, 0, -1)];
+
+/// Quarter turn clockwise of every face, the same matrices [Cube] turns its pieces with.
+const CLOCKWISE: [Mat3; 6] = [ROT_XY_CW, ROT_XZ_CW, ROT_YZ_CW, ROT_XZ_CCW, ROT_XY_CCW, ROT_YZ_CCW];
+
+pub(super) const OPPOSITE: [usize; 6] = [4, 3, 5, 1, 0, 2];
+
+const U: usize = 1;
+const D: usize = 3;
+const F: usize = 2;
+const R: usize = 4;
+
+/// A group of cases that are solved with the same method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AlgSet {
+    /// Inserting a corner and edge pair into its slot, once the cross is solved.
+    F2l,
+    /// Orienting the last layer.
+    Oll,
+    /// Permuting the oriented last layer.
+    Pll,
+    /// Solving the corners of the last layer when its edges are oriented, keeping the order of the edges.
+    Coll,
+    /// Solving the whole last layer when its edges are oriented. Only the T and U subsets have cases so far.
+    Zbll,
+}
+
+impl AlgSet {
+    pub const ALL: [AlgSet; 5] = [AlgSet::F2l, AlgSet::Oll, AlgSet::Pll, AlgSet::Coll, AlgSet::Zbll];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlgSet::F2l => "F2L",
+            AlgSet::Oll => "OLL",
+            AlgSet::Pll => "PLL",
+            AlgSet::Coll => "COLL",
+            AlgSet::Zbll => "ZBLL",
+        }
+    }
+}
+
+///
+/// One case of an [AlgSet] and the algorithms that solve it.
+///
+/// F2L cases insert the pair into the front right slot, last layer cases are solved from the
+/// orientation they are recognized in, see [recognize].
+///
+#[derive(Debug)]
+pub struct AlgCase {
+    pub set: AlgSet,
+    /// Name of the case within its set, like `27` for the Sune OLL or `Ja` for a PLL.
+    pub name: &'static str,
+    /// The group the case is usually learned in, like the shape of an OLL.
+    pub group: &'static str,
+    /// Algorithms solving the case, the first one is the most common.
+    pub algs: &'static [&'static str],
+}
+
+impl AlgCase {
+    /// The main algorithm as face turns, see [parse_alg].
+    pub fn moves(&self) -> Vec<Move> {
+        parse_alg(self.algs[0]).expect("algorithms in the database are valid")
+    }
+
+    /// Moves that turn a solved cube into this case.
+    pub fn setup(&self) -> Vec<Move> {
+        self.moves().iter().rev().map(Move::inverse).collect()
+    }
+
+    /// The case set up on a solved cube, as a facelet string.
+    pub fn facelets(&self) -> String {
+        apply_moves(SOLVED, &self.setup()).unwrap()
+    }
+}
+
+impl std::fmt::Display for AlgCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.set.name(), self.name)
+    }
+}
+
+/// Every case of a set, in the order they're usually numbered in.
+pub fn cases(set: AlgSet) -> &'static [AlgCase] {
+    match set {
+        AlgSet::F2l => &cases::F2L,
+        AlgSet::Oll => &cases::OLL,
+        AlgSet::Pll => &cases::PLL,
+        AlgSet::Coll => &cases::COLL,
+        AlgSet::Zbll => &cases::ZBLL,
+    }
+}
+
+/// Look up a case by set and name.
+pub fn find_case(set: AlgSet, name: &str) -> Option<&'static AlgCase> {
+    cases(set).iter().find(|case| case.name.eq_ignore_ascii_case(name))
+}
+
+///
+/// Parse an algorithm into face turns.
+///
+/// On top of what [Move::parse_sequence] reads, this takes wide turns (`r` or `Rw`), slices (`M`, `E`, `S`)
+/// and rotations (`x`, `y`, `z`), and turn counts like `R3`. Since a [Cube] keeps its centers in place,
+/// these are rewritten into turns of the outer faces: `r` becomes `L` and every later turn is
+/// remapped as if the cube had been rotated with `x`. The resulting state is the same, up to how the cube is held.
+///
+pub fn parse_alg(s: &str) -> Result<Vec<Move>, Error> {
+    // Face that each face of the algorithm is at on the cube
+    let mut orientation = [0, 1, 2, 3, 4, 5];
+    let mut moves = vec![];
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let face_index = |c: char| "LUFDRB".find(c.to_ascii_uppercase());
+        let mut token = match c {
+            'L' | 'U' | 'F' | 'D' | 'R' | 'B' if chars.peek() == Some(&'w') => {
+                chars.next();
+                Token::Wide(face_index(c).unwrap())
+            }
+            'L' | 'U' | 'F' | 'D' | 'R' | 'B' => Token::Face(face_index(c).unwrap()),
+            'l' | 'u' | 'f' | 'd' | 'r' | 'b' => Token::Wide(face_index(c).unwrap()),
+            'M' => Token::Slice(0),
+            'E' => Token::Slice(3),
+            'S' => Token::Slice(2),
+            'x' => Token::Rotation(4),
+            'y' => Token::Rotation(1),
+            'z' => Token::Rotation(2),
+            c if c.is_whitespace() || "()[]".contains(c) => continue,
+            c => return Err(Error::Parse(format!("Invalid move {}", c))),
+        };
+        let mut amount = 1;
+        if let Some(n) = chars.peek().and_then(|c| c.to_digit(10)) {
+            chars.next();
+            amount = n as u8 % 4;
+        }
+        if matches!(chars.peek(), Some('\'') | Some('’')) {
+            chars.next();
+            amount = (4 - amount) % 4;
+        }
+        let (Token::Face(face) | Token::Wide(face) | Token::Slice(face) | Token::Rotation(face)) = &mut token;
+        *face = orientation[*face];
+        let mut turn = |face: usize, amount: u8| moves.extend(Move::from_face(face, amount));
+        match token {
+            Token::Face(face) => turn(face, amount),
+            Token::Wide(face) => {
+                turn(OPPOSITE[face], amount);
+                rotate(&mut orientation, face, amount);
+            }
+            Token::Slice(face) => {
+                turn(OPPOSITE[face], amount);
+                turn(face, 4 - amount);
+                rotate(&mut orientation, face, amount);
+            }
+            Token::Rotation(face) => rotate(&mut orientation, face, amount),
+        }
+    }
+    Ok(simplify(&moves))
+}
+
+/// A turn of the algorithm notation, on the face of the cube it lands on.
+/// Slices turn in the direction of the given face, and rotations like a turn of it.
+enum Token {
+    Face(usize),
+    Wide(usize),
+    Slice(usize),
+    Rotation(usize),
+}
+
+/// Update where the faces of an algorithm are after rotating the cube like a turn of `face` (on the cube).
+fn rotate(orientation: &mut [usize; 6], face: usize, amount: u8) {
+    // The cube turns under the algorithm's faces, so they move the other way around it
+    for _ in 0..(4 - amount) % 4 {
+        for on_cube in orientation.iter_mut() {
+            let normal = CLOCKWISE[face] * normal(*on_cube);
+            *on_cube = NORMALS.iter()
+                .position(|&n| normal == Vec3::new(n.0 as f32, n.1 as f32, n.2 as f32))
+                .unwrap();
+        }
+    }
+}
+
+pub(super) fn normal(face: usize) -> Vec3 {
+    let (x, y, z) = NORMALS[face];
+    Vec3::new(x as f32, y as f32, z as f32)
+}
+
+/// Where every facelet goes when the layer of `face` turns a quarter clockwise, or the whole cube if `whole_cube`.
+fn quarter_turn(face: usize, whole_cube: bool) -> [usize; 54] {
+    let mut to = [0; 54];
+    for (facelet, &cubelet) in FACELETS.iter().enumerate() {
+        let cubelet = cubelet as i32;
+        let position = Vec3::new((cubelet / 9 - 1) as f32, (1 - cubelet / 3 % 3) as f32, (cubelet % 3 - 1) as f32);
+        let point = position + normal(facelet / 9) * 0.5;
+        to[facelet] = if whole_cube || normal(face).dot(position) > 0.5 {
+            facelet_at(CLOCKWISE[face] * point).unwrap()
+        } else {
+            facelet
+        };
+    }
+    to
+}
+
+/// Quarter turns of each face, then a whole cube rotation like a turn of U.
+fn permutations() -> &'static [[usize; 54]; 7] {
+    static PERMUTATIONS: OnceLock<[[usize; 54]; 7]> = OnceLock::new();
+    PERMUTATIONS.get_or_init(|| std::array::from_fn(|i| if i < 6 { quarter_turn(i, false) } else { quarter_turn(U, true) }))
+}
+
+fn permute<T: Copy>(state: &mut [T; 54], permutation: &[usize; 54], times: u8) {
+    for _ in 0..times {
+        let before = *state;
+        for (facelet, &to) in permutation.iter().enumerate() {
+            state[to] = before[facelet];
+        }
+    }
+}
+
+fn turn<T: Copy>(state: &mut [T; 54], moves: &[Move]) {
+    for mv in moves {
+        permute(state, &permutations()[mv.face()], mv.amount());
+    }
+}
+
+/// Apply moves to a facelet string, without a [Cube] or anything to draw it with.
+pub fn apply_moves(facelets: &str, moves: &[Move]) -> Result<String, Error> {
+    Cube::validate_facelet_str(facelets)?;
+    let mut state: [char; 54] = std::array::from_fn(|i| facelets.chars().nth(i).unwrap());
+    turn(&mut state, moves);
+    Ok(state.iter().collect())
+}
+
+/// A case found by [recognize] and how to hold the cube to solve it with the case's algorithms.
+#[derive(Clone, Copy, Debug)]
+pub struct CaseMatch {
+    pub case: &'static AlgCase,
+    /// Quarter turns of `y` to make before the algorithm, to bring an F2L slot to the front right.
+    /// Always zero for last layer cases, where a turn of U does the same.
+    pub y: u8,
+    /// Quarter turns of U to make before the algorithm.
+    pub auf: u8,
+}
+
+/// A facelet string with every sticker replaced by the face of the center with its color.
+type Faces = [u8; 54];
+
+/// What's compared when recognizing a case of each set.
+struct Pattern {
+    case: &'static AlgCase,
+    key: Faces,
+}
+
+/// Facelets of the last layer: the U face and the top row of the faces around it.
+fn is_last_layer(facelet: usize) -> bool {
+    facelet / 9 == U || (facelet / 9 != D && facelet % 9 < 3)
+}
+
+/// Stickers that don't matter for a set are blanked out of the key.
+fn key(set: AlgSet, faces: &Faces) -> Faces {
+    const BLANK: u8 = 6;
+    let mut key = *faces;
+    match set {
+        AlgSet::F2l => {
+            // Only the front right corner and edge, wherever they are
+            let mut on_piece = [0u8; 27];
+            for (facelet, &cubelet) in FACELETS.iter().enumerate() {
+                on_piece[cubelet] |= 1 << faces[facelet];
+            }
+            for (facelet, &cubelet) in FACELETS.iter().enumerate() {
+                let colors = on_piece[cubelet];
+                if colors != 1 << F | 1 << R | 1 << D && colors != 1 << F | 1 << R {
+                    key[facelet] = BLANK;
+                }
+            }
+        }
+        AlgSet::Oll => {
+            for (facelet, face) in key.iter_mut().enumerate() {
+                *face = if !is_last_layer(facelet) { BLANK } else { (*face == U as u8) as u8 };
+            }
+        }
+        AlgSet::Coll => {
+            // Only the corners, the edges can be in any order as the algorithms keep it
+            for (facelet, face) in key.iter_mut().enumerate() {
+                if !is_last_layer(facelet) || facelet % 9 % 2 == 1 {
+                    *face = BLANK;
+                }
+            }
+        }
+        AlgSet::Pll | AlgSet::Zbll => {
+            for (facelet, face) in key.iter_mut().enumerate() {
+                if !is_last_layer(facelet) {
+                    *face = BLANK;
+                }
+            }
+        }
+    }
+    key
+}
+
+fn to_faces(facelets: &[u8]) -> Faces {
+    let centers: [u8; 6] = std::array::from_fn(|face| facelets[face * 9 + 4]);
+    std::array::from_fn(|i| centers.iter().position(|&c| c == facelets[i]).unwrap() as u8)
+}
+
+fn patterns() -> &'static [Pattern] {
+    static PATTERNS: OnceLock<Vec<Pattern>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let solved = to_faces(SOLVED.as_bytes());
+        let mut patterns = vec![];
+        for set in AlgSet::ALL {
+            for case in cases(set) {
+                // Last layer cases may leave a turn of U to finish, which changes the colors they start from
+                let aufs = if matches!(set, AlgSet::F2l | AlgSet::Oll) { 1 } else { 4 };
+                for auf in 0..aufs {
+                    let mut faces = solved;
+                    permute(&mut faces, &permutations()[U], auf);
+                    turn(&mut faces, &case.setup());
+                    patterns.push(Pattern { case, key: key(set, &faces) });
+                }
+            }
+        }
+        patterns
+    })
+}
+
+///
+/// Whether all stickers that have to be on their own face for `solved` are, leaving out the ignored ones.
+/// A sticker has to be there if it's where `solved` looks, or if it belongs there.
+///
+fn is_solved(faces: &Faces, homes: &[usize; 54], ignored: &[bool; 54], solved: impl Fn(usize) -> bool) -> bool {
+    faces.iter().enumerate().all(|(facelet, &face)| {
+        !(solved(facelet) || solved(homes[facelet])) || ignored[facelet] || face as usize == facelet / 9
+    })
+}
+
+///
+/// Find the F2L and last layer cases the cube is in.
+///
+/// F2L cases are found for every slot whose pair is in the slot or the U layer, as long as the cross
+/// on D is solved. The other slots don't matter. Last layer cases are only found once the first
+/// two layers are solved, in every set that applies: an OLL with the edges already oriented is
+/// also a COLL and a ZBLL case. Cases are recognized up to turns of U and rotations around `y`,
+/// which the match tells how to undo.
+///
+pub fn recognize(facelets: &str) -> Result<Vec<CaseMatch>, Error> {
+    recognize_masked(facelets, &StickerMask::FULL)
+}
+
+///
+/// Like [recognize], with the stickers `mask` ignores left out: they may be anywhere, so a case
+/// matches whatever its algorithm does to them. With [MaskPreset::Oll](super::MaskPreset::Oll)
+/// only the orientation of the last layer counts, for instance.
+///
+pub fn recognize_masked(facelets: &str, mask: &StickerMask) -> Result<Vec<CaseMatch>, Error> {
+    let homes = home_facelets(facelets)?;
+    let ignored = homes.map(|home| mask.style(home) == StickerStyle::Ignored);
+    let faces = to_faces(facelets.as_bytes());
+    let mut found = vec![];
+    let cross = |facelet: usize| facelet / 9 == D && facelet % 9 % 2 == 1 || facelet / 9 != U && facelet % 9 == 7;
+    if !is_solved(&faces, &homes, &ignored, cross) {
+        return Ok(found);
+    }
+
+    let first_two_layers = |facelet: usize| !is_last_layer(facelet);
+    let sets: &[AlgSet] = if !is_solved(&faces, &homes, &ignored, first_two_layers) {
+        &[AlgSet::F2l]
+    } else if is_solved(&faces, &homes, &ignored, |facelet| facelet / 9 == U) {
+        &[AlgSet::Pll]
+    } else if is_solved(&faces, &homes, &ignored, |facelet| facelet / 9 == U && facelet % 9 % 2 == 1) {
+        &[AlgSet::Oll, AlgSet::Coll, AlgSet::Zbll]
+    } else {
+        &[AlgSet::Oll]
+    };
+    for &set in sets {
+        // Last layer cases don't need rotations, a turn of U does the same
+        let rotations = if set == AlgSet::F2l { 4 } else { 1 };
+        for y in 0..rotations {
+            let (mut rotated, mut rotated_ignored) = (faces, ignored);
+            permute(&mut rotated, &permutations()[6], y);
+            permute(&mut rotated_ignored, &permutations()[6], y);
+            let rotated = to_faces(&rotated);
+            let matched = (0..4).find_map(|auf| {
+                let (mut turned, mut turned_ignored) = (rotated, rotated_ignored);
+                permute(&mut turned, &permutations()[U], auf);
+                permute(&mut turned_ignored, &permutations()[U], auf);
+                let key = key(set, &turned);
+                let matches = |pattern: &Pattern| (0..54).all(|i| turned_ignored[i] || pattern.key[i] == key[i]);
+                patterns().iter()
+                    .find(|pattern| pattern.case.set == set && matches(pattern))
+                    .map(|pattern| CaseMatch { case: pattern.case, y, auf })
+            });
+            found.extend(matched);
+        }
+    }
+    Ok(found)
+}
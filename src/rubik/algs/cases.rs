@@ -0,0 +1,339 @@
+use super::{AlgCase, AlgSet};
+
+const fn f2l(name: &'static str, group: &'static str, algs: &'static [&'static str]) -> AlgCase {
+    AlgCase { set: AlgSet::F2l, name, group, algs }
+}
+
+const fn oll(name: &'static str, group: &'static str, algs: &'static [&'static str]) -> AlgCase {
+    AlgCase { set: AlgSet::Oll, name, group, algs }
+}
+
+const fn pll(name: &'static str, group: &'static str, algs: &'static [&'static str]) -> AlgCase {
+    AlgCase { set: AlgSet::Pll, name, group, algs }
+}
+
+const fn coll(name: &'static str, group: &'static str, algs: &'static [&'static str]) -> AlgCase {
+    AlgCase { set: AlgSet::Coll, name, group, algs }
+}
+
+const fn zbll(name: &'static str, group: &'static str, algs: &'static [&'static str]) -> AlgCase {
+    AlgCase { set: AlgSet::Zbll, name, group, algs }
+}
+
+pub static F2L: [AlgCase; 41] = [
+    f2l("1", "Basic inserts", &["U R U' R'", "R' F R F'"]),
+    f2l("2", "Basic inserts", &["y' U' R' U R", "U' F' U F"]),
+    f2l("3", "Basic inserts", &["y' R' U' R", "F' U' F"]),
+    f2l("4", "Basic inserts", &["R U R'", "y' f R f'"]),
+    f2l("5", "Corner and edge in U", &["U' R U R' U2 R U' R'", "U' R U R' U' R U2 R'"]),
+    f2l("6", "Corner and edge in U", &["y' U R' U' R U2 R' U R", "U' r U' R' U R U r'"]),
+    f2l("7", "Corner and edge in U", &["U' R U2 R' U2 R U' R'", "U' R U2 R' U' R U2 R'"]),
+    f2l("8", "Corner and edge in U", &["y' U R' U2 R U2 R' U R", "d R' U2 R U2 R' U R"]),
+    f2l("9", "Corner and edge in U", &["y' U R' U' R U' R' U' R", "U' R U' R' U F' U' F"]),
+    f2l("10", "Corner and edge in U", &["U' R U R' U R U R'", "U2 R U' R' U' R U R'"]),
+    f2l("11", "Corner and edge in U", &["U' R U2 R' U F' U' F", "U' R U2 R' d R' U' R"]),
+    f2l("12", "Corner and edge in U", &["R U' R' U R U' R' U2 R U' R'", "R' U2 R2 U R2 U R"]),
+    f2l("13", "Corner and edge in U", &["y' U R' U R U' R' U' R", "U F' U F U' F' U' F"]),
+    f2l("14", "Corner and edge in U", &["U' R U' R' U R U R'", "U2 R2 U R' U R U2 R2"]),
+    f2l("15", "Corner and edge in U", &["R' D' R U' R' D R U R U' R'", "y' R' U R U2 y R U R'"]),
+    f2l("16", "Corner and edge in U", &["R U' R' U2 F' U' F", "R U' R' U2 y' R' U' R"]),
+    f2l("17", "Corner and edge in U", &["R U2 R' U' R U R'", "U2 R2 U2 F R F' U2 R2"]),
+    f2l("18", "Corner and edge in U", &["y' R' U2 R U R' U' R", "F' U2 F U F' U' F"]),
+    f2l("19", "Corner and edge in U", &["U R U2 R' U R U' R'", "U R U2 R2 F R F'"]),
+    f2l("20", "Corner and edge in U", &["y' U' R' U2 R U' R' U R", "U' F' U2 F U' F' U F"]),
+    f2l("21", "Corner and edge in U", &["U2 R U R' U R U' R'", "R U' R' U2 R U R'"]),
+    f2l("22", "Corner and edge in U", &["r U' r' U2 r U r'", "y' U2 R' U' R U' R' U R"]),
+    f2l("23", "Corner and edge in U", &["U R U' R' U' R U' R' U R U' R'", "U2 R2 U2 R' U' R U' R2"]),
+    f2l("24", "Corner and edge in U", &["F U R U' R' F' R U' R'", "U' R' F R F' U' F' U' F"]),
+    f2l("25", "Corner in slot", &["U' R' F R F' R U R'", "U' F' U F U R U' R'"]),
+    f2l("26", "Corner in slot", &["U R U' R' F R' F' R", "U R U' R' U' F' U F"]),
+    f2l("27", "Corner in slot", &["R U' R' U R U' R'", "R U' R2 F R F'"]),
+    f2l("28", "Corner in slot", &["R U R' U' R U R'", "U2 F' U F R U R'"]),
+    f2l("29", "Corner in slot", &["R U' R' F' U' F", "U' R' F R F2 U' F", "U' F' U2 F U2 F' U' F"]),
+    f2l("30", "Corner in slot", &["R U2 R' F' U2 F", "F' U F U' F' U F", "R U R' U2 F' U F"]),
+    f2l("31", "Edge in slot", &["U' R' F R F' R U' R'", "R U' R' U F' U F"]),
+    f2l("32", "Edge in slot", &["R U R' U' R U R' U' R U R'", "R2 U R2 U R2 U2 R2", "U R U' R' U R U' R' U R U' R'"]),
+    f2l("33", "Edge in slot", &["U' R U' R' U2 R U' R'", "U' R U' R' U' R U2 R'"]),
+    f2l("34", "Edge in slot", &["U R U R' U2 R U R'", "U' R U2 R' U R U R'"]),
+    f2l("35", "Edge in slot", &["U' R U R' U F' U' F", "U' R U R' d R' U' R"]),
+    f2l("36", "Edge in slot", &["U F' U' F U' R U R'", "U2 F' U' F U R U' R'"]),
+    f2l("37", "Corner and edge in slot", &["R2 U2 F R2 F' U2 R' U R'", "R F R' F' U2 R U R U' R2"]),
+    f2l("38", "Corner and edge in slot", &["R U' R' U' R U R' U2 R U' R'", "R2 U2 R' U' R U' R' U2 R'"]),
+    f2l("39", "Corner and edge in slot", &["R U' R' U R U2 R' U R U' R'", "R U2 R U R' U R U2 R2"]),
+    f2l("40", "Corner and edge in slot", &["r U' r' U2 r U r' R U R'", "R U' R' F R U R' U' F' R U' R'"]),
+    f2l("41", "Corner and edge in slot", &["R U' R' r U' r' U2 r U r'", "R U F R U R' U' F' R'"]),
+];
+
+pub static OLL: [AlgCase; 57] = [
+    oll("1", "Dot", &["R U2 R2 F R F' U2 R' F R F'", "R U B' R B R2 U' R' F R F'"]),
+    oll("2", "Dot", &["F R U R' U' F' f R U R' U' f'", "U r U r' U2 r U2 R' U2 R U' r'"]),
+    oll("3", "Dot", &["r' R2 U R' U r U2 r' U M'", "y' f R U R' U' f' U' F R U R' U' F'"]),
+    oll("4", "Dot", &["M U' r U2 r' U' R U' R' M'", "y' f R U R' U' f' U F R U R' U' F'"]),
+    oll("5", "Square", &["r' U2 R U R' U r", "y2 l' U2 L U L' U l"]),
+    oll("6", "Square", &["r U2 R' U' R U' r'", "y2 l U2 L' U' L U' l'"]),
+    oll("7", "Small lightning", &["r U R' U R U2 r'", "y2 l U L' U L U2 l'"]),
+    oll("8", "Small lightning", &["y2 r' U' R U' R' U2 r", "l' U' L U' L' U2 l"]),
+    oll("9", "Fish", &["R U R' U' R' F R2 U R' U' F'", "R' U' R y r U' r' U r U r'"]),
+    oll("10", "Fish", &["R U R' U R' F R F' R U2 R'", "y2 L' U' L U L F' L2 U' L U F"]),
+    oll("11", "Small lightning", &["r U R' U R' F R F' R U2 r'", "U2 M R U R' U R U2 R' U M'"]),
+    oll("12", "Small lightning", &["M' R' U' R U' R' U2 R U' R r'", "y F R U R' U' F' U F R U R' U' F'"]),
+    oll("13", "Knight move", &["F U R U' R2 F' R U R U' R'", "r U' r' U' r U r' y' R' U R"]),
+    oll("14", "Knight move", &["R' F R U R' F' R F U' F'", "R' F R U R' F' R y' R U' R'"]),
+    oll("15", "Knight move", &["r' U' r R' U' R U r' U r", "y2 l' U' l L' U' L U l' U l"]),
+    oll("16", "Knight move", &["r U r' R U R' U' r U' r'", "y2 l U l' L U L' U' l U' l'"]),
+    oll("17", "Dot", &["F R' F' R2 r' U R U' R' U' M'", "U2 R U R' U R' F R F' U2 R' F R F'"]),
+    oll("18", "Dot", &["r U R' U R U2 r2 U' R U' R' U2 r", "y R U2 R2 F R F' U2 M' U R U' r'"]),
+    oll("19", "Dot", &["r' R U R U R' U' M' R' F R F'", "M U R U R' U' M' R' F R F'"]),
+    oll("20", "Dot", &["r U R' U' M2 U R U' R' U' M'", "M U R U R' U' M2 U R U' r'"]),
+    oll("21", "Cross", &["R U2 R' U' R U R' U' R U' R'", "y R U R' U R U' R' U R U2 R'"]),
+    oll("22", "Cross", &["R U2 R2 U' R2 U' R2 U2 R", "f R U R' U' f' F R U R' U' F'"]),
+    oll("23", "Cross", &["R2 D' R U2 R' D R U2 R", "y2 R2 D R' U2 R D' R' U2 R'"]),
+    oll("24", "Cross", &["r U R' U' r' F R F'", "L F R' F' L' F R F'"]),
+    oll("25", "Cross", &["F' r U R' U' r' F R", "U' R' F R B' R' F' R B"]),
+    oll("26", "Cross", &["R U2 R' U' R U' R'", "y' R' U' R U' R' U2 R"]),
+    oll("27", "Cross", &["R U R' U R U2 R'", "y' R' U2 R U R' U R"]),
+    oll("28", "Corners oriented", &["r U R' U' r' R U R U' R'", "U2 M' U M U2 M' U M"]),
+    oll("29", "Awkward", &["R U R' U' R U' R' F' U' F R U R'", "U' r2 D' r U r' D r2 U' r' U' r"]),
+    oll("30", "Awkward", &["F R' F R2 U' R' U' R U R' F2", "U r' D' r U' r' D r2 U' r' U r U r'"]),
+    oll("31", "P shape", &["R' U' F U R U' R' F' R", "y2 S' L' U' L U L F' L' f"]),
+    oll("32", "P shape", &["L U F' U' L' U L F L'", "y2 R U B' U' R' U R B R'"]),
+    oll("33", "T shape", &["R U R' U' R' F R F'", "F R U' R' U R U R' F'"]),
+    oll("34", "C shape", &["R U R2 U' R' F R U R U' F'", "R U R' U' B' R' F R F' B"]),
+    oll("35", "Fish", &["R U2 R2 F R F' R U2 R'", "F' U2 F2 R' F' R F' U2 F"]),
+    oll("36", "W shape", &["L' U' L U' L' U L U L F' L' F", "y2 R' U' R U' R' U R U l U' R' U x"]),
+    oll("37", "Fish", &["F R' F' R U R U' R'", "F R U' R' U' R U R' F'"]),
+    oll("38", "W shape", &["R U R' U R U' R' U' R' F R F'", "R' U' F' U F U2 R U R' U R"]),
+    oll("39", "Big lightning", &["L F' L' U' L U F U' L'", "U2 R U R' F' U' F U R U2 R'"]),
+    oll("40", "Big lightning", &["R' F R U R' U' F' U R", "R' F R U R' U' F' U R"]),
+    oll("41", "Awkward", &["R U R' U R U2 R' F R U R' U' F'", "y2 R U' R' U2 R U y R U' R' U' F'"]),
+    oll("42", "Awkward", &["R' U' R U' R' U2 R F R U R' U' F'", "U R' F R F' R' F R F' R U R' U' R U R'"]),
+    oll("43", "P shape", &["F' U' L' U L F", "U' R' U' F' U F R"]),
+    oll("44", "P shape", &["F U R U' R' F'", "y2 f R U R' U' f'"]),
+    oll("45", "T shape", &["F R U R' U' F'", "y2 f U R U' R' f'"]),
+    oll("46", "C shape", &["R' U' R' F R F' U R", "R' F' U' F R U' R' U2 R"]),
+    oll("47", "Small L shape", &["R' U' R' F R F' R' F R F' U R", "F' L' U' L U L' U' L U F"]),
+    oll("48", "Small L shape", &["F R U R' U' R U R' U' F'", "R U2 R' U' R U R' U2 R' F R F'"]),
+    oll("49", "Small L shape", &["r U' r2 U r2 U r2 U' r", "U2 R B' R2 F R2 B R2 F' R"]),
+    oll("50", "Small L shape", &["r' U r2 U' r2 U' r2 U r'", "U2 R' F R2 B' R2 F' R2 B R'"]),
+    oll("51", "Line", &["f R U R' U' R U R' U' f'", "y2 F U R U' R' U R U' R' F'"]),
+    oll("52", "Line", &["R U R' U R U' B U' B' R'", "U2 R' F' U' F U' R U R' U R"]),
+    oll("53", "Small L shape", &["r' U' R U' R' U R U' R' U2 r", "y2 l' U' L U' L' U L U' L' U2 l"]),
+    oll("54", "Small L shape", &["r U R' U R U' R' U R U2 r'", "y2 l U L' U L U' L' U L U2 l'"]),
+    oll("55", "Line", &["R' F R U R U' R2 F' R2 U' R' U R U R'", "U R U2 R2 U' R U' R' U2 F R F'"]),
+    oll("56", "Line", &["r' U' r U' R' U R U' R' U R r' U r", "F R U R' U' R F' r U R' U' r'"]),
+    oll("57", "Corners oriented", &["R U R' U' M' U R U' r'", "M' U M' U M' U2 M U M U M U2"]),
+];
+
+pub static PLL: [AlgCase; 21] = [
+    pll("Aa", "Corners only", &["x L2 D2 L' U' L D2 L' U L'", "U x R' U R' D2 R U' R' D2 R2 x'"]),
+    pll("Ab", "Corners only", &["x L U' L D2 L' U L D2 L2", "U x R2 D2 R U R' D2 R U' R x'"]),
+    pll("E", "Corners only", &["x' L' U L D' L' U' L D L' U' L D' L' U L D", "U R2 U R' U' y R U R' U' R U R' U' R U R' y' R U' R2"]),
+    pll("F", "Adjacent corner swap", &["R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R", "U' R' U R U' R2 F' U' F U R F R' F' R2"]),
+    pll("Ga", "Adjacent corner swap", &["R2 U R' U R' U' R U' R2 U' D R' U R D'", "R2 u R' U R' U' R u' R2 y' R' U R"]),
+    pll("Gb", "Adjacent corner swap", &["R' U' R U D' R2 U R' U R U' R U' R2 D", "R' U' R y R2 u R' U R U' R u' R2"]),
+    pll("Gc", "Adjacent corner swap", &["R2 U' R U' R U R' U R2 U D' R U' R' D", "R2 u' R U' R U R' u R2 y R U' R'"]),
+    pll("Gd", "Adjacent corner swap", &["R U R' U' D R2 U' R U' R' U R' U R2 D'", "R U R' y' R2 u' R U' R' U R' u R2"]),
+    pll("H", "Edges only", &["M2 U M2 U2 M2 U M2", "R2 U2 R U2 R2 U2 R2 U2 R U2 R2"]),
+    pll("Ja", "Adjacent corner swap", &["x R2 F R F' R U2 r' U r U2 x'", "U2 L' U' L F L' U' L U L F' L2 U L"]),
+    pll("Jb", "Adjacent corner swap", &["R U R' F' R U R' U' R' F R2 U' R'", "R U2 R' U' R U2 L' U R' U' L"]),
+    pll("Na", "Diagonal corner swap", &["R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'", "z R' U R' D R2 U' R U D' R' D R2 U' R D' z'"]),
+    pll("Nb", "Diagonal corner swap", &["R' U R U' R' F' U' F R U R' F R' F' R U' R", "z U' R D' R2 U R' D U' R D' R2 U R' D z'"]),
+    pll("Ra", "Adjacent corner swap", &["R U' R' U' R U R D R' U' R D' R' U2 R'", "R U R' F' R U2 R' U2 R' F R U R U2 R'"]),
+    pll("Rb", "Adjacent corner swap", &["R2 F R U R U' R' F' R U2 R' U2 R", "U' R' U2 R U2 R' F R U R' U' R' F' R2"]),
+    pll("T", "Adjacent corner swap", &["R U R' U' R' F R2 U' R' U' R U R' F'", "R U R' U' R' F R2 U' R' U F' L' U L"]),
+    pll("Ua", "Edges only", &["M2 U M U2 M' U M2", "R U' R U R U R U' R' U' R2"]),
+    pll("Ub", "Edges only", &["M2 U' M U2 M' U' M2", "R2 U R U R' U' R' U' R' U R'"]),
+    pll("V", "Diagonal corner swap", &["R' U R' U' y R' F' R2 U' R' U R' F R F", "U R U' R U R' D R D' R U' D R2 U R2 D' R2"]),
+    pll("Y", "Diagonal corner swap", &["F R U' R' U' R U R' F' R U R' U' R' F R F'", "F R' F R2 U' R' U' R U R' F' R U R' U' F'"]),
+    pll("Z", "Edges only", &["M' U M2 U M2 U M' U2 M2", "U M2 U M2 U M' U2 M2 U2 M'"]),
+];
+
+pub static COLL: [AlgCase; 40] = [
+    coll("H1", "H", &["R' F R' F' R F' U2 F R' F R F' R", "R U R2 F R F' U' F R' F' R F' U2 F", "F' U2 F R' F R F' U F R' F' R2 U' R'"]),
+    coll("H2", "H", &["F' U F2 R2 F' U2 F' U2 F R2 U F' U2 F", "R2 F' R' U2 R F' R2 F2 U2 R' F2 R' F' R2 F'"]),
+    coll("H3", "H", &["R' U R U' F R2 U F' R F R U' F' R2", "R2 U F2 U2 F U R2 U' R2 F' U F2 U' R2"]),
+    coll("H4", "H", &["R U R' U R U2 R' U' R' U2 R U R' U R", "R' U' R U' R' U2 R U R U2 R' U' R U' R'", "R U R' U R U2 R' F' U2 F U F' U F"]),
+    coll("Pi1", "Pi", &["R' F' R' F2 R2 U2 R U2 R F2 R2 F R U2 R'"]),
+    coll("Pi2", "Pi", &["R' F R' F' R2 U R2 U' F R' F' R U R2", "F2 U' R2 U R' F2 U' F2 U R U2 R2 U F2"]),
+    coll("Pi3", "Pi", &["R U' F' U2 R' U F U' F' R U2 F U' R'", "F' U' R U2 F R' U' R U F' U2 R' U' F", "R U2 R F2 R U' R2 U' R' U R' U R' F2 R2"]),
+    coll("Pi4", "Pi", &["R U2 R' U F2 R U2 R' U2 R' F2 R2 U R'", "R U2 R U' R2 U R F U' R2 U' R2 U F' R", "R' F2 R' F' R2 F' U2 R2 F2 R' F U2 F' R' F2"]),
+    coll("Pi5", "Pi", &["R2 U R' U R U' R2 U R U' R U' R' U R'", "R2 U' R U' R' U R2 U' R' U R' U R U' R", "F U F' U F U2 F' R U R' U R U2 R'"]),
+    coll("Pi6", "Pi", &["R U F' U2 R' F U F' U' R U2 F U R'", "F' U R U2 F U' R' U R F' U2 R' U F", "R' F R2 U R F' R2 F R U' R2 F' U R"]),
+    coll("U1", "U", &["R U2 R' U R' U2 R U2 R U R' U' R' U R", "R U2 R' U' R U' R' U2 R' U2 R U R' U R", "R' U2 R U R' U R U2 R U2 R' U' R U' R'"]),
+    coll("U2", "U", &["F U2 F' R F R' U2 R F' R'", "F U2 F' U' R' U F U2 F' U' R"]),
+    coll("U3", "U", &["F U2 R U2 R U R' U R' U' R2 U' R2 F'", "R' F R U R' U' F' R F R' U R2 U' R' F'", "F R U' R' U2 R' U' F' U F R2 U2 R' U' F'"]),
+    coll("U4", "U", &["F R F' R' F U2 F' R F R' U2 F'", "F2 U2 F' R F U' F' U R' U' F U' F2", "F2 U' F U F2 R' F2 U' F' U F' R F'"]),
+    coll("U5", "U", &["R' F' R F R' U2 R F' R' F U2 R", "R2 U R' U' R2 F R2 U R U' R F' R", "R2 U2 R F' R' U R U' F U R' U R2"]),
+    coll("U6", "U", &["R U R' U R' F2 U' R2 U' R2 U F2 U R"]),
+    coll("T1", "T", &["R' F' R U2 R U' R2 U' R' F R U R2 U R'", "F2 R U2 R' F2 U2 R U R2 U' F2 U R2 U' R'", "F R' F' R F' R' U' F U F2 U' F U R F"]),
+    coll("T2", "T", &["R U R' U R U2 R' U2 R' U' R U' R' U2 R", "R U R' U' R' U R U2 R U2 R' U R' U2 R", "R' U' R U R U' R' U2 R' U2 R U' R U2 R'"]),
+    coll("T3", "T", &["F' R' F U2 F' R F R' U2 R", "R U2 R' F2 R U2 R' U2 R' F2 R"]),
+    coll("T4", "T", &["F U F' R' F U' F' U R"]),
+    coll("T5", "T", &["R' U' R F R' U R U' F'"]),
+    coll("T6", "T", &["R' U' R' U2 R' U' R' U F' U2 R' U2 R F R2", "R U R2 F' U' F U R2 U' R2 F R F2 U F", "F' U' F' U2 F' U' F' U' F2 R F U2 F' U2 R'"]),
+    coll("L1", "L", &["R2 U' R F' U' F U' R' U' R F' U' F U' R"]),
+    coll("L2", "L", &["F U2 R F' R' F U2 F' R F R' F'", "F2 U F' U R U' F U F' R' F U2 F2", "F R' F U' F U F2 R F2 U' F' U F2"]),
+    coll("L3", "L", &["R' U2 R U R2 U R U F' R' U2 R U2 F R", "R U' R F' R' U R F' R2 F U' F' R2 F2 R2", "F R U2 F U2 F' R' U F U F2 U F U2 F'"]),
+    coll("L4", "L", &["F U R' U' R F' R' U R", "F U F' R F R' U' R F' R'"]),
+    coll("L5", "L", &["R' U R U' F U2 F' R' U2 R U' F U F'", "F U' F' U R' U2 R F U2 F' U R' U' R", "R' U R U2 R U2 R' U R' U2 R U2 R U R'"]),
+    coll("L6", "L", &["R' U' F U F' R F U' F'", "R' U' R F' R' F U F' R F"]),
+    coll("S1", "Sune", &["F R' U R U F' R' U F U F' R", "R' U2 R U2 F' U2 F' R' F U2 F' R F2"]),
+    coll("S2", "Sune", &["R2 U' F R U R U' R2 F' R' F R2 F' R", "R' U2 R U2 R' F' U2 F U2 R' F R F' R", "F R' F2 R F' R' F2 U' F U F R U' F2"]),
+    coll("S3", "Sune", &["R' U' R2 U2 R' F' U F U R' U2 F R F'", "R' F R' U R' F' R2 F R' F' R2 F U' R2 F'"]),
+    coll("S4", "Sune", &["F R' U R U' F' U R' U' R", "F U' F' U R' U' F U F' R"]),
+    coll("S5", "Sune", &["R' F R U2 F' U R U R' F' U2 F2 U' F'"]),
+    coll("S6", "Sune", &["R2 U R U' R U2 R U' R U2 R U' R U R2", "R2 U R' U R U2 R U2 R U R' U R2 U R'", "R' U R2 U R' U R U2 R U2 R U R' U R2"]),
+    coll("AS1", "Antisune", &["R' F U2 F' R F R' U2 R F'"]),
+    coll("AS2", "Antisune", &["R' U R U' F U R' U' R F'", "R' F U' F' U R U' F U F'"]),
+    coll("AS3", "Antisune", &["R U' R2 U' R U' R' U2 R' U2 R' U' R U' R2", "R2 U' R U' R' U2 R' U2 R' U' R U' R2 U' R", "R2 U' R' U R' U2 R' U R' U2 R' U R' U' R2"]),
+    coll("AS4", "Antisune", &["R U R' U R' F U' R2 U' R2 U F' U R"]),
+    coll("AS5", "Antisune", &["R' F R2 F' R F R2 U R' U' R' F' U R2", "F U2 F' U2 F R U2 R' U2 F R' F' R F'", "F2 U R' F' U' F' U F2 R F R' F2 R F'"]),
+    coll("AS6", "Antisune", &["F U R' U F2 U' F2 U' R F' U F' U F", "F U R' F2 U' F U F' R F2 R' U' F U R"]),
+];
+
+pub static ZBLL: [AlgCase; 144] = [
+    zbll("T1", "T", &["R U2 R' F R' U' R2 U' R2 U2 R U' R' F' R", "R U' R' U R F2 R F' R F R2 F' R F' R'", "F2 R2 F' R2 U' R2 U2 R2 U R2 F' U F2 R2 F2"]),
+    zbll("T2", "T", &["R U R2 F R F' R U' R' F' U F", "F' U' F R U R' F R' F' R2 U' R'"]),
+    zbll("T3", "T", &["R' F R U R' U2 R2 U R2 U R F' R U2 R'", "F2 R2 F2 U' F R2 U' R2 U2 R2 U R2 F R2 F2", "F' U' F' R2 U2 F U F' U R2 F2 U' F' U2 F"]),
+    zbll("T4", "T", &["R U2 R' F2 R2 F2 U2 R' U2 R' U2 R F2 R2 F2", "R' U2 F2 R2 F2 R' U2 R U2 R U2 F2 R2 F2 R", "R U2 F2 R2 F2 R2 F2 R F2 R F2 R' F2 U2 R'"]),
+    zbll("T5", "T", &["R' F' R U2 R U' R2 U' R' F R U R2 U R'", "F2 R U2 R' F2 U2 R U R2 U' F2 U R2 U' R'", "F R' F' R F' R' U' F U F2 U' F U R F"]),
+    zbll("T6", "T", &["R' F' U F U R U R2 F R F' R"]),
+    zbll("T7", "T", &["R' U R U2 R' F U2 F' U' F U' F' R"]),
+    zbll("T8", "T", &["F U' F' U2 F R' U2 R U R' U R F'"]),
+    zbll("T9", "T", &["R U R' U R U2 R' U2 R' U' R U' R' U2 R", "R U R' U' R' U R U2 R U2 R' U R' U2 R", "R' U' R U R U' R' U2 R' U2 R U' R U2 R'"]),
+    zbll("T10", "T", &["R U2 F U F' U R' U F U F'"]),
+    zbll("T11", "T", &["F' U2 R' U' R U' F U' R' U' R"]),
+    zbll("T12", "T", &["R U2 R' U' R U' R' U R U R' U R U2 R'", "R U' R U2 R U2 R' U R U2 R U' R' U' R2", "R' U2 R' U R2 U' R U2 R2 U' R' U2 R U R'"]),
+    zbll("T13", "T", &["R' U' R U' F U' R' U R U F' R' U R", "R' U' R U2 F' R' F U F' R F U R' U R", "R' F U' F U F2 R' F' U F U' F' U' F2 R2"]),
+    zbll("T14", "T", &["R' U2 R U R' U R2 U2 R' U' R U' R'"]),
+    zbll("T15", "T", &["F U F' R U R' U F U F' U' R U2 R'", "F U F' R U2 R F R' U R F' R' U R'", "R F2 U R2 U' R' U R' U' R2 U R U' F2 R'"]),
+    zbll("T16", "T", &["F' R' F U2 F' R F R' U2 R", "R U2 R' F2 R U2 R' U2 R' F2 R"]),
+    zbll("T17", "T", &["F U' R' U R U F' U R' U' R U R' U R", "R U F' U2 R' U R U2 R' U' R U2 F U' R'", "F' R F U' R' U R U2 R' U' R U F' R' F"]),
+    zbll("T18", "T", &["F2 U F' U' R U2 F U2 F' R' U F'"]),
+    zbll("T19", "T", &["F' R' F U' F' U2 F U' F' U' R F", "R U2 R F2 U R U R2 U' R' U' F2 R2"]),
+    zbll("T20", "T", &["R' F2 R U' R2 F2 R2 U' R' U2 R' F2 R2"]),
+    zbll("T21", "T", &["R F R' U2 R F' R' F U2 F'", "F' U2 F R2 F' U2 F U2 F R2 F'"]),
+    zbll("T22", "T", &["F U' R' U2 R U F' R' U R"]),
+    zbll("T23", "T", &["R' U' R U F U' R' U2 R U F' R' U2 R", "R' U' R F' R' F U2 F' R F U R' U2 R", "R' U' R U F R2 U R2 U' F' R U R2 U' R'"]),
+    zbll("T24", "T", &["R' U2 R F U' R' U R U F' R' U R", "R U2 R U' R2 F' R' U R U' F R2 U' R'"]),
+    zbll("T25", "T", &["R' U' F U F' R F U F' U' F U' F'", "F2 U F' U' R U' R' U' R U2 R' F U' F2"]),
+    zbll("T26", "T", &["R U R' U' R' F' R U2 R U2 R' F"]),
+    zbll("T27", "T", &["F U F' R' F U' F' U R"]),
+    zbll("T28", "T", &["R F U R2 U' R2 F' U R U R2 U' R2", "F U' F U F U R F U' F' U R' U2 F2"]),
+    zbll("T29", "T", &["R' U' F' U2 F U' F' U' F R"]),
+    zbll("T30", "T", &["R' U2 R' U2 R F U' R' U' R U F' R", "R' U2 R' U2 R2 U F' R U R' U' R' F", "R2 U' F' R F U' R' U2 R U' F' R' F R2"]),
+    zbll("T31", "T", &["F' R' U' F U F' U F U F' U' R F", "F' R U R U' R' F R' U' R' U2 R U2 R", "R U R2 F' R' U2 R U2 F R' U' R2 U' R"]),
+    zbll("T32", "T", &["R2 U' F' U2 F R2 U2 F R2 F' R2 U' R2", "F R U R2 U' R' F' R' U' R2 U2 R2 U2 R'"]),
+    zbll("T33", "T", &["R2 F' R' F U2 F' R F U2 R U2 R U2 R", "R' U2 R' U2 R' U2 F' R' F U2 F' R F R2", "R' U R F' R U R U' R' F R2 U R U2 R"]),
+    zbll("T34", "T", &["R' U' R F' U' F U' R' U' R U F' U2 F", "R' U' R F' U2 F' R' F U' F' R F U' F", "F R2 U' R' U' R U R' F R2 U R U' R F2"]),
+    zbll("T35", "T", &["R' U' R F R' U R U' F'"]),
+    zbll("T36", "T", &["F U' R F U2 F' U2 R' U F U' F2"]),
+    zbll("T37", "T", &["R' U' R U R U' R2 F' R' U R U' F R2", "R' U' R2 F' R' U R U' R U R U' R' F R'", "R U2 R' U' R U R' F2 R U2 R' U2 R' F2 R"]),
+    zbll("T38", "T", &["F U2 F' R' U F U' F' U' R F U' F'", "F' U2 F' U F2 R F U' F' U R' F2 U F"]),
+    zbll("T39", "T", &["R' U2 R U R' U R U' R' U' R U' R' U2 R", "R' U2 R U R' U R F' U' F U' F' U2 F", "R' U R' U2 R' U2 R U' R' U2 R' U R U R2"]),
+    zbll("T40", "T", &["F R U R' U F' U' F2 R' F' R U' F'"]),
+    zbll("T41", "T", &["R2 U' R U F' U2 R' U2 R F U' R"]),
+    zbll("T42", "T", &["R' U' R U' R' U R F U' R' U2 R U F'", "R' U' R U' R' U R U' F' R' F U2 F' R F"]),
+    zbll("T43", "T", &["R' U2 R' U2 R2 U R F R U R U' R' F' R2", "R U2 F' R F2 U2 R2 U2 F2 R' F R2 F R F'", "F R' F' R2 F' R F2 U2 R2 U2 F2 R' F U2 R'"]),
+    zbll("T44", "T", &["F U F' U F U' F' R' U F U2 F' U' R", "F U F' U F U' F' U R F R' U2 R F' R'"]),
+    zbll("T45", "T", &["R' U2 R2 U' R U' R U' R U' R' U2 R2 U R2", "R F2 R F R' F R' U' R F' R F' R' F2 R'", "F' R F' R F R2 F U' F' R2 F' R' F R' F"]),
+    zbll("T46", "T", &["F U' R' U2 R U F' R' U' R U R' U R", "F U2 F U2 F U R' F' U' F2 U F R U F2"]),
+    zbll("T47", "T", &["R' U F U2 F' U' R F U F' U' F U' F'", "R' U2 R' U2 R' U' F R U R2 U' R' F' U' R2"]),
+    zbll("T48", "T", &["R U2 R' U' R U' R2 U2 R U R' U R"]),
+    zbll("T49", "T", &["F U F' U R' U F U' F' U' R F U' F'", "F R' U R' U' R2 F R U' R' U R U R2 F2", "F U F' U2 R F R' U' R F' R' U' F U' F'"]),
+    zbll("T50", "T", &["R2 F R' U' R F' R' U2 F R' U' R F' U' R'", "F' U F R U R2 F R2 U R' U' F' R U' R'", "R U2 R2 F' R2 F R U F' U R U' F U R2"]),
+    zbll("T51", "T", &["R U2 R2 F2 R U2 R U R2 F2 R2 U R' F2", "R U R2 F' U' R' U' R U F R' U' R2 U2 R", "R' F2 U F R' F R U F U2 F' U2 F' U' R"]),
+    zbll("T52", "T", &["R F R' U R U2 R' U R U F' R'", "F' U2 F' R2 U' F' U' F2 U F U R2 F2"]),
+    zbll("T53", "T", &["R' U2 R' U' R' U' R' F' R' U R U' F U2 R2", "F' R U2 R' F' U' R' F R F U' F' U2 F2", "R' U2 R U' R' U F' U F U F' U2 F U2 R"]),
+    zbll("T54", "T", &["R' U' R' U' R' F' R U R2 U' R' F U2 R", "R U R' F2 U' F2 U' F2 R' F2 R U2 F2", "R' U' R' U' F U F' R2 F U' F' R' U2 R"]),
+    zbll("T55", "T", &["R' U R' U' R' U' F' R' U R U' F U2 R2", "F' R' U' F2 U F2 R U' F' U' F2 U F2"]),
+    zbll("T56", "T", &["R' U R F2 R F2 U' F2 U R2 U' R F2"]),
+    zbll("T57", "T", &["R U2 R' U2 R' F R U R U' R' F'"]),
+    zbll("T58", "T", &["R F' U2 F R U F R' F' R' U R U2 R2", "F U2 F' U F U' R U' R' U' R U2 R' U2 F'", "F U2 F U F U F R F U' F' U R' U2 F2"]),
+    zbll("T59", "T", &["F U R' U' R F' R' U' R U R' U R", "R2 U' R U F' U F U F' U2 F R' U R2"]),
+    zbll("T60", "T", &["R' U2 R U F U' R' U' R U F' R' U2 R", "F R U R2 U' R' F' R U R U R U2 R'", "R' U2 R F' R' F U' F' R F U R' U2 R"]),
+    zbll("T61", "T", &["F U' F' R2 F' R2 U R2 U' F2 U F' R2"]),
+    zbll("T62", "T", &["F U' R2 U R' U R U2 R2 U' R U2 R' F'", "R' U R U2 R' U F' U' F U2 F' U' F U' R", "F U' R U' R' U2 R U' R' U F' U2 F U F'"]),
+    zbll("T63", "T", &["R F U R' U' R U' R' U' R U F' R'", "R U R' U' R U2 F U R' U' R F' U R'", "R F' U' F' U F R' F U F U2 F' U2 F'"]),
+    zbll("T64", "T", &["R U R2 U' R2 U' R2 U2 R U' R U' R'", "F' R2 F2 R U R' U2 R U R' U F2 R2 F"]),
+    zbll("T65", "T", &["F R U2 R' U R2 U2 R' U' R U' R2 U F'", "F' R F' R2 F' U2 F' U F U F R2 F R' F", "F2 R2 F' U2 F' U2 F R F' U' F U F R F2"]),
+    zbll("T66", "T", &["R' U' R' U2 R' U' R' U F' U2 R' U2 R F R2", "R U R2 F' U' F U R2 U' R2 F R F2 U F", "F' U' F' U2 F' U' F' U' F2 R F U2 F' U2 R'"]),
+    zbll("T67", "T", &["R' U' R2 U R2 U R2 U2 R' U R' U R", "F R' U2 F2 U' F2 U' R F2 R' U' R U' F"]),
+    zbll("T68", "T", &["R' F' U' F U' R U R2 F R F' U R"]),
+    zbll("T69", "T", &["F R U R' U' R U' R' U' R U R' F'", "F2 R U' R' U R U R2 F2 R U R U' R'", "F R U R' U' F' R' F R F' U' F' U F"]),
+    zbll("T70", "T", &["R U2 F R' F R2 F' R' F R' F2 U2 R'", "R U2 F U R2 U' R' U R' U' R' F' U2 R'"]),
+    zbll("T71", "T", &["R' U F' R' U2 R U2 F U' R' U R2"]),
+    zbll("T72", "T", &["R2 F' R' U2 R U2 F U' R U R U2 R U R", "R2 F' R' U F U F' R U2 F R' U R U R2", "R' U2 F' R2 F R' U2 R F' R' U2 R' U2 F R"]),
+    zbll("U1", "U", &["F2 R U' R' U' R U F U F' R' F U' F", "F' U F' R F U' F' U' R' U R U R' F2", "F R' U' R F2 R' U R U F2 U' F2 U' F"]),
+    zbll("U2", "U", &["R F2 R2 U' R U' R' U R2 F2 R' U2 R' U' R"]),
+    zbll("U3", "U", &["R U' R F' R' U R U F U' F' U' F R2", "R2 F' U F U F' U' R' U' R F R' U R'", "R U' R2 U' R2 U F U F' R2 F U' F' R"]),
+    zbll("U4", "U", &["F R' U' R U' R' U2 R F' U2 F U F'", "R' U' R U' R' U2 R F' U2 F U F' U F"]),
+    zbll("U5", "U", &["R' F U F' U F U2 F' R U2 R' U' R", "F U F' U F U2 F' R U2 R' U' R U' R'"]),
+    zbll("U6", "U", &["R U2 R' U R' U2 R U2 R U R' U' R' U R", "R U2 R' U' R U' R' U2 R' U2 R U R' U R", "R' U2 R U R' U R U2 R U2 R' U' R U' R'"]),
+    zbll("U7", "U", &["F U F' R' U F U F' U' R U' F U' F'", "F2 R2 U' R' U' R U R' F' R2 U R U' R F'", "F U F' U R F R' U R F' R' U2 F U' F'"]),
+    zbll("U8", "U", &["F' U2 F U' R' U R U F' U F R' U R", "F' U F' R' F U F' R F U2 F R' U R", "R F U' R' F U R' U' R2 F' R U R2 F' R'"]),
+    zbll("U9", "U", &["F U2 F' R F R' U2 R F' R'", "F U2 F' U' R' U F U2 F' U' R"]),
+    zbll("U10", "U", &["F U2 F U R U' R' F2 U F R' F R F2", "F U' R2 U F2 U2 F2 U' R2 U F' U F' U F", "F' U2 F' R2 F' U F2 U F2 U2 F' U F2 R2 F2"]),
+    zbll("U11", "U", &["R2 F R F' R U R2 F' U' F U R U2 R", "R2 F2 R2 U R' U2 R2 U R2 U R' F2 R' U2 R'", "F' R F2 R U R U2 R' U R U R2 F2 R' F"]),
+    zbll("U12", "U", &["R2 F2 R U2 R U R2 F2 R2 U R' F2 R"]),
+    zbll("U13", "U", &["R' U2 R F' R' F U2 F' R F", "R' U2 R U F U' R' U2 R U F'"]),
+    zbll("U14", "U", &["F R F' U' R U2 F R2 F' U' R' U' R'", "F' U' R U' F U' F' R' U' R U' R' U' F", "R U F' U' F R U F R2 F' U' R' U' R'"]),
+    zbll("U15", "U", &["F R' U' R F' U' R' U2 R U2 F U F'", "R' U' R U2 F U R' U' R F' U R' U2 R"]),
+    zbll("U16", "U", &["R' U F U' F' U' R F U2 F'"]),
+    zbll("U17", "U", &["F' R' U F U F' U2 F U F' R F"]),
+    zbll("U18", "U", &["R U R U' R2 F2 U' R F2 R' U F2 R2 U R2", "R2 U' R2 F2 U' R F2 R' U F2 R2 U R' U' R'", "R U R2 U2 F2 U' R2 U2 R2 F2 U' F2 U2 F2 R"]),
+    zbll("U19", "U", &["R U R' U' R U' R2 F2 U' R F2 R' U F2 R", "R' F2 U' R F2 R' U F2 R2 U R' U R U' R'"]),
+    zbll("U20", "U", &["R U' R' F R2 U F' R F U' R F' R"]),
+    zbll("U21", "U", &["F' U F U F' U2 F U2 R U' R' U2 R U' R'"]),
+    zbll("U22", "U", &["F R U2 R2 U' R2 U' R' F U' R' U' R U F2"]),
+    zbll("U23", "U", &["R U R U F R2 F' U2 R' U F R' F'", "R U' F' U2 R' U R U R' U2 R U' F R'", "R' U' R U2 F R' U R U' F' U2 R' U R"]),
+    zbll("U24", "U", &["R' F' U' F U R F R' F2 R U2 R U2 R' F"]),
+    zbll("U25", "U", &["F U2 R U2 R U R' U R' U' R2 U' R2 F'", "R' F R U R' U' F' R F R' U R2 U' R' F'", "F R U' R' U2 R' U' F' U F R2 U2 R' U' F'"]),
+    zbll("U26", "U", &["F U2 F' R' U F U F' U' R", "F U2 F' U R F R' U R F' R'"]),
+    zbll("U27", "U", &["F U2 R' U' R F' R' U2 F U F' U' R", "F U' F' R2 F' U' R2 U' R2 U F2 U F' R2"]),
+    zbll("U28", "U", &["R' U2 R U2 F U' R' U R U F'", "R' U2 R U F' R' F U F' R F", "R' U2 R U R' F U F' R F U' F'"]),
+    zbll("U29", "U", &["F R F' R' F U2 F' R F R' U2 F'", "F2 U2 F' R F U' F' U R' U' F U' F2", "F2 U' F U F2 R' F2 U' F' U F' R F'"]),
+    zbll("U30", "U", &["F U2 R' U F R' F' U2 R U R U F R2 F2"]),
+    zbll("U31", "U", &["F' R2 F U' F2 U2 F2 U' F2 U' F R2 F", "R2 F2 R U' F' U2 F U' F' U' F R' F2 R2", "R2 F2 U2 R2 F2 R2 U' F' R2 F U' F' R2 F'"]),
+    zbll("U32", "U", &["F U' R U' R' F U' F' U' R U' R' F U' F2"]),
+    zbll("U33", "U", &["R' U' R U' R' U2 R2 U R' U R U2 R'"]),
+    zbll("U34", "U", &["R' U2 R U R' U R U R' U' R U' R' U2 R", "R2 U' R' U' R U2 R U R' U2 R U2 R U' R", "R2 U R U2 R U2 R' U R' U2 R2 U' R' U R"]),
+    zbll("U35", "U", &["F' U2 R' U' R F U' R' U2 R F' U2 F"]),
+    zbll("U36", "U", &["F2 R' F U' F' R F U' R' U2 R U' F", "R2 U' R F' R' U R U' F R U' R' U2 R", "R2 U' R2 F U' R2 U R2 U F' U' R2 U R2"]),
+    zbll("U37", "U", &["R F2 R' U R2 U2 R2 U R2 U R' F2 R'", "F2 R2 F' U R U2 R' U R U R' F R2 F2", "F2 R2 U2 F2 R2 F2 U R F2 R' U R F2 R"]),
+    zbll("U38", "U", &["R' F' R F R' U2 R F' R' F U2 R", "R2 U R' U' R2 F R2 U R U' R F' R", "R2 U2 R F' R' U R U' F U R' U R2"]),
+    zbll("U39", "U", &["F U R' F R F2 U F U' R U' R' F'"]),
+    zbll("U40", "U", &["F U2 F' U' R F R' U' R F' R'", "F U2 F' U2 R' U F U' F' U' R", "F U2 F' U' F R' U' R F' R' U R"]),
+    zbll("U41", "U", &["R U2 R' U' R2 U' R F2 R' U R' U' R2 F2 R", "R U' F' U2 R2 U F U' F' U' R2 U' F U' R'"]),
+    zbll("U42", "U", &["R' U' F R' F' R2 U' R' U F' U F R"]),
+    zbll("U43", "U", &["R2 U' R2 U2 R U R' U R' U R' U R2 U2 R", "R U2 F U F2 U2 R' F' U2 F2 R U F' R' F2"]),
+    zbll("U44", "U", &["R F U' R' U' R U2 R' U' R F' R'"]),
+    zbll("U45", "U", &["F2 R U' R2 F2 R2 U' R' U2 R' F2 R2 U2 R'", "F' U2 F2 U R' U F2 U' F2 U' R F2 U F", "R' F2 R U' R2 F2 R2 U R' F2 R U2 R' U2 R"]),
+    zbll("U46", "U", &["R F2 R2 U R' U' R' F U F' R' F U' F R'", "R F' U F' R F U' F' R U R U' R2 F2 R'"]),
+    zbll("U47", "U", &["R U2 R' U F U' F' U' R U' R' F U' F'", "R U' R F R' U' R F' R' U2 R' F U' F'", "R F2 U R' U' R2 U R U' R U R2 U' F2 R'"]),
+    zbll("U48", "U", &["F U' R' U R U F' R' U2 R"]),
+    zbll("U49", "U", &["R' U R U2 F U2 F' U' R' F U' F' R"]),
+    zbll("U50", "U", &["R' U' R F U' R' U' R U F' U R' U R", "R' U' R U' F' R' F U' F' R F U2 R' U R", "R2 U R U' R F R F' R F U R2 U' F' R'"]),
+    zbll("U51", "U", &["R' U2 R' U' F' U F R2 U' R' F R' F' R2", "R' U F2 U' R2 U2 R2 U F2 U' R U' R U' R'", "R U2 R F2 R U' R2 U' R2 U2 R U' R2 F2 R2"]),
+    zbll("U52", "U", &["R' U' F' R' U' F' R' F R U F R U F R"]),
+    zbll("U53", "U", &["R U2 F U F' R' U F U2 F' R U2 R'"]),
+    zbll("U54", "U", &["R2 F R' U R F' R' U F U2 F' U R'", "F2 U F' R F U' F' U R' F' U F U2 F'", "F2 U F2 R' U F2 U' F2 U' R U F2 U' F2"]),
+    zbll("U55", "U", &["R F R F' U R' U' R2 U R F' U' F U' R2"]),
+    zbll("U56", "U", &["R F2 U' R2 U' R U2 R' U' R U' R U F2 R'", "F' R2 U' R F2 R' U2 F2 U' F2 U' R F2 R F"]),
+    zbll("U57", "U", &["F' U F R' F2 U' R F' R' U F' R F'"]),
+    zbll("U58", "U", &["R' U' R U' R U2 R2 U' R2 U' R2 U R", "R2 F' R U' R F U' F' U R' U R' F R2", "R' U' R F U F' U F U2 F' U R' U2 R"]),
+    zbll("U59", "U", &["R U R' U R U2 R2 U' R U' R' U2 R"]),
+    zbll("U60", "U", &["R U2 R2 U' R F' U' R' U2 R U F R U' R'"]),
+    zbll("U61", "U", &["R' U2 F U F' R F U2 R' U' R U F'", "R' U R F2 R U F2 U F2 U' R2 U' R F2"]),
+    zbll("U62", "U", &["R' U F' U F R' U R U F' U F R' U R2"]),
+    zbll("U63", "U", &["R U R' U2 F2 R U2 R' U2 R' F2 R2 U R'", "F R U R2 F R F' R' U2 R2 U R2 U R F'"]),
+    zbll("U64", "U", &["R U R' U R' F2 U' R2 U' R2 U F2 U R"]),
+    zbll("U65", "U", &["R U R' U R' U2 R2 U R2 U R2 U' R'", "R U' F U' F' R2 F U' R2 U' R2 U2 F' R"]),
+    zbll("U66", "U", &["R U2 F2 R F' R F R2 F' R F' U2 R'", "R U2 F R U R U' R U R2 U' F' U2 R'"]),
+    zbll("U67", "U", &["R' U' F2 U' R2 U R2 U F2 R U' R U' R'"]),
+    zbll("U68", "U", &["F' R U2 R' U2 R' F2 R F' R' U' F' U F R"]),
+    zbll("U69", "U", &["R' U2 R F U' R' U' R U F'", "R' U2 R U' F' R' F U' F' R F"]),
+    zbll("U70", "U", &["R U2 R' U' R U' R' U' R U R' U R U2 R'", "R U2 R' U' R U' R' F U F' U F U2 F'", "R2 U R U R' U2 R' U' R U2 R' U2 R' U R'"]),
+    zbll("U71", "U", &["F R U' R' U R U R' U R U' R' F'", "R U R' U' R' F2 R2 U' R' U' R U R' F2"]),
+    zbll("U72", "U", &["F R U R U R2 U R2 U2 R2 U2 R U R' U2 F'", "F R2 U2 R2 U2 R' U' R U2 R' U' R' U' R2 U' F'", "F R2 U2 R2 U' R2 U' R' U' R' U2 R U' R' U2 F'"]),
+];
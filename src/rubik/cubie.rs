@@ -0,0 +1,455 @@
+use std::fmt;
+use std::ops::Mul;
+use std::sync::OnceLock;
+
+use crate::Error;
+use super::formats::KOCIEMBA_FACES;
+use super::{apply_moves, to_kociemba, Cube, Move, SOLVED};
+
+/// The corner positions of the cube, named after the faces they touch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Corner {
+    Urf,
+    Ufl,
+    Ulb,
+    Ubr,
+    Dfr,
+    Dlf,
+    Dbl,
+    Drb,
+}
+
+/// The edge positions of the cube, named after the faces they touch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Edge {
+    Ur,
+    Uf,
+    Ul,
+    Ub,
+    Dr,
+    Df,
+    Dl,
+    Db,
+    Fr,
+    Fl,
+    Bl,
+    Br,
+}
+
+impl Corner {
+    pub const ALL: [Corner; 8] = [
+        Corner::Urf, Corner::Ufl, Corner::Ulb, Corner::Ubr, Corner::Dfr, Corner::Dlf, Corner::Dbl, Corner::Drb,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        ["URF", "UFL", "ULB", "UBR", "DFR", "DLF", "DBL", "DRB"][*self as usize]
+    }
+}
+
+impl Edge {
+    pub const ALL: [Edge; 12] = [
+        Edge::Ur, Edge::Uf, Edge::Ul, Edge::Ub, Edge::Dr, Edge::Df, Edge::Dl, Edge::Db, Edge::Fr, Edge::Fl, Edge::Bl, Edge::Br,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        ["UR", "UF", "UL", "UB", "DR", "DF", "DL", "DB", "FR", "FL", "BL", "BR"][*self as usize]
+    }
+}
+
+/// A corner or an edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Cubie {
+    Corner(Corner),
+    Edge(Edge),
+}
+
+impl fmt::Display for Cubie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cubie::Corner(corner) => write!(f, "{}", corner.name()),
+            Cubie::Edge(edge) => write!(f, "{}", edge.name()),
+        }
+    }
+}
+
+/// Stickers of every corner in a Kociemba facelet string, clockwise starting from the U or D sticker.
+const CORNER_FACELETS: [[usize; 3]; 8] = [
+    [8, 9, 20], [6, 18, 38], [0, 36, 47], [2, 45, 11], [29, 26, 15], [27, 44, 24], [33, 53, 42], [35, 17, 51],
+];
+
+/// Stickers of every edge in a Kociemba facelet string, starting from the U or D sticker, or F or B for the middle layer.
+const EDGE_FACELETS: [[usize; 2]; 12] = [
+    [5, 10], [7, 19], [3, 37], [1, 46], [32, 16], [28, 25], [30, 43], [34, 52], [23, 12], [21, 41], [50, 39], [48, 14],
+];
+
+///
+/// The cube as pieces instead of stickers: which corner and edge is at each position, and how it's twisted.
+///
+/// `cp[i]` is the corner at position `i` and `co[i]` its twist, the number of clockwise turns that
+/// bring its U or D sticker back up or down. Edges are the same, with `eo` telling whether they're flipped.
+/// This is the representation and the numbering of Kociemba's two phase solver.
+///
+/// States multiply like permutations, `a * b` being `a` followed by `b`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CubieCube {
+    pub cp: [Corner; 8],
+    pub co: [u8; 8],
+    pub ep: [Edge; 12],
+    pub eo: [u8; 12],
+}
+
+impl Default for CubieCube {
+    fn default() -> Self {
+        Self::SOLVED
+    }
+}
+
+impl CubieCube {
+    pub const SOLVED: CubieCube = CubieCube { cp: Corner::ALL, co: [0; 8], ep: Edge::ALL, eo: [0; 12] };
+
+    /// The pieces of a facelet string. Fails if a sticker combination isn't a piece of the cube.
+    pub fn from_facelets(facelets: &str) -> Result<Self, Error> {
+        let kociemba = to_kociemba(facelets)?.into_bytes();
+        let mut cube = Self::SOLVED;
+        for (i, stickers) in CORNER_FACELETS.iter().enumerate() {
+            let colors = stickers.map(|facelet| kociemba[facelet]);
+            let twist = colors.iter().position(|&c| c == b'U' || c == b'D')
+                .ok_or_else(|| Error::Validation(format!("The corner at {} has no U or D sticker", Corner::ALL[i].name())))?;
+            let turned = [colors[twist], colors[(twist + 1) % 3], colors[(twist + 2) % 3]];
+            let corner = CORNER_FACELETS.iter().position(|solved| solved.map(|facelet| SOLVED_KOCIEMBA[facelet]) == turned)
+                .ok_or_else(|| {
+                    // The colors of a real corner in the wrong order around it, like a sticker swap
+                    let mirrored = [turned[0], turned[2], turned[1]];
+                    let what = match CORNER_FACELETS.iter().any(|solved| solved.map(|facelet| SOLVED_KOCIEMBA[facelet]) == mirrored) {
+                        true => "is mirrored",
+                        false => "isn't a piece of the cube",
+                    };
+                    Error::Validation(format!("The corner at {} {}", Corner::ALL[i].name(), what))
+                })?;
+            cube.cp[i] = Corner::ALL[corner];
+            cube.co[i] = twist as u8;
+        }
+        for (i, stickers) in EDGE_FACELETS.iter().enumerate() {
+            let colors = stickers.map(|facelet| kociemba[facelet]);
+            let (edge, flip) = EDGE_FACELETS.iter().enumerate().find_map(|(edge, solved)| {
+                let solved = solved.map(|facelet| SOLVED_KOCIEMBA[facelet]);
+                match colors {
+                    _ if colors == solved => Some((edge, 0)),
+                    [a, b] if [b, a] == solved => Some((edge, 1)),
+                    _ => None,
+                }
+            }).ok_or_else(|| Error::Validation(format!("The edge at {} isn't a piece of the cube", Edge::ALL[i].name())))?;
+            cube.ep[i] = Edge::ALL[edge];
+            cube.eo[i] = flip;
+        }
+        // Every sticker can be right with a piece missing and another one there twice
+        if let Some(corner) = repeated(&cube.cp) {
+            return Err(Error::Validation(format!("There are two {} corners", corner.name())));
+        }
+        if let Some(edge) = repeated(&cube.ep) {
+            return Err(Error::Validation(format!("There are two {} edges", edge.name())));
+        }
+        Ok(cube)
+    }
+
+    /// The facelet string of the pieces, with the colors of [SOLVED].
+    pub fn to_facelets(&self) -> String {
+        let mut kociemba = SOLVED_KOCIEMBA;
+        for (i, stickers) in CORNER_FACELETS.iter().enumerate() {
+            let solved = CORNER_FACELETS[self.cp[i] as usize];
+            for (k, &facelet) in stickers.iter().enumerate() {
+                kociemba[facelet] = SOLVED_KOCIEMBA[solved[(k + 3 - self.co[i] as usize) % 3]];
+            }
+        }
+        for (i, stickers) in EDGE_FACELETS.iter().enumerate() {
+            let solved = EDGE_FACELETS[self.ep[i] as usize];
+            for (k, &facelet) in stickers.iter().enumerate() {
+                kociemba[facelet] = SOLVED_KOCIEMBA[solved[(k + self.eo[i] as usize) % 2]];
+            }
+        }
+        super::from_kociemba(std::str::from_utf8(&kociemba).unwrap()).unwrap()
+    }
+
+    /// The pieces of a solved cube after `moves`.
+    pub fn from_moves(moves: &[Move]) -> Self {
+        moves.iter().fold(Self::SOLVED, |cube, &mv| cube * move_cube(mv))
+    }
+
+    /// The state after the moves.
+    pub fn apply(&self, moves: &[Move]) -> Self {
+        *self * Self::from_moves(moves)
+    }
+
+    /// The state that undoes this one.
+    pub fn inverse(&self) -> Self {
+        let mut inverse = Self::SOLVED;
+        for i in 0..8 {
+            let corner = self.cp[i] as usize;
+            inverse.cp[corner] = Corner::ALL[i];
+            inverse.co[corner] = (3 - self.co[i]) % 3;
+        }
+        for i in 0..12 {
+            let edge = self.ep[i] as usize;
+            inverse.ep[edge] = Edge::ALL[i];
+            inverse.eo[edge] = self.eo[i];
+        }
+        inverse
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Self::SOLVED
+    }
+
+    ///
+    /// Whether a real cube can be in this state: every piece is there once, the corner twists add up to whole turns,
+    /// the edge flips to an even number and the corner and edge permutations have the same parity.
+    ///
+    pub fn is_solvable(&self) -> bool {
+        self.check_solvable().is_ok()
+    }
+
+    /// Like [CubieCube::is_solvable], with an error telling which of the rules is broken.
+    pub fn check_solvable(&self) -> Result<(), Error> {
+        // A cycle of n pieces takes n - 1 swaps
+        let parity = |permutation: &[usize]| {
+            let mut seen = vec![false; permutation.len()];
+            let mut swaps = 0;
+            for start in 0..permutation.len() {
+                if seen[start] {
+                    continue;
+                }
+                let mut i = start;
+                while !seen[i] {
+                    seen[i] = true;
+                    i = permutation[i];
+                    swaps += 1;
+                }
+                swaps -= 1;
+            }
+            swaps % 2
+        };
+        let corners: Vec<usize> = self.cp.iter().map(|&c| c as usize).collect();
+        let edges: Vec<usize> = self.ep.iter().map(|&e| e as usize).collect();
+        if repeated(&self.cp).is_some() || repeated(&self.ep).is_some() {
+            return Err(Error::Validation("A piece is there twice".to_string()));
+        }
+        if self.co.iter().map(|&t| t as u32).sum::<u32>() % 3 != 0 {
+            return Err(Error::Validation("A corner is twisted".to_string()));
+        }
+        if self.eo.iter().map(|&f| f as u32).sum::<u32>() % 2 != 0 {
+            return Err(Error::Validation("An edge is flipped".to_string()));
+        }
+        if parity(&corners) != parity(&edges) {
+            return Err(Error::Validation("Two pieces are swapped".to_string()));
+        }
+        Ok(())
+    }
+
+    ///
+    /// The cycles the pieces move in, each starting from the position of its first piece.
+    /// Pieces that stay in place only show up if they're twisted or flipped.
+    ///
+    pub fn cycles(&self) -> Vec<Cycle> {
+        let mut cycles = vec![];
+        // Where the piece at each position goes
+        let inverse = self.inverse();
+        let mut seen = [false; 8];
+        for start in 0..8 {
+            let (mut pieces, mut twist, mut i) = (vec![], 0, start);
+            while !seen[i] {
+                seen[i] = true;
+                pieces.push(Cubie::Corner(Corner::ALL[i]));
+                twist += self.co[i];
+                i = inverse.cp[i] as usize;
+            }
+            if pieces.len() > 1 || twist % 3 != 0 {
+                cycles.push(Cycle { pieces, orientation: twist % 3 });
+            }
+        }
+        let mut seen = [false; 12];
+        for start in 0..12 {
+            let (mut pieces, mut flip, mut i) = (vec![], 0, start);
+            while !seen[i] {
+                seen[i] = true;
+                pieces.push(Cubie::Edge(Edge::ALL[i]));
+                flip += self.eo[i];
+                i = inverse.ep[i] as usize;
+            }
+            if pieces.len() > 1 || flip % 2 != 0 {
+                cycles.push(Cycle { pieces, orientation: flip % 2 });
+            }
+        }
+        cycles
+    }
+
+    /// How many times the state has to be repeated to get back to solved.
+    pub fn order(&self) -> u64 {
+        self.cycles().iter().map(Cycle::order).fold(1, lcm)
+    }
+
+    /// Positions whose piece isn't the solved one in the solved orientation.
+    pub fn affected(&self) -> Vec<Cubie> {
+        let corners = (0..8).filter(|&i| self.cp[i] != Corner::ALL[i] || self.co[i] != 0).map(|i| Cubie::Corner(Corner::ALL[i]));
+        let edges = (0..12).filter(|&i| self.ep[i] != Edge::ALL[i] || self.eo[i] != 0).map(|i| Cubie::Edge(Edge::ALL[i]));
+        corners.chain(edges).collect()
+    }
+}
+
+impl Mul for CubieCube {
+    type Output = CubieCube;
+
+    /// `self` followed by `other`.
+    fn mul(self, other: CubieCube) -> CubieCube {
+        let mut product = CubieCube::SOLVED;
+        for i in 0..8 {
+            let from = other.cp[i] as usize;
+            product.cp[i] = self.cp[from];
+            product.co[i] = (self.co[from] + other.co[i]) % 3;
+        }
+        for i in 0..12 {
+            let from = other.ep[i] as usize;
+            product.ep[i] = self.ep[from];
+            product.eo[i] = (self.eo[from] + other.eo[i]) % 2;
+        }
+        product
+    }
+}
+
+impl Cube {
+    /// The current state as pieces. A move that is still animating isn't applied yet.
+    pub fn cubies(&self) -> Result<CubieCube, Error> {
+        CubieCube::from_facelets(&self.facelet_str())
+    }
+}
+
+/// The solved cube as a Kociemba facelet string.
+const SOLVED_KOCIEMBA: [u8; 54] = *b"UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+/// Our facelet of a sticker of a Kociemba facelet string.
+fn from_kociemba_facelet(facelet: usize) -> usize {
+    KOCIEMBA_FACES[facelet / 9] * 9 + facelet % 9
+}
+
+/// The stickers of every corner position in our facelet strings, in the order twists count them.
+pub(super) fn corner_facelets() -> [[usize; 3]; 8] {
+    CORNER_FACELETS.map(|stickers| stickers.map(from_kociemba_facelet))
+}
+
+/// The stickers of every edge position in our facelet strings, in the order flips count them.
+pub(super) fn edge_facelets() -> [[usize; 2]; 12] {
+    EDGE_FACELETS.map(|stickers| stickers.map(from_kociemba_facelet))
+}
+
+/// Every move as pieces, worked out from what it does to the stickers.
+pub(super) fn move_cube(mv: Move) -> CubieCube {
+    static MOVES: OnceLock<[CubieCube; 18]> = OnceLock::new();
+    let moves = MOVES.get_or_init(|| {
+        Move::ALL.map(|mv| CubieCube::from_facelets(&apply_moves(SOLVED, &[mv]).unwrap()).unwrap())
+    });
+    moves[Move::ALL.iter().position(|&m| m == mv).unwrap()]
+}
+
+/// A piece that is in more than one position, if there is one.
+fn repeated<T: Copy + PartialEq>(pieces: &[T]) -> Option<T> {
+    pieces.iter().enumerate().find(|&(i, piece)| pieces[..i].contains(piece)).map(|(_, &piece)| piece)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+///
+/// Pieces that move into each other's places, see [CubieCube::cycles].
+/// `orientation` is how much the pieces are twisted (corners, in clockwise thirds of a turn)
+/// or flipped (edges) once they have gone all the way around.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub pieces: Vec<Cubie>,
+    pub orientation: u8,
+}
+
+impl Cycle {
+    /// How many times the cycle has to be repeated for its pieces to be back and oriented.
+    pub fn order(&self) -> u64 {
+        let modulus = match self.pieces.first() {
+            Some(Cubie::Corner(_)) => 3,
+            _ => 2,
+        };
+        let turns = if self.orientation == 0 { 1 } else { modulus };
+        self.pieces.len() as u64 * turns
+    }
+}
+
+impl fmt::Display for Cycle {
+    /// Like `(URF UBR ULB)+`, with `+` for a clockwise twist or a flip and `-` for a counter clockwise twist.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pieces = self.pieces.iter().map(|piece| piece.to_string()).collect::<Vec<_>>().join(" ");
+        let orientation = match (self.orientation, self.pieces.first()) {
+            (0, _) => "",
+            (2, Some(Cubie::Corner(_))) => "-",
+            _ => "+",
+        };
+        write!(f, "({}){}", pieces, orientation)
+    }
+}
+
+/// What an algorithm does to the pieces, see [analyze].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AlgAnalysis {
+    /// The state the algorithm leaves a solved cube in.
+    pub cube: CubieCube,
+    /// How many times it has to be repeated to get back to solved.
+    pub order: u64,
+    pub cycles: Vec<Cycle>,
+    /// Pieces that end up moved or turned.
+    pub affected: Vec<Cubie>,
+    /// Pieces that are turned by any of the moves, even if they end up back in place.
+    pub touched: Vec<Cubie>,
+}
+
+impl fmt::Display for AlgAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cycles = self.cycles.iter().map(|cycle| cycle.to_string()).collect::<Vec<_>>().join(" ");
+        write!(
+            f,
+            "order {}, {} pieces affected, {} touched: {}",
+            self.order, self.affected.len(), self.touched.len(), if cycles.is_empty() { "()" } else { &cycles },
+        )
+    }
+}
+
+/// Work out the order, cycles and pieces of an algorithm, for instance to check what a commutator swaps.
+pub fn analyze(moves: &[Move]) -> AlgAnalysis {
+    let mut cube = CubieCube::SOLVED;
+    let mut touched = vec![];
+    for &mv in moves {
+        let next = cube * move_cube(mv);
+        // The pieces that changed place are the ones in the turned layer
+        for i in 0..8 {
+            if next.cp[i] != cube.cp[i] {
+                touched.push(Cubie::Corner(cube.cp[i]));
+            }
+        }
+        for i in 0..12 {
+            if next.ep[i] != cube.ep[i] {
+                touched.push(Cubie::Edge(cube.ep[i]));
+            }
+        }
+        cube = next;
+    }
+    touched.sort();
+    touched.dedup();
+    AlgAnalysis {
+        cube,
+        order: cube.order(),
+        cycles: cube.cycles(),
+        affected: cube.affected(),
+        touched,
+    }
+}
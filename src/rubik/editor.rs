@@ -0,0 +1,329 @@
+use three_d::*;
+
+use super::algs::OPPOSITE;
+use super::graphics::piece_mesh;
+use super::instances::{PieceInstance, PieceInstances};
+use super::{Color, CubeAppearance, CubieCube, PieceMaterial, COLOR_ORDER, FACELETS};
+use crate::Error;
+
+/// Where each face goes in the net overlay, in stickers from the top left corner. Faces are in L, U, F, D, R, B order.
+const NET_OFFSETS: [(u32, u32); 6] = [(0, 3), (3, 0), (3, 3), (3, 6), (6, 3), (9, 3)];
+
+/// Size of a sticker of the net overlay in logical pixels.
+const NET_CELL: f32 = 14.0;
+
+/// Mouse movement in logical pixels between press and release under which it still counts as a click.
+const CLICK_SLOP: f32 = 4.0;
+
+///
+/// Paints the stickers of a cube one by one, to enter the state of a real cube.
+///
+/// Stickers can be clicked on the cube itself or on the net in the bottom left corner.
+/// With a color picked (keys 1 to 6, in the order of the facelet letters) a left click paints it,
+/// without one (key 0) a left click cycles through the colors. A right click clears a sticker.
+/// The frame around the net is green when the state is possible, red when it isn't and gray while
+/// stickers are left to paint.
+///
+pub struct FaceletEditor {
+    context: Context,
+    appearance: CubeAppearance,
+    facelets: [Color; 54],
+    pieces: Gm<PieceInstances, PieceMaterial>,
+    net: Vec<Gm<Rectangle, ColorMaterial>>,
+    frame: Gm<Rectangle, ColorMaterial>,
+    /// Viewport and pixel ratio the net was laid out for.
+    layout: Option<(Viewport, f32)>,
+    brush: Option<Color>,
+    pressed_at: Option<(MouseButton, LogicalPoint)>,
+    status: Result<(), Error>,
+}
+
+impl FaceletEditor {
+    /// Start editing from a facelet string, where `-` is a sticker that isn't painted yet.
+    pub fn new(context: &Context, facelets: &str, appearance: CubeAppearance) -> Self {
+        let rectangle = || {
+            Gm::new(
+                Rectangle::new(context, PhysicalPoint { x: 0.0, y: 0.0 }, degrees(0.0), 1.0, 1.0),
+                ColorMaterial {
+                    // Drawn in order, the frame first and the stickers over it
+                    render_states: RenderStates {
+                        depth_test: DepthTest::Always,
+                        write_mask: WriteMask::COLOR,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            )
+        };
+        let mut editor = Self {
+            context: context.clone(),
+            appearance,
+            facelets: [Color::None; 54],
+            pieces: pieces(context, &appearance),
+            net: (0..54).map(|_| rectangle()).collect(),
+            frame: rectangle(),
+            layout: None,
+            brush: None,
+            pressed_at: None,
+            status: Ok(()),
+        };
+        editor.load(facelets);
+        editor
+    }
+
+    /// Replace all the stickers. Characters that aren't a facelet letter are left unpainted.
+    pub fn load(&mut self, facelets: &str) {
+        let mut chars = facelets.chars();
+        for facelet in self.facelets.iter_mut() {
+            *facelet = chars.next().and_then(Color::from_char).unwrap_or_default();
+        }
+        self.rebuild();
+    }
+
+    /// The stickers as a facelet string, with `-` for the ones that aren't painted.
+    pub fn facelets(&self) -> String {
+        self.facelets.iter().map(|c| c.to_char()).collect()
+    }
+
+    /// Whether the painted state is one a real cube can be in, and why not if it isn't.
+    pub fn status(&self) -> Result<(), Error> {
+        self.status.clone()
+    }
+
+    pub fn set_appearance(&mut self, appearance: CubeAppearance) {
+        self.appearance = appearance;
+        self.pieces = pieces(&self.context, &appearance);
+        self.rebuild();
+    }
+
+    ///
+    /// Handle clicks and the color keys. Dragging is left alone for the camera control.
+    /// Returns whether any sticker changed.
+    ///
+    pub fn update(&mut self, frame_input: &mut FrameInput, camera: &Camera) -> bool {
+        let layout = (frame_input.viewport, frame_input.device_pixel_ratio);
+        if self.layout != Some(layout) {
+            self.layout = Some(layout);
+            self.update_net();
+        }
+        let mut clicks = Vec::new();
+        for event in frame_input.events.iter_mut() {
+            match event {
+                Event::MousePress { button, position, handled, .. } if !*handled => {
+                    self.pressed_at = Some((*button, *position));
+                }
+                Event::MouseRelease { button, position, .. } => {
+                    if let Some((pressed, at)) = self.pressed_at.take() {
+                        let moved = Vec2::from(*position) - Vec2::from(at);
+                        if pressed == *button && moved.magnitude() < CLICK_SLOP {
+                            clicks.push((*button, PhysicalPoint::from(*position)));
+                        }
+                    }
+                }
+                Event::KeyPress { kind, handled, .. } if !*handled => {
+                    let brush = match kind {
+                        Key::Num0 => Some(None),
+                        Key::Num1 => Some(Some(COLOR_ORDER[0])),
+                        Key::Num2 => Some(Some(COLOR_ORDER[1])),
+                        Key::Num3 => Some(Some(COLOR_ORDER[2])),
+                        Key::Num4 => Some(Some(COLOR_ORDER[3])),
+                        Key::Num5 => Some(Some(COLOR_ORDER[4])),
+                        Key::Num6 => Some(Some(COLOR_ORDER[5])),
+                        _ => None,
+                    };
+                    if let Some(brush) = brush {
+                        self.brush = brush;
+                        *handled = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut changed = false;
+        for (button, point) in clicks {
+            let facelet = self.net_facelet_at(point).or_else(|| {
+                pick(&self.context, camera, point, std::iter::once(&self.pieces)).and_then(facelet_at)
+            });
+            if let Some(facelet) = facelet {
+                self.facelets[facelet] = match (button, self.brush) {
+                    (MouseButton::Right, _) => Color::None,
+                    (_, Some(color)) => color,
+                    (_, None) => next_color(self.facelets[facelet]),
+                };
+                changed = true;
+            }
+        }
+        if changed {
+            self.rebuild();
+        }
+        changed
+    }
+
+    /// The pieces of the cube being painted.
+    pub fn objects(&self) -> impl Iterator<Item = &dyn Object> + Clone {
+        std::iter::once(&self.pieces as &dyn Object)
+    }
+
+    pub fn geometries(&self) -> impl Iterator<Item = &dyn Geometry> + Clone {
+        std::iter::once(&self.pieces as &dyn Geometry)
+    }
+
+    /// Draw the net on top of whatever is on the target.
+    pub fn render_overlay(&self, target: &RenderTarget) {
+        let camera = Camera::new_2d(target.viewport());
+        target.render(&camera, std::iter::once(&self.frame).chain(&self.net), &[]);
+    }
+
+    fn rebuild(&mut self) {
+        let mut faces = [[Color::None; 6]; 27];
+        for (facelet, &cubelet) in FACELETS.iter().enumerate() {
+            faces[cubelet][facelet / 9] = self.facelets[facelet];
+        }
+        let instances: Vec<PieceInstance> = (0..27i32).map(|i| {
+            let origin = vec3((i / 9 - 1) as f32, (1 - (i / 3) % 3) as f32, (i % 3 - 1) as f32);
+            PieceInstance {
+                transformation: Mat4::from_translation(origin),
+                colors: faces[i as usize].map(|color| color.sticker(&self.appearance.colors)),
+            }
+        }).collect();
+        self.pieces.geometry.set_colors(&instances);
+        self.pieces.geometry.set_transformations(&instances);
+
+        self.status = if self.facelets.contains(&Color::None) {
+            let left = self.facelets.iter().filter(|&&c| c == Color::None).count();
+            Err(Error::Validation(format!("{} stickers left to paint", left)))
+        } else {
+            check_facelets(&self.facelets())
+        };
+        let unpainted = Srgba::new_opaque(60, 60, 60);
+        for (rectangle, color) in self.net.iter_mut().zip(self.facelets) {
+            rectangle.material.color = color.sticker(&self.appearance.colors).unwrap_or(unpainted);
+        }
+        self.frame.material.color = match &self.status {
+            Ok(()) => Srgba::new_opaque(70, 200, 90),
+            Err(_) if self.facelets.contains(&Color::None) => Srgba::new_opaque(120, 120, 120),
+            Err(_) => Srgba::new_opaque(220, 50, 50),
+        };
+    }
+
+    /// Size of a net sticker and the corner the net starts at, in physical pixels from the bottom left.
+    fn net_metrics(&self) -> (f32, f32) {
+        let ratio = self.layout.map(|(_, ratio)| ratio).unwrap_or(1.0);
+        (NET_CELL * ratio, NET_CELL * ratio)
+    }
+
+    fn update_net(&mut self) {
+        let (cell, margin) = self.net_metrics();
+        for (facelet, rectangle) in self.net.iter_mut().enumerate() {
+            let (x, y) = net_cell(facelet);
+            rectangle.set_center(PhysicalPoint {
+                x: margin + (x as f32 + 0.5) * cell,
+                y: margin + (8.5 - y as f32) * cell,
+            });
+            rectangle.set_size(cell * 0.85, cell * 0.85);
+        }
+        self.frame.set_center(PhysicalPoint { x: margin + 6.0 * cell, y: margin + 4.5 * cell });
+        self.frame.set_size(12.0 * cell + margin * 0.5, 9.0 * cell + margin * 0.5);
+    }
+
+    fn net_facelet_at(&self, point: PhysicalPoint) -> Option<usize> {
+        let (cell, margin) = self.net_metrics();
+        let x = (point.x - margin) / cell;
+        let y = 9.0 - (point.y - margin) / cell;
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+        let (x, y) = (x as u32, y as u32);
+        NET_OFFSETS.iter().enumerate().find_map(|(face, &(fx, fy))| {
+            let inside = (fx..fx + 3).contains(&x) && (fy..fy + 3).contains(&y);
+            inside.then(|| face * 9 + ((y - fy) * 3 + x - fx) as usize)
+        })
+    }
+}
+
+/// Position of a facelet in the net, in stickers from the top left corner.
+fn net_cell(facelet: usize) -> (u32, u32) {
+    let (fx, fy) = NET_OFFSETS[facelet / 9];
+    let i = (facelet % 9) as u32;
+    (fx + i % 3, fy + i / 3)
+}
+
+fn next_color(color: Color) -> Color {
+    match COLOR_ORDER.iter().position(|&c| c == color) {
+        Some(i) if i + 1 < COLOR_ORDER.len() => COLOR_ORDER[i + 1],
+        Some(_) => Color::None,
+        None => COLOR_ORDER[0],
+    }
+}
+
+///
+/// The facelet under a point on the surface of an unturned cube centered at the origin.
+///
+pub fn facelet_at(point: Vec3) -> Option<usize> {
+    let axis = (0..3).max_by(|&a, &b| point[a].abs().total_cmp(&point[b].abs()))?;
+    let sign = point[axis].signum() as i32;
+    let face = match (axis, sign) {
+        (0, -1) => 0,
+        (1, 1) => 1,
+        (2, 1) => 2,
+        (1, -1) => 3,
+        (0, 1) => 4,
+        (2, -1) => 5,
+        _ => return None,
+    };
+    let mut cell = [0i32; 3];
+    for (i, c) in cell.iter_mut().enumerate() {
+        *c = if i == axis { sign } else { (point[i].round() as i32).clamp(-1, 1) };
+    }
+    let cubelet = ((cell[0] + 1) * 9 + (1 - cell[1]) * 3 + (cell[2] + 1)) as usize;
+    FACELETS[face * 9..face * 9 + 9].iter()
+        .position(|&c| c == cubelet)
+        .map(|i| face * 9 + i)
+}
+
+///
+/// Check that a facelet string is a state a real cube can be in.
+///
+/// On top of [Cube::validate_facelet_str](super::Cube::validate_facelet_str) this catches
+/// centers with the same color, stickers that can't be on the same piece, pieces that show up twice,
+/// corners with their colors mirrored and the states [CubieCube::check_solvable] rules out.
+///
+pub fn check_facelets(facelets: &str) -> Result<(), Error> {
+    super::Cube::validate_facelet_str(facelets)?;
+    let colors: Vec<Color> = facelets.chars().filter_map(Color::from_char).collect();
+    // The face every color belongs on, going by the centers
+    let mut home = [None; 6];
+    for face in 0..6 {
+        let center = colors[face * 9 + 4] as usize;
+        if home[center].replace(face).is_some() {
+            return Err(Error::Validation(format!("Two centers are {}", colors[face * 9 + 4].to_char())));
+        }
+    }
+
+    let mut seen: Vec<Vec<usize>> = Vec::new();
+    for cubelet in (0..27).filter(|&c| c != 13) {
+        let stickers: Vec<usize> = (0..54).filter(|&f| FACELETS[f] == cubelet && f % 9 != 4).collect();
+        if stickers.is_empty() {
+            continue;
+        }
+        let mut faces: Vec<usize> = stickers.iter().map(|&f| home[colors[f] as usize].unwrap()).collect();
+        let name = || stickers.iter().map(|&f| colors[f].to_char()).collect::<String>();
+        faces.sort_unstable();
+        let impossible = faces.iter().enumerate()
+            .any(|(i, &a)| faces[i + 1..].iter().any(|&b| a == b || OPPOSITE[a] == b));
+        if impossible {
+            return Err(Error::Validation(format!("No piece has the colors {}", name())));
+        }
+        if seen.contains(&faces) {
+            return Err(Error::Validation(format!("The piece {} is there twice", name())));
+        }
+        seen.push(faces);
+    }
+    CubieCube::from_facelets(facelets)?.check_solvable()
+}
+
+/// The pieces of the cube, before anything is painted.
+fn pieces(context: &Context, appearance: &CubeAppearance) -> Gm<PieceInstances, PieceMaterial> {
+    Gm::new(PieceInstances::new(context, &piece_mesh(appearance), &[]), PieceMaterial::from(appearance))
+}
@@ -0,0 +1,103 @@
+use super::{parse_alg, Cube, Move, SOLVED};
+use crate::Error;
+
+/// Our faces (L, U, F, D, R, B) in the order of Kociemba's facelet strings: U, R, F, D, L, B.
+pub(super) const KOCIEMBA_FACES: [usize; 6] = [1, 4, 2, 3, 0, 5];
+
+/// Face letters in our order.
+const FACE_LETTERS: [u8; 6] = *b"LUFDRB";
+
+///
+/// Convert a facelet string to the order used by Kociemba's solver and most other cube software:
+/// the U, R, F, D, L and B faces, each sticker named after the face whose center has its color.
+/// Every face is read in the same order as in our own facelet strings.
+///
+pub fn to_kociemba(facelets: &str) -> Result<String, Error> {
+    Cube::validate_facelet_str(facelets)?;
+    let facelets = facelets.as_bytes();
+    let centers: [u8; 6] = std::array::from_fn(|face| facelets[face * 9 + 4]);
+    let letters: Vec<u8> = KOCIEMBA_FACES.iter().flat_map(|&face| {
+        facelets[face * 9..face * 9 + 9].iter().map(|color| FACE_LETTERS[centers.iter().position(|c| c == color).unwrap()])
+    }).collect();
+    Ok(String::from_utf8(letters).unwrap())
+}
+
+/// Convert a Kociemba facelet string back to our own, with the colors of [SOLVED].
+pub fn from_kociemba(kociemba: &str) -> Result<String, Error> {
+    if kociemba.len() != 54 {
+        return Err(Error::Parse("Invalid string length".to_string()));
+    }
+    let kociemba = kociemba.as_bytes();
+    let mut facelets = vec![0; 54];
+    for (block, &face) in KOCIEMBA_FACES.iter().enumerate() {
+        if kociemba[block * 9 + 4] != FACE_LETTERS[face] {
+            return Err(Error::Parse(format!("Expected the center of face {} to be {}", block, FACE_LETTERS[face] as char)));
+        }
+        for i in 0..9 {
+            let letter = kociemba[block * 9 + i];
+            let color_of = FACE_LETTERS.iter().position(|&l| l == letter)
+                .ok_or_else(|| Error::Parse(format!("Invalid char {}", letter as char)))?;
+            facelets[face * 9 + i] = SOLVED.as_bytes()[color_of * 9];
+        }
+    }
+    let facelets = String::from_utf8(facelets).unwrap();
+    Cube::validate_facelet_str(&facelets)?;
+    Ok(facelets)
+}
+
+fn notation(moves: &[Move]) -> String {
+    moves.iter().map(|mv| mv.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+/// A link that shows `alg` on alg.cubing.net, applied to a cube scrambled with `setup`.
+pub fn alg_cubing_url(setup: &[Move], alg: &[Move]) -> String {
+    // alg.cubing.net writes spaces as underscores and primes as dashes to keep links readable
+    let encode = |moves: &[Move]| notation(moves).replace(' ', "_").replace('\'', "-");
+    format!("https://alg.cubing.net/?setup={}&alg={}", encode(setup), encode(alg))
+}
+
+/// The same as [alg_cubing_url] for twizzle, the player that replaces alg.cubing.net.
+pub fn twizzle_url(setup: &[Move], alg: &[Move]) -> String {
+    let encode = |moves: &[Move]| notation(moves).replace(' ', "+").replace('\'', "%27");
+    format!("https://alpha.twizzle.net/edit/?puzzle=3x3x3&setup-alg={}&alg={}", encode(setup), encode(alg))
+}
+
+///
+/// The setup and the algorithm of an alg.cubing.net or twizzle link, as face turns.
+/// Either can be missing from the link, which gives no moves. Wide turns, slices and rotations
+/// are rewritten like [parse_alg] does.
+///
+pub fn parse_alg_url(url: &str) -> Result<(Vec<Move>, Vec<Move>), Error> {
+    let query = url.split_once('?').map_or(url, |(_, query)| query);
+    let query = query.split('#').next().unwrap_or_default();
+    let (mut setup, mut alg) = (vec![], vec![]);
+    for pair in query.split('&') {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let moves = match key {
+            "setup" | "setup-alg" => &mut setup,
+            "alg" => &mut alg,
+            _ => continue,
+        };
+        let value = percent_decode(&value.replace('+', " "))?.replace('_', " ").replace('-', "'");
+        *moves = parse_alg(&value)?;
+    }
+    Ok((setup, alg))
+}
+
+fn percent_decode(s: &str) -> Result<String, Error> {
+    let mut bytes = vec![];
+    let mut rest = s.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2).and_then(|hex| std::str::from_utf8(hex).ok());
+            let decoded = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| Error::Parse(format!("Invalid escape in {}", s)))?;
+            bytes.push(decoded);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| Error::Parse(format!("Invalid text in {}", s)))
+}
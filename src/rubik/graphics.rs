@@ -1,13 +1,42 @@
 use crate::control::{SmoothOrbitControl, SmoothOrbitControlSettings};
-use crate::WindowLike;
+use crate::postprocessing::{PostEffect, PostProcessing};
+use crate::scene::Scene;
+use crate::Error;
 use three_d::*;
 
-use super::{CubeAnimationOptions, Move};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
 
-#[derive(Clone)]
+use super::instances::PieceMesh;
+use super::timer::unix_time;
+use super::{
+    apply_moves, scramble, Cube, CubeAnimationOptions, CubeAppearance, CubeCommand, CubeRemote, FaceletEditor, Move, Penalty,
+    MaskPreset, Playback, Reconstruction, Replay, StickerStyle, Timer, TimerPhase, SOLVED,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RubikMaterial {
     pub margin: f32,
     pub radius: f32,
+    /// Color the whole face instead of drawing a sticker on it.
+    pub stickerless: bool,
+    /// Color of the plastic around the stickers and on the inner faces.
+    pub body_color: Srgba,
+    /// Color of the stickers a [StickerMask](super::StickerMask) ignores.
+    pub masked_color: Srgba,
+    /// Roughness and metallic factors of the stickers, only used when rendering with lights.
+    pub sticker_roughness: f32,
+    pub sticker_metallic: f32,
+    /// Roughness and metallic factors of the plastic, only used when rendering with lights.
+    pub body_roughness: f32,
+    pub body_metallic: f32,
+    /// Below 1.0 the material is drawn see-through, which also needs blending in the render states.
+    pub opacity: f32,
+    /// Not saved, it's up to whoever renders the material.
+    #[serde(skip)]
     pub render_states: RenderStates,
 }
 
@@ -16,64 +45,194 @@ impl Default for RubikMaterial {
         Self {
             margin: 0.2,
             radius: 0.1,
+            stickerless: false,
+            body_color: Srgba::BLACK,
+            masked_color: CubeAppearance::default().masked_color,
+            sticker_roughness: 0.6,
+            sticker_metallic: 0.0,
+            body_roughness: 0.35,
+            body_metallic: 0.0,
+            opacity: 1.0,
             render_states: RenderStates::default(),
         }
     }
 }
 
-//TODO: Rewrite UV coordinates for CpuMesh::cube()
-// CpuMesh::cube() face order is: up, down, back, front, right, left
-pub(crate) fn cube_uvs() -> Vec<Vec2> {vec![
-    Vec2::new(1.0, 1.0),
-    Vec2::new(0.0, 1.0),
-    Vec2::new(1.0, 0.0),
-    Vec2::new(0.0, 0.0),
-    Vec2::new(1.0, 0.0),
-    Vec2::new(0.0, 1.0),
-
-    Vec2::new(0.0, 1.0),
-    Vec2::new(1.0, 1.0),
-    Vec2::new(1.0, 0.0),
-    Vec2::new(1.0, 0.0),
-    Vec2::new(0.0, 0.0),
-    Vec2::new(0.0, 1.0),
-
-    Vec2::new(1.0, 0.0),
-    Vec2::new(0.0, 0.0),
-    Vec2::new(1.0, 1.0),
-    Vec2::new(0.0, 1.0),
-    Vec2::new(1.0, 1.0),
-    Vec2::new(0.0, 0.0),
-
-    Vec2::new(0.0, 0.0),
-    Vec2::new(1.0, 0.0),
-    Vec2::new(1.0, 1.0),
-    Vec2::new(1.0, 1.0),
-    Vec2::new(0.0, 1.0),
-    Vec2::new(0.0, 0.0),
-
-    Vec2::new(1.0, 0.0),
-    Vec2::new(1.0, 1.0),
-    Vec2::new(0.0, 1.0),
-    Vec2::new(0.0, 1.0),
-    Vec2::new(0.0, 0.0),
-    Vec2::new(1.0, 0.0),
-
-    Vec2::new(1.0, 1.0),
-    Vec2::new(1.0, 0.0),
-    Vec2::new(0.0, 1.0),
-    Vec2::new(0.0, 0.0),
-    Vec2::new(0.0, 1.0),
-    Vec2::new(1.0, 0.0),
-]}
+impl RubikMaterial {
+    /// See-through stickers with nothing around them, for [CubeAppearance::hint_facelets].
+    pub fn hints(appearance: &CubeAppearance) -> Self {
+        Self {
+            body_color: Srgba::new(0, 0, 0, 0),
+            opacity: appearance.hint_opacity.clamp(0.0, 1.0),
+            render_states: RenderStates {
+                write_mask: WriteMask::COLOR,
+                blend: Blend::TRANSPARENCY,
+                ..Default::default()
+            },
+            ..Self::from(appearance)
+        }
+    }
+}
+
+impl From<&CubeAppearance> for RubikMaterial {
+    fn from(appearance: &CubeAppearance) -> Self {
+        Self {
+            margin: appearance.sticker_margin,
+            radius: appearance.sticker_radius,
+            stickerless: appearance.stickerless,
+            body_color: appearance.body_color,
+            masked_color: appearance.masked_color,
+            sticker_roughness: appearance.sticker_roughness,
+            sticker_metallic: appearance.sticker_metallic,
+            body_roughness: appearance.body_roughness,
+            body_metallic: appearance.body_metallic,
+            ..Default::default()
+        }
+    }
+}
+
+/// Face normals and in-plane axes in the cube's face order (L, U, F, D, R, B).
+/// The two axes are picked so `u x v` points outwards, keeping triangles counter-clockwise.
+pub(crate) const FACE_AXES: [(Vec3, Vec3, Vec3); 6] = [
+    (vec3(-1.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0), vec3(0.0, 1.0, 0.0)),
+    (vec3(0.0, 1.0, 0.0), vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, -1.0)),
+    (vec3(0.0, 0.0, 1.0), vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+    (vec3(0.0, -1.0, 0.0), vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0)),
+    (vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, -1.0), vec3(0.0, 1.0, 0.0)),
+    (vec3(0.0, 0.0, -1.0), vec3(-1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+];
+
+/// Alpha of the vertex color of ignored stickers, which rubik.frag draws with the masked color.
+const IGNORED_ALPHA: u8 = 160;
+
+/// The vertex color of a sticker drawn with `style`.
+pub(crate) fn styled_sticker(color: Srgba, style: StickerStyle) -> Srgba {
+    match style {
+        StickerStyle::Regular => color,
+        StickerStyle::Dim => Srgba::new(color.r / 3, color.g / 3, color.b / 3, 255),
+        StickerStyle::Ignored => Srgba::new(0, 0, 0, IGNORED_ALPHA),
+    }
+}
+
+/// Same test as `sticker()` in rubik.frag, with `p` in the [-1, 1] face space.
+fn in_sticker(p: Vec2, margin: f32, radius: f32) -> bool {
+    let inner = 1.0 - margin;
+    let (x, y) = (p.x.abs(), p.y.abs());
+    let eps = 1e-4;
+    (x <= inner + eps && y <= inner + radius + eps)
+        || (x <= inner + radius + eps && y <= inner + eps)
+        || (x - inner).powi(2) + (y - inner).powi(2) <= radius * radius + eps
+}
+
+///
+/// The hint facelets of a piece: flat stickers in front of every face, moved away from the cube by
+/// [CubeAppearance::hint_distance]. Only the faces that get a color are seen.
+///
+pub(crate) fn hint_mesh(appearance: &CubeAppearance) -> PieceMesh {
+    let mut mesh = PieceMesh { positions: vec![], normals: vec![], uvs: vec![], faces: vec![], sticker_offsets: vec![], indices: vec![] };
+    for (face, &(normal, u_axis, v_axis)) in FACE_AXES.iter().enumerate() {
+        let center = normal * (0.5 + appearance.hint_distance);
+        let base = mesh.positions.len() as u32;
+        for (u, v) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            mesh.positions.push(center + (u_axis * u + v_axis * v) * 0.5);
+            mesh.normals.push(normal);
+            mesh.uvs.push(Vec2::new((u + 1.0) / 2.0, (v + 1.0) / 2.0));
+            mesh.faces.push(face as f32);
+            mesh.sticker_offsets.push(Vec3::zero());
+        }
+        mesh.indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+    mesh
+}
+
+///
+/// Build the mesh all pieces are drawn with, centered at the origin.
+///
+/// Every face is a grid that gets projected onto a rounded box, so a bevel of zero gives back
+/// a plain cube. When the sticker depth is not zero the sticker area of every face gets an offset
+/// along the surface normal, which the shader only applies to faces with a color.
+///
+pub(crate) fn piece_mesh(appearance: &CubeAppearance) -> PieceMesh {
+    let half = (0.5 - appearance.gap / 2.0).max(0.01);
+    let bevel = appearance.bevel.clamp(0.0, half);
+    let segments = appearance.bevel_segments.max(1);
+    let core = half - bevel;
+    let extrude = !appearance.stickerless && appearance.sticker_depth != 0.0;
+
+    let mut mesh = PieceMesh { positions: vec![], normals: vec![], uvs: vec![], faces: vec![], sticker_offsets: vec![], indices: vec![] };
+    for (face, &(normal, u_axis, v_axis)) in FACE_AXES.iter().enumerate() {
+        // Grid lines along one face axis, denser where the bevel curves
+        let mut steps = vec![-half, half];
+        for k in 0..=segments {
+            let angle = k as f32 / segments as f32 * std::f32::consts::FRAC_PI_4;
+            let s = core + bevel * angle.tan();
+            steps.extend([s, -s]);
+        }
+        if extrude {
+            // Extra lines around the sticker edge keep its walls steep
+            let inner = 1.0 - appearance.sticker_margin;
+            let outer = inner + appearance.sticker_radius;
+            for s in [inner, outer, outer + 1e-3] {
+                steps.extend([s * half, -s * half]);
+            }
+        }
+        steps.retain(|s| s.abs() <= half);
+        steps.sort_by(|a, b| a.total_cmp(b));
+        steps.dedup_by(|a, b| (*a - *b).abs() < 1e-5);
+
+        let base = mesh.positions.len() as u32;
+        let n = steps.len() as u32;
+        for &sv in &steps {
+            for &su in &steps {
+                let flat = normal * half + u_axis * su + v_axis * sv;
+                let inner = flat.map(|c| c.clamp(-core, core));
+                let offset = flat - inner;
+                let surface_normal = if offset.magnitude() > 1e-6 { offset.normalize() } else { normal };
+                let face_coords = Vec2::new(su / half, sv / half);
+                let in_sticker = extrude && in_sticker(face_coords, appearance.sticker_margin, appearance.sticker_radius);
+                mesh.positions.push(inner + surface_normal * bevel);
+                mesh.normals.push(surface_normal);
+                mesh.uvs.push((face_coords + Vec2::new(1.0, 1.0)) / 2.0);
+                mesh.faces.push(face as f32);
+                mesh.sticker_offsets.push(if in_sticker { surface_normal * appearance.sticker_depth } else { Vec3::zero() });
+            }
+        }
+        for j in 0..n - 1 {
+            for i in 0..n - 1 {
+                let a = base + j * n + i;
+                let b = a + 1;
+                let c = a + n + 1;
+                let d = a + n;
+                mesh.indices.extend([a, b, c, a, c, d]);
+            }
+        }
+    }
+    mesh
+}
+
+/// Lighting model used when the cube is rendered with lights.
+const LIGHTING_MODEL: LightingModel = LightingModel::Cook(
+    NormalDistributionFunction::TrowbridgeReitzGGX,
+    GeometryFunction::SmithSchlickGGX,
+);
 
+///
+/// Renders pieces with the procedural sticker from rubik.frag.
+/// Without lights the colors are used as they are. When lights are given the stickers and
+/// the plastic are shaded physically, each with their own roughness and metallic factors.
+///
 impl Material for RubikMaterial {
     fn id(&self) -> u16 {
         0u16
     }
 
-    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+    fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
         let mut shader = String::new();
+        if !lights.is_empty() {
+            shader.push_str("#define LIT\n");
+            shader.push_str(&lights_shader_source(lights, LIGHTING_MODEL));
+            shader.push_str(ToneMapping::fragment_shader_source());
+        }
         shader.push_str(ColorMapping::fragment_shader_source());
         shader.push_str(include_str!("rubik.frag"));
         shader
@@ -81,6 +240,7 @@ impl Material for RubikMaterial {
 
     fn fragment_attributes(&self) -> FragmentAttributes {
         FragmentAttributes {
+            position: true,
             color: true,
             normal: true,
             uv: true,
@@ -88,9 +248,22 @@ impl Material for RubikMaterial {
         }
     }
 
-    fn use_uniforms(&self, program: &Program, camera: &Camera, _: &[&dyn Light]) {
+    fn use_uniforms(&self, program: &Program, camera: &Camera, lights: &[&dyn Light]) {
         camera.color_mapping.use_uniforms(program);
+        if !lights.is_empty() {
+            camera.tone_mapping.use_uniforms(program);
+            program.use_uniform_if_required("cameraPosition", camera.position());
+            for (i, light) in lights.iter().enumerate() {
+                light.use_uniforms(program, i as u32);
+            }
+            program.use_uniform("stickerSurface", Vec2::new(self.sticker_roughness, self.sticker_metallic));
+            program.use_uniform("bodySurface", Vec2::new(self.body_roughness, self.body_metallic));
+        }
         program.use_uniform("stickerData", Vec2::new(1.0 - self.margin, self.radius));
+        program.use_uniform("bodyColor", Vec4::from(self.body_color));
+        program.use_uniform("maskedColor", Vec4::from(self.masked_color));
+        program.use_uniform("stickerless", if self.stickerless { 1.0f32 } else { 0.0 });
+        program.use_uniform("opacity", self.opacity);
     }
 
     fn render_states(&self) -> RenderStates {
@@ -98,53 +271,505 @@ impl Material for RubikMaterial {
     }
 
     fn material_type(&self) -> MaterialType {
-        MaterialType::Opaque
+        if self.opacity < 1.0 || self.body_color.a < 255 {
+            MaterialType::Transparent
+        } else {
+            MaterialType::Opaque
+        }
+    }
+}
+
+///
+/// The default lights of the demo scene: a soft studio environment for ambient light and
+/// reflections, a bright key light from the top right and a dim, cooler fill light from the left.
+///
+pub struct LightRig {
+    pub ambient: AmbientLight,
+    pub key: DirectionalLight,
+    pub fill: DirectionalLight,
+    /// Whether the key light casts shadows. The shadow map is regenerated on every [LightRig::update].
+    pub shadows: bool,
+    pub shadow_map_size: u32,
+}
+
+impl LightRig {
+    pub fn new(context: &Context) -> Self {
+        let environment = studio_environment(context);
+        Self {
+            ambient: AmbientLight::new_with_environment(context, 0.8, Srgba::WHITE, &environment),
+            key: DirectionalLight::new(context, 2.0, Srgba::WHITE, &vec3(-1.2, -1.0, -0.2)),
+            fill: DirectionalLight::new(context, 0.5, Srgba::new_opaque(200, 215, 255), &vec3(0.1, -0.2, -1.1)),
+            shadows: false,
+            shadow_map_size: 1024,
+        }
+    }
+
+    /// Update the shadow map of the key light, or clear it if shadows are turned off.
+    pub fn update<G: Geometry>(&mut self, geometries: impl IntoIterator<Item = G> + Clone) {
+        if self.shadows {
+            self.key.generate_shadow_map(self.shadow_map_size, geometries);
+        } else if self.key.shadow_map().is_some() {
+            self.key.clear_shadow_map();
+        }
+    }
+
+    pub fn lights(&self) -> [&dyn Light; 3] {
+        [&self.ambient, &self.key, &self.fill]
     }
 }
 
-pub fn run(window: &impl WindowLike) -> impl 'static + FnMut(FrameInput) -> FrameOutput {
-    let context = window.gl();
-
-    let mut camera = Camera::new_perspective(
-        window.viewport(),
-        Vec3::new(4.5, 0.0, 4.5),
-        Vec3::new(0.0, 0.0, 0.0),
-        Vec3::new(0.0, 1.0, 0.0),
-        degrees(45.0),
-        0.1,
-        50.0,
-    );
-
-    let mut control = SmoothOrbitControl::new(
-        *camera.target(),
-        &camera,
-        SmoothOrbitControlSettings {
-            min_zoom: 4.0,
+/// A small cube map that is bright on top and dark at the bottom, like a photo studio.
+fn studio_environment(context: &Context) -> TextureCubeMap {
+    const SIZE: u32 = 16;
+    let side = |top: [u8; 3], bottom: [u8; 3]| {
+        let data = (0..SIZE).flat_map(|row| {
+            let t = row as f32 / (SIZE - 1) as f32;
+            let c = [0, 1, 2].map(|i| (top[i] as f32 * (1.0 - t) + bottom[i] as f32 * t) as u8);
+            std::iter::repeat_n([c[0], c[1], c[2], 255], SIZE as usize)
+        }).collect();
+        CpuTexture {
+            data: TextureData::RgbaU8(data),
+            width: SIZE,
+            height: SIZE,
             ..Default::default()
-        },
-    );
-
-
-    // x axis red
-    // y axis green
-    // z axis blue
-    let axes = Axes::new(&context, 0.08, 5.0);
-    let mut cooler_rubik = super::Cube::solved(CubeAnimationOptions::default(), &context);
-    // let mut cooler_rubik = super::Cube::debug(&context, &texture);
-    cooler_rubik.queue([Move::L, Move::F, Move::L2, Move::UP, Move::B, Move::RP, Move::LP].into_iter());
-
-    move |mut frame_input| {
-        cooler_rubik.animate(frame_input.accumulated_time as f32);
-
-        frame_input
-            .screen()
-            .clear(ClearState::color_and_depth(0.2, 0.2, 0.2, 0.8, 1.0))
-            .render(&camera, cooler_rubik.into_iter().chain(&axes), &[]);
-            // .render(&camera, &test_cube, &[]);
-            // .render(&camera, rubik.into_iter().chain(&axes), &[]);
+        }
+    };
+    let sky = [230, 232, 240];
+    let floor = [35, 35, 38];
+    let wall = side(sky, floor);
+    TextureCubeMap::new(context, &wall, &wall, &side(sky, sky), &side(floor, floor), &wall, &wall)
+}
+
+///
+/// The Rubik's cube demo: a cube going through a few moves, with an orbiting camera,
+/// the default [LightRig] and post-processing.
+/// The hidden faces show as hint facelets, H hides them. Press M to go through the stickerings of [MaskPreset].
+///
+pub struct RubikScene {
+    context: Context,
+    camera: Camera,
+    control: SmoothOrbitControl,
+    axes: Axes,
+    lights: LightRig,
+    post: PostProcessing,
+    cube: Cube,
+    remote: Rc<RefCell<CubeRemote>>,
+    /// Set while the stickers are being painted, the cube is hidden meanwhile.
+    editor: Option<FaceletEditor>,
+    timer: Timer,
+    /// Set while the timer is in use, to the animation speed and playback from before.
+    timing: Option<(f32, Playback)>,
+    /// The recording of the last timed solve.
+    reconstruction: Option<Reconstruction>,
+    /// The solve being played back, with the animation speed and playback from before.
+    replay: Option<(Replay, f32, Playback)>,
+    /// The stickering M switches to next.
+    stickering: MaskPreset,
+    idle: bool,
+}
+
+/// Animation speed while timing, so keyboard turns keep up with the fingers.
+const TIMING_SPEED: f32 = 12.0;
+
+/// Playback while timing, a turn typed while two others wait finishes the one that is turning.
+const TIMING_PLAYBACK: Playback = Playback::Responsive { max_pending: 2 };
+
+/// Keys that turn the faces while timing, with shift for counter clockwise turns. Faces are in L, U, F, D, R, B order.
+const FACE_KEYS: [Key; 6] = [Key::L, Key::U, Key::F, Key::D, Key::R, Key::B];
+
+impl RubikScene {
+    pub fn new(context: &Context, viewport: Viewport) -> Self {
+        let camera = Camera::new_perspective(
+            viewport,
+            Vec3::new(4.5, 0.0, 4.5),
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            degrees(45.0),
+            0.1,
+            50.0,
+        );
+
+        let control = SmoothOrbitControl::new(
+            *camera.target(),
+            &camera,
+            SmoothOrbitControlSettings {
+                min_zoom: 4.0,
+                ..Default::default()
+            },
+        );
+
+        let mut cube = Cube::solved(CubeAnimationOptions::default(), context);
+        cube.set_appearance(CubeAppearance { hint_facelets: true, ..Default::default() });
+        cube.queue([Move::L, Move::F, Move::L2, Move::UP, Move::B, Move::RP, Move::LP].into_iter());
+
+        Self {
+            context: context.clone(),
+            camera,
+            control,
+            // x axis red
+            // y axis green
+            // z axis blue
+            axes: Axes::new(context, 0.08, 5.0),
+            lights: LightRig::new(context),
+            post: PostProcessing::with_context(context, viewport, PostEffect::defaults()),
+            cube,
+            remote: Rc::new(RefCell::new(CubeRemote::default())),
+            editor: None,
+            timer: Timer::new(),
+            timing: None,
+            reconstruction: None,
+            replay: None,
+            stickering: MaskPreset::Full,
+            idle: false,
+        }
+    }
+
+    /// Control the cube through `remote` while the scene is running.
+    pub fn with_remote(mut self, remote: Rc<RefCell<CubeRemote>>) -> Self {
+        {
+            let mut shared = remote.borrow_mut();
+            shared.set_state(self.state());
+            shared.set_solution_known(self.cube.solution().is_some());
+        }
+        self.remote = remote;
+        self
+    }
+
+    /// The facelet string of the cube, or of the stickers painted so far in the editor.
+    fn state(&self) -> String {
+        match &self.editor {
+            Some(editor) => editor.facelets(),
+            None => self.cube.facelet_str(),
+        }
+    }
+
+    /// Start in the editor with all stickers unpainted.
+    pub fn editing(mut self) -> Self {
+        self.editor = Some(FaceletEditor::new(&self.context, "", self.cube.appearance()));
+        self
+    }
+
+    /// Start with the timer.
+    pub fn timing(mut self) -> Self {
+        self.time();
+        self
+    }
+
+    ///
+    /// Time solves with the spacebar, see [Timer]. Press T to start and to stop.
+    ///
+    /// Every solve starts from a new scramble, which is applied right away. The faces can be turned
+    /// with the L, U, F, D, R and B keys, holding shift for counter clockwise turns, and turns typed
+    /// faster than they animate hurry the ones before them along, see [Playback::Responsive].
+    /// Keys 1, 2 and 3 change the penalty of the last solve to none, +2 and DNF. Solves are recorded
+    /// while the timer runs, and P plays the last one back.
+    ///
+    pub fn time(&mut self) {
+        if self.timing.is_none() {
+            self.stop_replay();
+            self.editor = None;
+            self.timing = Some((self.cube.speed(), self.cube.playback()));
+            self.cube.set_speed(TIMING_SPEED);
+            self.cube.set_playback(TIMING_PLAYBACK);
+        }
+    }
+
+    /// Leave the timer. The solves are kept for the next time.
+    pub fn stop_timing(&mut self) {
+        if let Some((speed, playback)) = self.timing.take() {
+            self.stop_replay();
+            self.cube.set_speed(speed);
+            self.cube.set_playback(playback);
+            self.cube.stop_recording();
+            self.timer.cancel();
+        }
+    }
+
+    /// Paint the stickers of the cube, see [FaceletEditor]. Press E to start and Enter to apply.
+    pub fn edit(&mut self) {
+        if self.editor.is_none() {
+            self.stop_timing();
+            self.editor = Some(FaceletEditor::new(&self.context, &self.cube.facelet_str(), self.cube.appearance()));
+        }
+    }
+
+    /// Build the cube from the painted stickers and leave the editor, unless they don't make a possible state.
+    pub fn apply(&mut self) -> Result<(), Error> {
+        let Some(editor) = &self.editor else {
+            return Ok(());
+        };
+        editor.status()?;
+        let mut cube = Cube::from_facelet_str(editor.facelets(), CubeAnimationOptions::default(), &self.context)?;
+        cube.set_appearance(self.cube.appearance());
+        cube.set_speed(self.cube.speed());
+        cube.set_mask(self.cube.mask());
+        self.cube = cube;
+        self.editor = None;
+        Ok(())
+    }
+
+    fn run_command(&mut self, command: CubeCommand) {
+        if let Some(editor) = &mut self.editor {
+            match command {
+                CubeCommand::SetState(state) => return editor.load(&state),
+                CubeCommand::SetColors(colors) => {
+                    let mut appearance = self.cube.appearance();
+                    appearance.colors = colors;
+                    editor.set_appearance(appearance);
+                }
+                CubeCommand::SetAppearance(appearance) => editor.set_appearance(appearance),
+                CubeCommand::Queue(_) | CubeCommand::QueueGroup(_) | CubeCommand::Scramble(_) | CubeCommand::Solve => {
+                    return warn!("The cube can't be turned in the editor");
+                }
+                _ => {}
+            }
+        }
+        match command {
+            CubeCommand::Queue(moves) => self.cube.queue(moves.into_iter()),
+            CubeCommand::QueueGroup(group) => self.cube.queue_group(group),
+            CubeCommand::SetState(state) => {
+                if let Err(e) = self.cube.set_state(&state) {
+                    warn!("Can't set the cube state: {}", e);
+                }
+            }
+            CubeCommand::Scramble(seed) => self.cube.queue(scramble(seed, 25).into_iter()),
+            CubeCommand::Solve => match self.cube.solution() {
+                Some(moves) => self.cube.queue(moves.into_iter()),
+                None => warn!("The solution is only known for cubes that were solved since their state was set"),
+            },
+            CubeCommand::Pause => self.cube.pause(),
+            CubeCommand::Resume => self.cube.resume(),
+            CubeCommand::SetSpeed(speed) => self.cube.set_speed(speed),
+            CubeCommand::SetPlayback(playback) => self.cube.set_playback(playback),
+            CubeCommand::SetColors(colors) => {
+                let mut appearance = self.cube.appearance();
+                appearance.colors = colors;
+                self.cube.set_appearance(appearance);
+            }
+            CubeCommand::SetAppearance(appearance) => self.cube.set_appearance(appearance),
+            CubeCommand::SetMask(mask) => self.cube.set_mask(mask),
+            CubeCommand::Edit => self.edit(),
+            CubeCommand::Apply => {
+                if let Err(e) = self.apply() {
+                    warn!("Can't apply the edited state: {}", e);
+                }
+            }
+            CubeCommand::Time => self.time(),
+            CubeCommand::StopTiming => self.stop_timing(),
+            CubeCommand::SetSession(session) => self.timer.set_session(session),
+            CubeCommand::Penalize(penalty) => self.timer.penalize(penalty),
+            CubeCommand::Replay(reconstruction, speed) => self.start_replay(reconstruction, speed),
+        }
+    }
+
+    /// Play a recorded solve back at its own pace, with every move as long as [Replay::move_time].
+    fn start_replay(&mut self, reconstruction: Reconstruction, speed: f32) {
+        self.stop_replay();
+        let replay = Replay::new(reconstruction, speed);
+        let before = (self.cube.speed(), self.cube.playback());
+        self.cube.set_playback(Playback::Fixed);
+        self.cube.set_speed(self.cube.speed() * self.cube.move_time() / replay.move_time());
+        self.replay = Some((replay, before.0, before.1));
+    }
+
+    /// Stop the replay, if there is one, and go back to the animation speed and playback from before.
+    fn stop_replay(&mut self) {
+        if let Some((_, speed, playback)) = self.replay.take() {
+            self.cube.set_speed(speed);
+            self.cube.set_playback(playback);
+        }
+    }
+
+    /// Switch to the next of [MaskPreset::ALL].
+    fn next_stickering(&mut self) {
+        let next = (MaskPreset::ALL.iter().position(|&p| p == self.stickering).unwrap() + 1) % MaskPreset::ALL.len();
+        self.stickering = MaskPreset::ALL[next];
+        self.cube.set_mask(self.stickering.into());
+        info!("Stickering: {}", self.stickering.name());
+    }
+
+    /// Handle a key while timing. Returns whether the key did something.
+    fn timer_key(&mut self, key: Key, shift: bool, time: f64) -> bool {
+        if let Some(face) = FACE_KEYS.iter().position(|&k| k == key) {
+            let mv = Move::from_face(face, if shift { 3 } else { 1 }).unwrap();
+            self.cube.queue(mv.into_iter());
+            self.timer.turned(time);
+            return true;
+        }
+        match key {
+            Key::Space => {
+                if !self.timer.is_active() {
+                    self.new_scramble(time);
+                }
+                if self.timer.press(time).is_some() {
+                    self.log_solve();
+                }
+            }
+            Key::Num1 if !self.timer.is_active() => self.timer.penalize(Penalty::None),
+            Key::Num2 if !self.timer.is_active() => self.timer.penalize(Penalty::PlusTwo),
+            Key::Num3 if !self.timer.is_active() => self.timer.penalize(Penalty::Dnf),
+            Key::P if !self.timer.is_active() && self.reconstruction.is_some() => {
+                if let Some(reconstruction) = self.reconstruction.clone() {
+                    self.start_replay(reconstruction, 1.0);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn log_solve(&self) {
+        let session = self.timer.session();
+        if let Some(solve) = session.solves.last() {
+            info!("Solve {}: {}. {}", session.solves.len(), solve.result(), session.stats());
+        }
+    }
+
+    /// Record the moves while the timer runs, keeping the recording once it stops.
+    fn record_timed_solve(&mut self) {
+        let running = matches!(self.timer.phase(), TimerPhase::Running { .. });
+        if running && !self.cube.is_recording() {
+            self.cube.record();
+        } else if !running && self.cube.is_recording() {
+            self.reconstruction = self.cube.stop_recording();
+            if let Some(reconstruction) = &self.reconstruction {
+                info!("Reconstruction:\n{}", reconstruction);
+            }
+            self.remote.borrow_mut().set_reconstruction(self.reconstruction.clone());
+        }
+    }
+
+    /// Scramble the cube for the next solve, right away instead of animating the moves.
+    fn new_scramble(&mut self, time: f64) {
+        let seed = time.to_bits() ^ unix_time().rotate_left(32) ^ self.timer.session().solves.len() as u64;
+        let moves = scramble(seed, 25);
+        let state = apply_moves(SOLVED, &moves).unwrap();
+        self.cube.set_state(&state).unwrap();
+        let notation = moves.iter().map(|mv| mv.to_string()).collect::<Vec<_>>().join(" ");
+        info!("Scramble: {}", notation);
+        self.timer.set_scramble(&notation);
+    }
+}
+
+impl Scene for RubikScene {
+    fn resize(&mut self, viewport: Viewport) {
+        self.camera.set_viewport(viewport);
+    }
+
+    fn update(&mut self, frame_input: &mut FrameInput) {
+        let commands = self.remote.borrow_mut().take_commands();
+        let changed = !commands.is_empty();
+        for command in commands {
+            self.run_command(command);
+        }
+        let mut edited = false;
+        let mut finished = None;
+        if let Some(editor) = &mut self.editor {
+            edited = editor.update(frame_input, &self.camera);
+        } else {
+            if let Some((replay, ..)) = &mut self.replay {
+                if let Err(e) = replay.update(&mut self.cube, frame_input.accumulated_time as f32) {
+                    warn!("Can't replay the solve: {}", e);
+                    self.stop_replay();
+                }
+            }
+            // The settings go back once the last move has finished too
+            if self.replay.as_ref().is_some_and(|(replay, ..)| replay.is_done()) && self.cube.is_idle() {
+                self.stop_replay();
+            }
+            finished = self.cube.animate(frame_input.accumulated_time as f32).unwrap_or_else(|e| {
+                warn!("Can't animate the cube: {}", e);
+                None
+            });
+        }
+        let time = frame_input.accumulated_time;
+        for event in frame_input.events.iter_mut() {
+            if let Event::KeyPress { kind, modifiers, handled } = event {
+                if *handled {
+                    continue;
+                }
+                match kind {
+                    Key::T if self.editor.is_none() => match self.timing {
+                        Some(_) => self.stop_timing(),
+                        None => self.time(),
+                    },
+                    Key::E if self.editor.is_none() => self.edit(),
+                    Key::M if self.editor.is_none() && self.timing.is_none() => self.next_stickering(),
+                    Key::H if self.editor.is_none() && self.timing.is_none() => {
+                        let mut appearance = self.cube.appearance();
+                        appearance.hint_facelets = !appearance.hint_facelets;
+                        self.cube.set_appearance(appearance);
+                    }
+                    Key::Enter if self.editor.is_some() => {
+                        if let Err(e) = self.apply() {
+                            warn!("Can't apply the edited state: {}", e);
+                        }
+                    }
+                    _ if self.timing.is_some() => {
+                        if !self.timer_key(*kind, modifiers.shift, time) {
+                            continue;
+                        }
+                    }
+                    _ => continue,
+                }
+                *handled = true;
+                edited = true;
+            }
+        }
+        // Keyboard solves stop by themselves once the last turn lands
+        let solved = finished.is_some() && self.cube.is_idle() && self.cube.is_solved();
+        if self.timing.is_some() && solved && self.timer.solved().is_some() {
+            self.log_solve();
+        }
+        if self.timing.is_some() {
+            self.record_timed_solve();
+        }
+        if changed || edited || finished.is_some() {
+            let state = self.state();
+            let mut remote = self.remote.borrow_mut();
+            remote.set_state(state);
+            remote.set_solution_known(self.editor.is_none() && self.cube.solution().is_some());
+            remote.set_session(self.timer.session().clone());
+        }
+        if self.timing.is_some() {
+            self.remote.borrow_mut().set_readout(self.timer.readout(time));
+        }
+        if let Some(group) = finished {
+            let mut listeners = self.remote.borrow_mut().take_move_listeners();
+            for mv in group.moves() {
+                listeners.iter_mut().for_each(|listener| listener(mv));
+            }
+            self.remote.borrow_mut().restore_move_listeners(listeners);
+        }
+        match &self.editor {
+            Some(editor) => self.lights.update(editor.geometries()),
+            None => self.lights.update(self.cube.geometries()),
+        }
         let dt: f32 = frame_input.elapsed_time as f32;
-        camera.set_viewport(frame_input.viewport);
-        control.handle_events(&mut camera, &mut frame_input.events, dt);
-        FrameOutput::default()
+        let input = self.control.handle_events(&mut self.camera, &mut frame_input.events, dt);
+        let cube_idle = self.editor.is_some() || self.cube.is_idle() || self.cube.is_paused();
+        // The readout of a running timer changes every frame
+        let timer_idle = self.timing.is_none() || !self.timer.is_active();
+        let replay_idle = self.replay.is_none();
+        self.idle = !changed && !edited && !input && finished.is_none() && cube_idle && timer_idle && replay_idle && self.control.is_settled();
+    }
+
+    fn render(&mut self, screen: &RenderTarget) {
+        let (cube, editor, axes, lights) = (&self.cube, &self.editor, &self.axes, &self.lights);
+        // The offscreen target is linear, 0.033 maps back to the old 0.2 gray on screen
+        self.post.render(screen, &self.camera, |target, camera| {
+            let target = target.clear(ClearState::color_and_depth(0.033, 0.033, 0.033, 0.8, 1.0));
+            match editor {
+                Some(editor) => target.render(camera, editor.objects().chain(axes), &lights.lights()),
+                None => target.render(camera, cube.into_iter().chain(axes), &lights.lights()),
+            };
+        });
+        if let Some(editor) = editor {
+            editor.render_overlay(screen);
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.idle
     }
 }
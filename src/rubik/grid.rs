@@ -0,0 +1,131 @@
+use log::{info, warn};
+use three_d::*;
+
+use super::{scramble, Cube, CubeAnimationOptions, LightRig};
+use crate::scene::Scene;
+
+/// Cubes along each side of the grid.
+const GRID_SIZE: usize = 8;
+
+/// Distance between the centers of neighbouring cubes.
+const GRID_SPACING: f32 = 4.0;
+
+/// How often the frame time is logged, in milliseconds.
+const REPORT_INTERVAL: f64 = 2000.0;
+
+///
+/// A benchmark: a grid of cubes that keep turning, all at once.
+///
+/// Every couple of seconds the number of draw calls and the average frame time are logged, to see
+/// how rendering scales with the number of cubes. Number keys 1 to 8 change the size of the grid.
+/// Run it with `RUST_LOG=info`, tracing every move of every cube takes longer than drawing them.
+///
+pub struct CubeGridScene {
+    context: Context,
+    camera: Camera,
+    control: OrbitControl,
+    lights: LightRig,
+    cubes: Vec<Cube>,
+    /// Seed of the next scramble a cube turns through.
+    seed: u64,
+    frames: u32,
+    frame_time: f64,
+}
+
+impl CubeGridScene {
+    pub fn new(context: &Context, viewport: Viewport) -> Self {
+        let camera = Camera::new_perspective(
+            viewport,
+            vec3(0.0, 30.0, 40.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(45.0),
+            0.1,
+            200.0,
+        );
+        let control = OrbitControl::new(*camera.target(), 5.0, 150.0);
+        let mut scene = Self {
+            context: context.clone(),
+            camera,
+            control,
+            lights: LightRig::new(context),
+            cubes: Vec::new(),
+            seed: 1,
+            frames: 0,
+            frame_time: 0.0,
+        };
+        scene.set_size(GRID_SIZE);
+        scene
+    }
+
+    /// Replace the cubes with a grid of `size` by `size` solved cubes.
+    pub fn set_size(&mut self, size: usize) {
+        let anim = CubeAnimationOptions {
+            move_time: 300.0,
+            ..Default::default()
+        };
+        let offset = (size as f32 - 1.0) * GRID_SPACING / 2.0;
+        self.cubes = (0..size * size).map(|i| {
+            let mut cube = Cube::solved(anim, &self.context);
+            let position = vec3((i % size) as f32 * GRID_SPACING - offset, 0.0, (i / size) as f32 * GRID_SPACING - offset);
+            cube.set_transformation(Mat4::from_translation(position));
+            cube
+        }).collect();
+        self.frames = 0;
+        self.frame_time = 0.0;
+    }
+
+    /// Draw calls per frame for all the cubes, without the shadow map.
+    pub fn draw_calls(&self) -> usize {
+        self.cubes.iter().map(Cube::draw_calls).sum()
+    }
+}
+
+impl Scene for CubeGridScene {
+    fn resize(&mut self, viewport: Viewport) {
+        self.camera.set_viewport(viewport);
+    }
+
+    fn update(&mut self, frame_input: &mut FrameInput) {
+        for event in frame_input.events.iter_mut() {
+            if let Event::KeyPress { kind, handled: handled @ false, .. } = event {
+                let keys = [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8];
+                if let Some(size) = keys.iter().position(|key| key == kind) {
+                    self.set_size(size + 1);
+                    *handled = true;
+                }
+            }
+        }
+        self.control.handle_events(&mut self.camera, &mut frame_input.events);
+
+        let time = frame_input.accumulated_time as f32;
+        for cube in self.cubes.iter_mut() {
+            if cube.is_idle() {
+                cube.queue(scramble(self.seed, 20).into_iter());
+                self.seed += 1;
+            }
+            if let Err(e) = cube.animate(time) {
+                warn!("Can't animate a cube: {}", e);
+            }
+        }
+
+        self.frames += 1;
+        self.frame_time += frame_input.elapsed_time;
+        if self.frame_time >= REPORT_INTERVAL {
+            info!(
+                "{} cubes, {} draw calls, {:.2} ms per frame",
+                self.cubes.len(),
+                self.draw_calls(),
+                self.frame_time / self.frames as f64,
+            );
+            self.frames = 0;
+            self.frame_time = 0.0;
+        }
+    }
+
+    fn render(&mut self, screen: &RenderTarget) {
+        screen
+            .clear(ClearState::color_and_depth(0.2, 0.2, 0.2, 1.0, 1.0))
+            .render(&self.camera, self.cubes.iter().flatten(), &self.lights.lights());
+    }
+}
@@ -0,0 +1,205 @@
+use std::cell::RefCell;
+
+use three_d::*;
+
+/// Id of the shader of [PieceInstances], below the ids three-d keeps for its own geometries.
+const PIECE_INSTANCES_ID: u16 = 0b1u16 << 14;
+
+/// Vertices of the mesh every piece of a cube is drawn with, centered at the origin.
+pub(crate) struct PieceMesh {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<Vec2>,
+    /// Face of the piece every vertex is on, in L, U, F, D, R, B order.
+    pub faces: Vec<f32>,
+    /// How far every vertex moves out when its face has a sticker, for stickers that stick out or in.
+    pub sticker_offsets: Vec<Vec3>,
+    pub indices: Vec<u32>,
+}
+
+/// One piece drawn by [PieceInstances].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PieceInstance {
+    pub transformation: Mat4,
+    /// Colors of the faces in L, U, F, D, R, B order. Faces without one are plastic.
+    pub colors: [Option<Srgba>; 6],
+}
+
+///
+/// All the pieces of a cube as instances of one [PieceMesh], drawn in a single call.
+///
+/// Every instance has its own transformation and a color for each face, which the vertex shader
+/// picks by the face of the vertex. Colors only change when stickers are repainted, while
+/// transformations are updated every frame of a move.
+///
+/// Instances are drawn nearest to the camera first, like three-d orders its objects, so the depth
+/// test skips shading the pieces behind them. Blended instances go the other way round.
+///
+pub(crate) struct PieceInstances {
+    context: Context,
+    positions: VertexBuffer,
+    normals: VertexBuffer,
+    uvs: VertexBuffer,
+    faces: VertexBuffer,
+    sticker_offsets: VertexBuffer,
+    elements: ElementBuffer,
+    /// Rows of the transformation of every instance, in the order they are drawn.
+    rows: RefCell<[InstanceBuffer; 3]>,
+    /// Color of every face of every instance, in the order they are drawn.
+    colors: RefCell<[InstanceBuffer; 6]>,
+    count: u32,
+    transformations: Vec<Mat4>,
+    face_colors: Vec<[Vec4; 6]>,
+    /// The order the instances are in the buffers, `None` when the buffers are out of date.
+    order: RefCell<Option<Vec<usize>>>,
+    /// Placement of all the instances together, applied after their own transformations.
+    transformation: Mat4,
+    /// Of the mesh itself, before any transformation.
+    mesh_aabb: AxisAlignedBoundingBox,
+    /// Of all the instances, before the placement.
+    aabb: AxisAlignedBoundingBox,
+}
+
+impl PieceInstances {
+    pub fn new(context: &Context, mesh: &PieceMesh, instances: &[PieceInstance]) -> Self {
+        // Textures are read upside down, like three-d does for its meshes
+        let uvs: Vec<Vec2> = mesh.uvs.iter().map(|uv| vec2(uv.x, 1.0 - uv.y)).collect();
+        let mut piece_instances = Self {
+            context: context.clone(),
+            positions: VertexBuffer::new_with_data(context, &mesh.positions),
+            normals: VertexBuffer::new_with_data(context, &mesh.normals),
+            uvs: VertexBuffer::new_with_data(context, &uvs),
+            faces: VertexBuffer::new_with_data(context, &mesh.faces),
+            sticker_offsets: VertexBuffer::new_with_data(context, &mesh.sticker_offsets),
+            elements: ElementBuffer::new_with_data(context, &mesh.indices),
+            rows: RefCell::new(std::array::from_fn(|_| InstanceBuffer::new(context))),
+            colors: RefCell::new(std::array::from_fn(|_| InstanceBuffer::new(context))),
+            count: 0,
+            transformations: vec![],
+            face_colors: vec![],
+            order: RefCell::new(None),
+            transformation: Mat4::identity(),
+            mesh_aabb: AxisAlignedBoundingBox::new_with_positions(&mesh.positions),
+            aabb: AxisAlignedBoundingBox::EMPTY,
+        };
+        piece_instances.set_colors(instances);
+        piece_instances.set_transformations(instances);
+        piece_instances
+    }
+
+    /// Update the face colors, which also sets how many instances are drawn.
+    pub fn set_colors(&mut self, instances: &[PieceInstance]) {
+        self.count = instances.len() as u32;
+        self.face_colors = instances.iter()
+            .map(|instance| instance.colors.map(|color| color.map_or(Vec4::zero(), |color| color.to_linear_srgb())))
+            .collect();
+        *self.order.get_mut() = None;
+    }
+
+    pub fn set_transformations(&mut self, instances: &[PieceInstance]) {
+        self.transformations = instances.iter().map(|instance| instance.transformation).collect();
+        *self.order.get_mut() = None;
+        self.aabb = AxisAlignedBoundingBox::EMPTY;
+        for instance in instances {
+            let mut aabb = self.mesh_aabb;
+            aabb.transform(&instance.transformation);
+            self.aabb.expand_with_aabb(&aabb);
+        }
+    }
+
+    /// Fill the buffers with the instances in `order`.
+    fn upload(&self, order: &[usize]) {
+        for (row, buffer) in self.rows.borrow_mut().iter_mut().enumerate() {
+            let rows: Vec<Vec4> = order.iter().map(|&i| self.transformations[i].row(row)).collect();
+            buffer.fill(&rows);
+        }
+        for (face, buffer) in self.colors.borrow_mut().iter_mut().enumerate() {
+            let colors: Vec<Vec4> = order.iter().map(|&i| self.face_colors[i][face]).collect();
+            buffer.fill(&colors);
+        }
+    }
+
+    /// The instances nearest to the camera first, or last if they are blended.
+    fn draw_order(&self, camera: &Camera, render_states: RenderStates) -> Vec<usize> {
+        let distance = |i: usize| (self.transformation * self.transformations[i]).w.truncate().distance2(*camera.position());
+        let mut order: Vec<usize> = (0..self.count as usize).collect();
+        order.sort_by(|&a, &b| distance(a).total_cmp(&distance(b)));
+        if render_states.blend != Blend::Disabled {
+            order.reverse();
+        }
+        order
+    }
+
+    /// Place all the instances at once, like [Mesh::set_transformation].
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+}
+
+impl Geometry for PieceInstances {
+    fn draw(&self, camera: &Camera, program: &Program, render_states: RenderStates, _attributes: FragmentAttributes) {
+        if self.count == 0 {
+            return;
+        }
+        let order = self.draw_order(camera, render_states);
+        if self.order.borrow().as_ref() != Some(&order) {
+            self.upload(&order);
+            *self.order.borrow_mut() = Some(order);
+        }
+        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        program.use_uniform("modelMatrix", self.transformation);
+        // Attributes the material doesn't need are optimized out of the shader
+        let vertex_buffers = [
+            ("position", &self.positions),
+            ("normal", &self.normals),
+            ("uv_coordinates", &self.uvs),
+            ("face", &self.faces),
+            ("sticker_offset", &self.sticker_offsets),
+        ];
+        for (name, buffer) in vertex_buffers {
+            if program.requires_attribute(name) {
+                program.use_vertex_attribute(name, buffer);
+            }
+        }
+        let (rows, colors) = (self.rows.borrow(), self.colors.borrow());
+        let rows = ["row1", "row2", "row3"].into_iter().zip(rows.iter());
+        let colors = ["color0", "color1", "color2", "color3", "color4", "color5"].into_iter().zip(colors.iter());
+        for (name, buffer) in rows.chain(colors) {
+            if program.requires_attribute(name) {
+                program.use_instance_attribute(name, buffer);
+            }
+        }
+        program.draw_elements_instanced(render_states, camera.viewport(), &self.elements, self.count);
+    }
+
+    fn vertex_shader_source(&self, _required_attributes: FragmentAttributes) -> String {
+        include_str!("pieces.vert").to_string()
+    }
+
+    fn id(&self, _required_attributes: FragmentAttributes) -> u16 {
+        PIECE_INSTANCES_ID
+    }
+
+    fn render_with_material(&self, material: &dyn Material, camera: &Camera, lights: &[&dyn Light]) {
+        render_with_material(&self.context, camera, self, material, lights)
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        render_with_effect(&self.context, camera, self, material, lights, color_texture, depth_texture)
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        let mut aabb = self.aabb;
+        aabb.transform(&self.transformation);
+        aabb
+    }
+
+    fn animate(&mut self, _time: f32) {}
+}
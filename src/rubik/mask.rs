@@ -0,0 +1,190 @@
+use three_d::Vec3;
+
+use super::algs::normal;
+use super::{facelet_at, Cube, FACELETS};
+use crate::Error;
+
+/// How a sticker is drawn, and whether it counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum StickerStyle {
+    #[default]
+    Regular,
+    /// Drawn darker, for pieces that are already solved at this step. It still counts.
+    Dim,
+    /// Drawn gray, and solvers and recognizers don't care where it is.
+    Ignored,
+}
+
+/// The stickerings of [StickerMask::preset], named like on alg.cubing.net and twizzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MaskPreset {
+    Full,
+    /// The D edges.
+    Cross,
+    /// The first two layers, with the cross on D.
+    F2l,
+    /// Only the U stickers of the last layer, with the first two layers dimmed.
+    Oll,
+    /// The last layer, with the first two layers dimmed.
+    Pll,
+    /// The same as [MaskPreset::Pll], the whole last layer matters.
+    Zbll,
+    Centers,
+}
+
+impl MaskPreset {
+    pub const ALL: [MaskPreset; 7] = [
+        MaskPreset::Full, MaskPreset::Cross, MaskPreset::F2l, MaskPreset::Oll, MaskPreset::Pll, MaskPreset::Zbll, MaskPreset::Centers,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaskPreset::Full => "full",
+            MaskPreset::Cross => "Cross",
+            MaskPreset::F2l => "F2L",
+            MaskPreset::Oll => "OLL",
+            MaskPreset::Pll => "PLL",
+            MaskPreset::Zbll => "ZBLL",
+            MaskPreset::Centers => "Centers",
+        }
+    }
+}
+
+impl std::str::FromStr for MaskPreset {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MaskPreset::ALL.into_iter()
+            .find(|preset| preset.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::Parse(format!("Unknown stickering {}", s)))
+    }
+}
+
+///
+/// A style for each of the 54 stickers, to hide what doesn't matter when learning a step of a solve.
+///
+/// Stickers are told apart by where they are on a solved cube, so the mask follows the pieces
+/// as they move: with [MaskPreset::Cross] the D edges stay colored wherever they are.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StickerMask {
+    styles: [StickerStyle; 54],
+}
+
+impl Default for StickerMask {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl From<MaskPreset> for StickerMask {
+    fn from(preset: MaskPreset) -> Self {
+        Self::preset(preset)
+    }
+}
+
+const U: usize = 1;
+
+/// Height of the piece of a facelet on a solved cube, -1 for the D layer and 1 for the U layer.
+fn layer(facelet: usize) -> i32 {
+    1 - FACELETS[facelet] as i32 / 3 % 3
+}
+
+fn is_center(facelet: usize) -> bool {
+    facelet % 9 == 4
+}
+
+fn is_edge(facelet: usize) -> bool {
+    facelet % 9 % 2 == 1
+}
+
+impl StickerMask {
+    pub const FULL: StickerMask = StickerMask { styles: [StickerStyle::Regular; 54] };
+
+    /// The style of every sticker, by its facelet on a solved cube.
+    pub fn new(style: impl Fn(usize) -> StickerStyle) -> Self {
+        Self { styles: std::array::from_fn(style) }
+    }
+
+    pub fn preset(preset: MaskPreset) -> Self {
+        use StickerStyle::*;
+        Self::new(|facelet| {
+            let (face, layer) = (facelet / 9, layer(facelet));
+            match preset {
+                _ if is_center(facelet) => Regular,
+                MaskPreset::Full => Regular,
+                MaskPreset::Centers => Ignored,
+                MaskPreset::Cross if layer == -1 && is_edge(facelet) => Regular,
+                MaskPreset::Cross => Ignored,
+                MaskPreset::F2l if layer < 1 => Regular,
+                MaskPreset::F2l => Ignored,
+                MaskPreset::Oll | MaskPreset::Pll | MaskPreset::Zbll if layer < 1 => Dim,
+                MaskPreset::Oll if face == U => Regular,
+                MaskPreset::Oll => Ignored,
+                MaskPreset::Pll | MaskPreset::Zbll => Regular,
+            }
+        })
+    }
+
+    /// Style of the sticker that is at `facelet` on a solved cube.
+    pub fn style(&self, facelet: usize) -> StickerStyle {
+        self.styles[facelet]
+    }
+
+    pub fn set_style(&mut self, facelet: usize, style: StickerStyle) {
+        self.styles[facelet] = style;
+    }
+
+    /// Whether no sticker is ignored, so the whole cube matters.
+    pub fn is_full(&self) -> bool {
+        !self.styles.contains(&StickerStyle::Ignored)
+    }
+
+    /// Facelets of a state whose sticker is ignored, wherever it has moved.
+    pub fn ignored(&self, facelets: &str) -> Result<[bool; 54], Error> {
+        let homes = home_facelets(facelets)?;
+        Ok(homes.map(|home| self.styles[home] == StickerStyle::Ignored))
+    }
+
+    /// Whether every sticker that isn't ignored has the color of its face.
+    pub fn is_solved(&self, facelets: &str) -> Result<bool, Error> {
+        let ignored = self.ignored(facelets)?;
+        let facelets = facelets.as_bytes();
+        Ok((0..54).all(|facelet| ignored[facelet] || facelets[facelet] == facelets[facelet / 9 * 9 + 4]))
+    }
+
+    ///
+    /// The facelet string with ignored stickers replaced by `-`, like the unpainted stickers of the
+    /// [FaceletEditor](super::FaceletEditor).
+    ///
+    pub fn apply(&self, facelets: &str) -> Result<String, Error> {
+        let ignored = self.ignored(facelets)?;
+        Ok(facelets.chars().enumerate().map(|(facelet, c)| if ignored[facelet] { '-' } else { c }).collect())
+    }
+}
+
+///
+/// Where every sticker of a state is on a solved cube, told by the colors of its piece.
+/// Fails if a piece has colors no piece of the cube has.
+///
+pub fn home_facelets(facelets: &str) -> Result<[usize; 54], Error> {
+    Cube::validate_facelet_str(facelets)?;
+    let facelets = facelets.as_bytes();
+    let centers: [u8; 6] = std::array::from_fn(|face| facelets[face * 9 + 4]);
+    if (0..6).any(|face| centers[face + 1..].contains(&centers[face])) {
+        return Err(Error::Validation("Expected every center to have a different color".to_string()));
+    }
+    let face_of = |facelet: usize| centers.iter().position(|&c| c == facelets[facelet]).unwrap();
+    let mut homes = [0; 54];
+    for (facelet, &cubelet) in FACELETS.iter().enumerate() {
+        // A piece belongs where the normals of the faces of its colors point
+        let stickers: Vec<usize> = (0..54).filter(|&f| FACELETS[f] == cubelet).collect();
+        let home: Vec3 = stickers.iter().map(|&f| normal(face_of(f))).sum();
+        let face = face_of(facelet);
+        let sticker_count = |cubelet: usize| FACELETS.iter().filter(|&&c| c == cubelet).count();
+        homes[facelet] = facelet_at(home + normal(face) * 0.5)
+            .filter(|&home| home / 9 == face && sticker_count(FACELETS[home]) == stickers.len())
+            .ok_or_else(|| Error::Validation(format!("The piece at facelet {} has colors that don't go together", facelet)))?;
+    }
+    Ok(homes)
+}
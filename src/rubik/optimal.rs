@@ -0,0 +1,708 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+
+use super::algs::OPPOSITE;
+use super::cubie::{corner_facelets, edge_facelets, move_cube};
+use super::{Corner, CubieCube, Edge, Move, StickerMask, StickerStyle};
+use crate::Error;
+
+/// How solutions are counted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Metric {
+    /// Every turn is one move, `R2` included.
+    #[default]
+    HalfTurn,
+    /// Half turns are two moves.
+    QuarterTurn,
+}
+
+impl Metric {
+    fn cost(&self, mv: Move) -> u8 {
+        match self {
+            Metric::QuarterTurn if mv.amount() == 2 => 2,
+            _ => 1,
+        }
+    }
+
+    /// Length of a sequence of moves.
+    pub fn length(&self, moves: &[Move]) -> usize {
+        moves.iter().map(|&mv| self.cost(mv) as usize).sum()
+    }
+
+    /// Moves needed to solve the hardest states, God's number.
+    pub fn diameter(&self) -> u8 {
+        match self {
+            Metric::HalfTurn => 20,
+            Metric::QuarterTurn => 26,
+        }
+    }
+}
+
+/// Entries of a pattern database that haven't been reached.
+const UNKNOWN: u8 = 0xF;
+
+/// Deepest a pattern database can go with 4 bits per entry, more than any of them needs.
+const MAX_TABLE_DEPTH: u8 = 14;
+
+/// Searching the database from a list of states stops paying off past this many, scanning the whole table is faster.
+const MAX_FRONTIER: usize = 1 << 20;
+
+/// Pieces tracked by a pattern database.
+#[derive(Clone, Copy)]
+enum Pattern {
+    Corners,
+    /// Six edges, starting from this one.
+    Edges(usize),
+}
+
+impl Pattern {
+    fn size(&self) -> usize {
+        match self {
+            // 8! permutations times 3^7 twists
+            Pattern::Corners => 40320 * 2187,
+            // 12 * 11 * ... * 7 positions times 2^6 flips
+            Pattern::Edges(_) => 665280 * 64,
+        }
+    }
+
+    fn index(&self, cube: &CubieCube) -> usize {
+        match *self {
+            Pattern::Corners => {
+                let twist = cube.co[..7].iter().rev().fold(0, |twist, &co| twist * 3 + co as usize);
+                rank(&cube.cp.map(|c| c as u8), 8) * 2187 + twist
+            }
+            Pattern::Edges(first) => {
+                let mut positions = [0; 6];
+                let mut flips = 0;
+                for (i, &edge) in cube.ep.iter().enumerate() {
+                    let tracked = (edge as usize).wrapping_sub(first);
+                    if tracked < 6 {
+                        positions[tracked] = i as u8;
+                        flips |= (cube.eo[i] as usize) << tracked;
+                    }
+                }
+                rank(&positions, 12) * 64 + flips
+            }
+        }
+    }
+
+    /// A state with the tracked pieces of `index`, the others are put anywhere.
+    fn cube(&self, index: usize) -> CubieCube {
+        let mut cube = CubieCube::SOLVED;
+        match *self {
+            Pattern::Corners => {
+                let (permutation, mut twist) = (index / 2187, index % 2187);
+                let corners = unrank(permutation, 8, 8);
+                for i in 0..7 {
+                    cube.co[i] = (twist % 3) as u8;
+                    twist /= 3;
+                }
+                cube.co[7] = (3 - cube.co[..7].iter().sum::<u8>() % 3) % 3;
+                cube.cp = std::array::from_fn(|i| Corner::ALL[corners[i] as usize]);
+            }
+            Pattern::Edges(first) => {
+                let positions = unrank(index / 64, 12, 6);
+                let mut placed = [false; 12];
+                for (tracked, &position) in positions[..6].iter().enumerate() {
+                    cube.ep[position as usize] = Edge::ALL[first + tracked];
+                    cube.eo[position as usize] = (index >> tracked & 1) as u8;
+                    placed[position as usize] = true;
+                }
+                let mut others = (0..12).filter(|edge| !(first..first + 6).contains(edge));
+                for position in (0..12).filter(|&position| !placed[position]) {
+                    cube.ep[position] = Edge::ALL[others.next().unwrap()];
+                    cube.eo[position] = 0;
+                }
+            }
+        }
+        cube
+    }
+}
+
+/// Position of an arrangement of `values.len()` distinct values below `n`, among all of them.
+fn rank(values: &[u8], n: usize) -> usize {
+    let mut used = 0u16;
+    let mut rank = 0;
+    for (i, &value) in values.iter().enumerate() {
+        let smaller_used = (used & ((1 << value) - 1)).count_ones() as usize;
+        rank = rank * (n - i) + value as usize - smaller_used;
+        used |= 1 << value;
+    }
+    rank
+}
+
+/// The arrangement of `k` values below `n` at position `rank`, the inverse of [rank].
+fn unrank(mut rank: usize, n: usize, k: usize) -> [u8; 8] {
+    let mut digits = [0; 8];
+    for i in (0..k).rev() {
+        digits[i] = rank % (n - i);
+        rank /= n - i;
+    }
+    let mut unused = (1u16 << n) - 1;
+    let mut values = [0; 8];
+    for i in 0..k {
+        // The digit-th value that isn't used yet
+        let mut rest = unused;
+        for _ in 0..digits[i] {
+            rest &= rest - 1;
+        }
+        values[i] = rest.trailing_zeros() as u8;
+        unused &= !(1 << values[i]);
+    }
+    values
+}
+
+/// Run `tasks` tasks on up to `threads` threads, calling `done` on this thread after each one.
+fn parallel(threads: usize, tasks: usize, task: impl Fn(usize) + Sync, mut done: impl FnMut()) {
+    if threads <= 1 {
+        for i in 0..tasks {
+            task(i);
+            done();
+        }
+        return;
+    }
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for _ in 0..threads.min(tasks) {
+            let (next, task, sender) = (&next, &task, sender.clone());
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= tasks {
+                    break;
+                }
+                task(i);
+                let _ = sender.send(());
+            });
+        }
+        drop(sender);
+        for _ in receiver {
+            done();
+        }
+    });
+}
+
+///
+/// Moves needed to solve the tracked pieces of every state, 4 bits per state.
+/// States further than `depth` aren't searched, they're known to take at least `depth + 1` moves.
+///
+struct Table {
+    pattern: Pattern,
+    entries: Vec<AtomicU8>,
+    depth: u8,
+}
+
+impl Table {
+    fn raw(&self, index: usize) -> u8 {
+        self.entries[index / 2].load(Ordering::Relaxed) >> (index % 2 * 4) & 0xF
+    }
+
+    fn get(&self, index: usize) -> u8 {
+        match self.raw(index) {
+            UNKNOWN => self.depth + 1,
+            distance => distance,
+        }
+    }
+
+    /// Set an entry that is still unknown, which only needs clearing bits.
+    fn set(&self, index: usize, distance: u8) {
+        self.entries[index / 2].fetch_and(!((!distance & 0xF) << (index % 2 * 4)), Ordering::Relaxed);
+    }
+
+    /// Search the states breadth first from the solved one. Gives up if `cancelled` gets set.
+    fn generate(pattern: Pattern, depth: u8, threads: usize, cancelled: &AtomicBool) -> Option<Table> {
+        let entries = (0..pattern.size().div_ceil(2)).map(|_| AtomicU8::new(0xFF)).collect();
+        let mut table = Table { pattern, entries, depth: 0 };
+        let moves = Move::ALL.map(move_cube);
+        // New neighbours of a state at `distance`
+        let expand = |table: &Table, index: usize, distance: u8, found: &mut dyn FnMut(usize)| {
+            let cube = pattern.cube(index);
+            for mv in &moves {
+                let next = pattern.index(&(cube * *mv));
+                if table.raw(next) == UNKNOWN {
+                    table.set(next, distance + 1);
+                    found(next);
+                }
+            }
+        };
+        // Whether a state is next to one at `distance`, to search backwards from the unknown states
+        let is_next = |table: &Table, index: usize, distance: u8| {
+            let cube = pattern.cube(index);
+            moves.iter().any(|mv| table.raw(pattern.index(&(cube * *mv))) == distance)
+        };
+        let solved = pattern.index(&CubieCube::SOLVED);
+        table.set(solved, 0);
+        let mut frontier = Some(vec![solved]);
+        let mut known = 1;
+        for distance in 0..depth.min(MAX_TABLE_DEPTH) {
+            let found = match frontier.take() {
+                Some(states) => {
+                    let mut next = vec![];
+                    for index in states {
+                        if cancelled.load(Ordering::Relaxed) {
+                            return None;
+                        }
+                        expand(&table, index, distance, &mut |found| next.push(found));
+                    }
+                    let found = next.len();
+                    frontier = Some(next).filter(|next| next.len() <= MAX_FRONTIER);
+                    found
+                }
+                None => {
+                    // Once most states are known, there are fewer unknown ones to look at than known ones to expand
+                    let backward = known > pattern.size() / 2;
+                    let found = AtomicUsize::new(0);
+                    let chunks = threads.max(1) * 16;
+                    let chunk = pattern.size().div_ceil(chunks);
+                    parallel(threads, chunks, |i| {
+                        // A scan of the biggest tables takes seconds
+                        if cancelled.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        let mut count = 0;
+                        for index in i * chunk..((i + 1) * chunk).min(pattern.size()) {
+                            let raw = table.raw(index);
+                            if backward && raw == UNKNOWN && is_next(&table, index, distance) {
+                                table.set(index, distance + 1);
+                                count += 1;
+                            } else if !backward && raw == distance {
+                                expand(&table, index, distance, &mut |_| count += 1);
+                            }
+                        }
+                        found.fetch_add(count, Ordering::Relaxed);
+                    }, || {});
+                    found.into_inner()
+                }
+            };
+            // A cancelled scan stops halfway through
+            if cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+            known += found;
+            if found == 0 {
+                break;
+            }
+            table.depth = distance + 1;
+        }
+        Some(table)
+    }
+}
+
+/// Korf's pattern databases: the corners, and the edges split in two halves.
+struct PatternDatabases {
+    tables: [Table; 3],
+}
+
+impl PatternDatabases {
+    fn generate(depth: u8, threads: usize, cancelled: &AtomicBool) -> Option<Self> {
+        let table = |pattern| Table::generate(pattern, depth, threads, cancelled);
+        Some(Self { tables: [table(Pattern::Corners)?, table(Pattern::Edges(0))?, table(Pattern::Edges(6))?] })
+    }
+}
+
+/// Pieces a [PieceGroup] tracks at most, so that their states stay few.
+const GROUP_SIZE: u32 = 4;
+
+/// A piece's position times its orientations plus its orientation, 24 of them for corners as for edges.
+const PIECE_STATES: usize = 24;
+
+///
+/// Pieces of a kind with stickers a [StickerMask] doesn't ignore, and the moves they need to all reach
+/// a position and orientation where those stickers are on their own faces. It's searched anew for
+/// every solve, being small.
+///
+struct PieceGroup {
+    corners: bool,
+    pieces: Vec<usize>,
+    distances: Vec<u8>,
+}
+
+impl PieceGroup {
+    fn new(mask: &StickerMask, metric: Metric, corners: bool, pieces: Vec<usize>) -> Self {
+        let (stickers, orientations): (Vec<Vec<usize>>, usize) = if corners {
+            (corner_facelets().iter().map(|stickers| stickers.to_vec()).collect(), 3)
+        } else {
+            (edge_facelets().iter().map(|stickers| stickers.to_vec()).collect(), 2)
+        };
+        // Sticker n of a piece turned o times is on sticker (n + o) % orientations of its position
+        let solved = |piece: usize, state: usize| {
+            let (position, orientation) = (state / orientations, state % orientations);
+            (0..orientations).all(|n| {
+                let (home, at) = (stickers[piece][n], stickers[position][(n + orientation) % orientations]);
+                mask.style(home) == StickerStyle::Ignored || home / 9 == at / 9
+            })
+        };
+        let moves: Vec<[usize; PIECE_STATES]> = Move::ALL.iter().map(|&mv| piece_moves(mv, corners)).collect();
+        let size = PIECE_STATES.pow(pieces.len() as u32);
+        let states = |index: usize| (0..pieces.len()).map(move |i| index / PIECE_STATES.pow(i as u32) % PIECE_STATES);
+        let mut distances: Vec<u8> = (0..size).map(|index| {
+            let positions: Vec<usize> = states(index).map(|state| state / orientations).collect();
+            let distinct = (1..positions.len()).all(|i| !positions[..i].contains(&positions[i]));
+            if distinct && states(index).zip(&pieces).all(|(state, &piece)| solved(piece, state)) { 0 } else { u8::MAX }
+        }).collect();
+        // Moves come with their inverse, so searching forward from the solved states gives the distances back to them
+        let (mut depth, mut furthest) = (0, 0);
+        while depth <= furthest {
+            for index in 0..size {
+                if distances[index] != depth {
+                    continue;
+                }
+                for (mv, moved) in Move::ALL.iter().zip(&moves) {
+                    let next = states(index).enumerate().map(|(i, state)| moved[state] * PIECE_STATES.pow(i as u32)).sum::<usize>();
+                    let distance = depth + metric.cost(*mv);
+                    if distance < distances[next] {
+                        distances[next] = distance;
+                        furthest = furthest.max(distance);
+                    }
+                }
+            }
+            depth += 1;
+        }
+        Self { corners, pieces, distances }
+    }
+
+    fn distance(&self, cube: &CubieCube) -> u8 {
+        let state = |piece: usize| piece_state(cube, self.corners, piece);
+        let index = self.pieces.iter().rev().fold(0, |index, &piece| index * PIECE_STATES + state(piece));
+        self.distances[index]
+    }
+}
+
+/// Position and orientation of a corner or an edge, as one number below [PIECE_STATES].
+fn piece_state(cube: &CubieCube, corners: bool, piece: usize) -> usize {
+    if corners {
+        let i = cube.cp.iter().position(|&c| c as usize == piece).unwrap();
+        i * 3 + cube.co[i] as usize
+    } else {
+        let i = cube.ep.iter().position(|&e| e as usize == piece).unwrap();
+        i * 2 + cube.eo[i] as usize
+    }
+}
+
+/// The state every piece state goes to with `mv`.
+fn piece_moves(mv: Move, corners: bool) -> [usize; PIECE_STATES] {
+    let turn = move_cube(mv);
+    let orientations = if corners { 3 } else { 2 };
+    let mut moved = [0; PIECE_STATES];
+    // On a solved cube every piece is at the position with its own number
+    for position in 0..PIECE_STATES / orientations {
+        let before = piece_state(&CubieCube::SOLVED, corners, position);
+        let after = piece_state(&turn, corners, position);
+        for orientation in 0..orientations {
+            moved[before + orientation] = after - after % orientations + (after + orientation) % orientations;
+        }
+    }
+    moved
+}
+
+///
+/// What a solve has to reach, the solved state or the one of a [StickerMask], and a lower bound
+/// of the moves to get there.
+///
+struct Goal<'a> {
+    /// The pattern databases whose pieces all have to be solved.
+    tables: Vec<&'a Table>,
+    /// Only with a mask, the databases are enough otherwise.
+    groups: Vec<PieceGroup>,
+}
+
+impl<'a> Goal<'a> {
+    fn new(databases: &'a PatternDatabases, mask: &StickerMask, metric: Metric) -> Self {
+        if mask.is_full() {
+            return Self { tables: databases.tables.iter().collect(), groups: vec![] };
+        }
+        let (corner_facelets, edge_facelets) = (corner_facelets(), edge_facelets());
+        let counts = |stickers: &[usize]| stickers.iter().all(|&home| mask.style(home) != StickerStyle::Ignored);
+        let tables = databases.tables.iter().filter(|table| match table.pattern {
+            Pattern::Corners => corner_facelets.iter().all(|stickers| counts(stickers)),
+            Pattern::Edges(first) => edge_facelets[first..first + 6].iter().all(|stickers| counts(stickers)),
+        }).collect();
+        let counting = |stickers: &[usize]| stickers.iter().any(|&home| mask.style(home) != StickerStyle::Ignored);
+        let corners: Vec<usize> = (0..8).filter(|&corner| counting(&corner_facelets[corner])).collect();
+        let edges: Vec<usize> = (0..12).filter(|&edge| counting(&edge_facelets[edge])).collect();
+        let groups = corners.chunks(GROUP_SIZE as usize).map(|pieces| PieceGroup::new(mask, metric, true, pieces.to_vec()))
+            .chain(edges.chunks(GROUP_SIZE as usize).map(|pieces| PieceGroup::new(mask, metric, false, pieces.to_vec())))
+            .collect();
+        Self { tables, groups }
+    }
+
+    /// A lower bound of the moves needed to reach the goal from `cube`, 0 only if it's reached.
+    fn distance(&self, cube: &CubieCube) -> u8 {
+        let tables = self.tables.iter().map(|table| table.get(table.pattern.index(cube)));
+        let groups = self.groups.iter().map(|group| group.distance(cube));
+        tables.chain(groups).max().unwrap_or(0)
+    }
+}
+
+/// Stops an [OptimalSolver] from another thread, see [OptimalSolver::cancel_token].
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How far an [OptimalSolver] got, reported while it searches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Progress {
+    /// Length of the solutions being searched, no shorter solution exists.
+    pub depth: u8,
+    /// How much of this depth was searched, from 0.0 to 1.0.
+    pub done: f32,
+    /// States looked at so far.
+    pub nodes: u64,
+    pub solutions: usize,
+}
+
+///
+/// Finds the shortest solutions of a state with IDA*, guided by pattern databases like Korf's solver.
+///
+/// The databases take about 90 megabytes and a while to build, which happens on the first solve
+/// (or with [OptimalSolver::prepare]) and is shared by all solvers with the same tables. Limiting their
+/// depth with [OptimalSolver::table_depth] makes them much faster to build, and the search slower.
+///
+/// Meant for short distances, fewest moves analysis and checking that a solution is optimal:
+/// a random state can take hours.
+///
+pub struct OptimalSolver {
+    metric: Metric,
+    max_solutions: usize,
+    threads: usize,
+    table_depth: u8,
+    mask: StickerMask,
+    tables: OnceLock<Arc<PatternDatabases>>,
+    cancel: CancelToken,
+}
+
+impl Default for OptimalSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OptimalSolver {
+    pub fn new() -> Self {
+        #[cfg(not(target_family = "wasm"))]
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        #[cfg(target_family = "wasm")]
+        let threads = 1;
+        Self {
+            metric: Metric::default(),
+            max_solutions: 1,
+            threads,
+            table_depth: MAX_TABLE_DEPTH,
+            mask: StickerMask::FULL,
+            tables: OnceLock::new(),
+            cancel: CancelToken::default(),
+        }
+    }
+
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Stop after finding this many solutions of the optimal length.
+    pub fn max_solutions(mut self, max_solutions: usize) -> Self {
+        self.max_solutions = max_solutions.max(1);
+        self
+    }
+
+    /// Threads to search with. There are no threads on the web, where this has no effect.
+    pub fn threads(mut self, threads: usize) -> Self {
+        if cfg!(not(target_family = "wasm")) {
+            self.threads = threads.max(1);
+        }
+        self
+    }
+
+    /// Only build the pattern databases up to this many moves.
+    pub fn table_depth(mut self, depth: u8) -> Self {
+        self.table_depth = depth.min(MAX_TABLE_DEPTH);
+        self.tables = OnceLock::new();
+        self
+    }
+
+    ///
+    /// Only solve the stickers `mask` doesn't ignore, like the cross with [MaskPreset::Cross](super::MaskPreset::Cross).
+    /// Pieces whose stickers are all ignored can end up anywhere.
+    ///
+    pub fn mask(mut self, mask: StickerMask) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    ///
+    /// A token that cancels the solve that is running, or the next one if none is, from any thread.
+    /// That includes building the pattern databases. Solves after that one aren't affected.
+    ///
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    /// Build the pattern databases now instead of on the first solve. Fails if cancelled, like a solve.
+    pub fn prepare(&self) -> Result<(), Error> {
+        self.once(|| self.databases().map(|_| ()))
+    }
+
+    /// Run a solve or a preparation, which a cancel stops. A cancel only stops one of them.
+    fn once<T>(&self, run: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+        let result = run();
+        self.cancel.0.store(false, Ordering::Relaxed);
+        result
+    }
+
+    fn databases(&self) -> Result<&PatternDatabases, Error> {
+        if let Some(tables) = self.tables.get() {
+            return Ok(tables);
+        }
+        // Solvers with the same depth share their databases
+        static BUILT: Mutex<Vec<(u8, Arc<PatternDatabases>)>> = Mutex::new(vec![]);
+        let mut built = BUILT.lock().unwrap();
+        let tables = match built.iter().find(|(depth, _)| *depth == self.table_depth) {
+            Some((_, tables)) => tables.clone(),
+            None => {
+                let tables = PatternDatabases::generate(self.table_depth, self.threads, &self.cancel.0).ok_or(Error::Cancelled)?;
+                let tables = Arc::new(tables);
+                built.push((self.table_depth, tables.clone()));
+                tables
+            }
+        };
+        Ok(self.tables.get_or_init(|| tables))
+    }
+
+    ///
+    /// All the optimal solutions of `cube`, up to [OptimalSolver::max_solutions] of them.
+    /// `progress` is called on this thread every time a part of the search is done.
+    /// Fails if the state can't be solved or the solver was cancelled.
+    ///
+    pub fn solve(&self, cube: &CubieCube, progress: impl FnMut(&Progress)) -> Result<Vec<Vec<Move>>, Error> {
+        self.once(|| self.search(cube, progress))
+    }
+
+    fn search(&self, cube: &CubieCube, mut progress: impl FnMut(&Progress)) -> Result<Vec<Vec<Move>>, Error> {
+        cube.check_solvable()?;
+        let goal = Goal::new(self.databases()?, &self.mask, self.metric);
+        let nodes = AtomicU64::new(0);
+        let solutions = Mutex::new(vec![]);
+        let enough = AtomicBool::new(false);
+        for depth in goal.distance(cube)..=self.metric.diameter() {
+            let prefixes = self.prefixes(depth);
+            let mut report = Progress { depth, done: 0.0, nodes: nodes.load(Ordering::Relaxed), solutions: 0 };
+            progress(&report);
+            let search = |i: usize| {
+                let prefix: &Vec<Move> = &prefixes[i];
+                let mut search = Search {
+                    goal: &goal,
+                    metric: self.metric,
+                    depth,
+                    max_solutions: self.max_solutions,
+                    cancelled: &self.cancel.0,
+                    enough: &enough,
+                    solutions: &solutions,
+                    nodes: 0,
+                };
+                let start = prefix.iter().fold(*cube, |cube, &mv| cube * move_cube(mv));
+                search.search(&start, self.metric.length(prefix) as u8, &mut prefix.clone());
+                nodes.fetch_add(search.nodes, Ordering::Relaxed);
+            };
+            let mut done = 0;
+            parallel(self.threads, prefixes.len(), search, || {
+                done += 1;
+                report.done = done as f32 / prefixes.len() as f32;
+                report.nodes = nodes.load(Ordering::Relaxed);
+                report.solutions = solutions.lock().unwrap().len();
+                progress(&report);
+            });
+            if self.cancel.is_cancelled() {
+                return Err(Error::Cancelled);
+            }
+            let mut solutions = std::mem::take(&mut *solutions.lock().unwrap());
+            if !solutions.is_empty() {
+                // Threads find them in any order
+                solutions.sort_by_key(|moves: &Vec<Move>| moves.iter().map(|mv| Move::ALL.iter().position(|m| m == mv)).collect::<Vec<_>>());
+                solutions.truncate(self.max_solutions);
+                return Ok(solutions);
+            }
+        }
+        Err(Error::Validation("No solution found".to_string()))
+    }
+
+    /// Convenience for [OptimalSolver::solve] from a facelet string, without progress reports.
+    pub fn solve_facelets(&self, facelets: &str) -> Result<Vec<Vec<Move>>, Error> {
+        self.solve(&CubieCube::from_facelets(facelets)?, |_| {})
+    }
+
+    /// The first moves of the search, each searched on its own: two moves, or one if it already reaches `depth`.
+    fn prefixes(&self, depth: u8) -> Vec<Vec<Move>> {
+        if depth == 0 {
+            return vec![vec![]];
+        }
+        let mut prefixes = vec![];
+        for first in Move::ALL {
+            if self.metric.cost(first) >= depth {
+                prefixes.push(vec![first]);
+                continue;
+            }
+            prefixes.extend(Move::ALL.iter().filter(|&&second| follows(first, second)).map(|&second| vec![first, second]));
+        }
+        prefixes
+    }
+}
+
+/// Whether `next` is worth trying after `last`: turning the same face twice is one move,
+/// and opposite faces are only turned in one order as they commute.
+fn follows(last: Move, next: Move) -> bool {
+    next.face() != last.face() && !(next.face() == OPPOSITE[last.face()] && next.face() < last.face())
+}
+
+/// One thread's part of a search at a given depth.
+struct Search<'a> {
+    goal: &'a Goal<'a>,
+    metric: Metric,
+    depth: u8,
+    max_solutions: usize,
+    cancelled: &'a AtomicBool,
+    /// Set once enough solutions were found.
+    enough: &'a AtomicBool,
+    solutions: &'a Mutex<Vec<Vec<Move>>>,
+    nodes: u64,
+}
+
+impl Search<'_> {
+    fn search(&mut self, cube: &CubieCube, cost: u8, path: &mut Vec<Move>) {
+        if self.cancelled.load(Ordering::Relaxed) || self.enough.load(Ordering::Relaxed) {
+            return;
+        }
+        self.nodes += 1;
+        let distance = self.goal.distance(cube);
+        if cost + distance > self.depth {
+            return;
+        }
+        if distance == 0 {
+            // Solutions that go through the solved state are longer than the shortest one
+            if cost == self.depth {
+                let mut solutions = self.solutions.lock().unwrap();
+                solutions.push(path.clone());
+                if solutions.len() >= self.max_solutions {
+                    self.enough.store(true, Ordering::Relaxed);
+                }
+            }
+            return;
+        }
+        for mv in Move::ALL {
+            if path.last().is_some_and(|&last| !follows(last, mv)) {
+                continue;
+            }
+            path.push(mv);
+            self.search(&(*cube * move_cube(mv)), cost + self.metric.cost(mv), path);
+            path.pop();
+        }
+    }
+}
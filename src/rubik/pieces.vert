@@ -0,0 +1,49 @@
+uniform mat4 viewProjection;
+uniform mat4 modelMatrix;
+
+in vec3 position;
+in vec3 normal;
+in vec2 uv_coordinates;
+// Face of the piece the vertex is on, in L, U, F, D, R, B order
+in float face;
+in vec3 sticker_offset;
+
+// Rows of the transformation of the instance
+in vec4 row1;
+in vec4 row2;
+in vec4 row3;
+
+// Face colors of the instance, transparent for faces without a sticker
+in vec4 color0;
+in vec4 color1;
+in vec4 color2;
+in vec4 color3;
+in vec4 color4;
+in vec4 color5;
+
+out vec3 pos;
+out vec3 nor;
+out vec2 uvs;
+out vec4 col;
+
+void main()
+{
+    mat4 transform;
+    transform[0] = vec4(row1.x, row2.x, row3.x, 0.0);
+    transform[1] = vec4(row1.y, row2.y, row3.y, 0.0);
+    transform[2] = vec4(row1.z, row2.z, row3.z, 0.0);
+    transform[3] = vec4(row1.w, row2.w, row3.w, 1.0);
+
+    vec4 colors[6] = vec4[6](color0, color1, color2, color3, color4, color5);
+    col = colors[int(face + 0.5)];
+
+    // Same test as sticker() in rubik.frag, only faces with a color get a sticker
+    vec3 local = col.a < 0.5 ? position : position + sticker_offset;
+    vec4 worldPosition = modelMatrix * transform * vec4(local, 1.0);
+    gl_Position = viewProjection * worldPosition;
+    pos = worldPosition.xyz;
+
+    // Pieces are only turned and moved, and whole cubes are scaled evenly if at all
+    nor = normalize(mat3(modelMatrix) * mat3(transform) * normal);
+    uvs = uv_coordinates;
+}
@@ -0,0 +1,286 @@
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use super::{apply_moves, Cube, Move, FACELETS};
+use crate::Error;
+
+/// Bounds of [Replay::move_time], a frame at 60 Hz and a leisurely turn.
+const MIN_MOVE_TIME: f32 = 16.0;
+const MAX_MOVE_TIME: f32 = 1200.0;
+
+/// A move and when it finished animating, in milliseconds since the recording started.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimedMove {
+    pub mv: Move,
+    pub time: f32,
+}
+
+/// The steps of a CFOP solve, in the order they're finished.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    Cross,
+    F2l,
+    Oll,
+    /// The cube is solved.
+    Pll,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 4] = [Stage::Cross, Stage::F2l, Stage::Oll, Stage::Pll];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Cross => "Cross",
+            Stage::F2l => "F2L",
+            Stage::Oll => "OLL",
+            Stage::Pll => "PLL",
+        }
+    }
+}
+
+///
+/// The furthest stage of a CFOP solve the cube is at, with the cross on any face.
+/// Nothing if there isn't a solved cross anywhere, and an error if `facelets` isn't a facelet string.
+///
+pub fn stage(facelets: &str) -> Result<Option<Stage>, Error> {
+    Cube::validate_facelet_str(facelets)?;
+    let facelets = facelets.as_bytes();
+    let centers: [u8; 6] = std::array::from_fn(|face| facelets[face * 9 + 4]);
+    // A piece is solved when all its stickers match the centers of their faces
+    let mut solved = [true; 27];
+    for (facelet, &cubelet) in FACELETS.iter().enumerate() {
+        solved[cubelet] &= facelets[facelet] == centers[facelet / 9];
+    }
+    let position = |cubelet: usize| [cubelet as i32 / 9 - 1, 1 - cubelet as i32 / 3 % 3, cubelet as i32 % 3 - 1];
+    let furthest = (0..6).filter_map(|bottom| {
+        // Distance of every piece from the bottom layer along the axis of the bottom face
+        let axis = [0, 1, 2, 1, 0, 2][bottom];
+        let sign = if matches!(bottom, 0 | 3 | 5) { -1 } else { 1 };
+        let height = |cubelet: usize| 1 - position(cubelet)[axis] * sign;
+        let is_edge = |cubelet: usize| position(cubelet).iter().filter(|&&c| c != 0).count() == 2;
+        let cross = (0..27).filter(|&c| height(c) == 0 && is_edge(c)).all(|c| solved[c]);
+        let f2l = (0..27).filter(|&c| height(c) < 2).all(|c| solved[c]);
+        let top = [4, 3, 5, 1, 0, 2][bottom];
+        let oriented = facelets[top * 9..top * 9 + 9].iter().all(|&c| c == centers[top]);
+        match (cross, f2l, oriented) {
+            _ if solved.iter().all(|&s| s) => Some(Stage::Pll),
+            (_, true, true) => Some(Stage::Oll),
+            (_, true, false) => Some(Stage::F2l),
+            (true, _, _) => Some(Stage::Cross),
+            _ => None,
+        }
+    }).max();
+    Ok(furthest)
+}
+
+/// A stage of a recorded solve, see [Reconstruction::splits].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Split {
+    pub stage: Stage,
+    /// Moves made during the stage.
+    pub moves: usize,
+    /// Time spent on the stage, in milliseconds.
+    pub time: f32,
+    /// When the stage was finished, in milliseconds since the recording started.
+    pub end: f32,
+}
+
+impl Split {
+    /// Turns per second during the stage.
+    pub fn tps(&self) -> f32 {
+        tps(self.moves, self.time)
+    }
+}
+
+fn tps(moves: usize, time: f32) -> f32 {
+    if time > 0.0 {
+        moves as f32 / time * 1000.0
+    } else {
+        0.0
+    }
+}
+
+///
+/// The moves of a solve and when they were made, with the state it started from.
+/// Recorded with [Cube::record], replayed with [Replay].
+///
+/// It can be saved as JSON to load it back, or as text with a line per stage of the solve,
+/// like the reconstructions speedcubers share.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reconstruction {
+    /// Facelet string of the cube when the recording started.
+    pub start: String,
+    pub moves: Vec<TimedMove>,
+}
+
+impl Reconstruction {
+    /// Time from the start of the recording to the last move, in milliseconds.
+    pub fn duration(&self) -> f32 {
+        self.moves.last().map_or(0.0, |last| last.time)
+    }
+
+    /// Turns per second over the whole recording.
+    pub fn tps(&self) -> f32 {
+        tps(self.moves.len(), self.duration())
+    }
+
+    /// The facelet string after all the moves.
+    pub fn end(&self) -> String {
+        let moves: Vec<Move> = self.moves.iter().map(|timed| timed.mv).collect();
+        apply_moves(&self.start, &moves).unwrap_or_else(|_| self.start.clone())
+    }
+
+    ///
+    /// Where each stage of the solve was first reached, see [stage].
+    /// Stages already done when the recording started are left out, and so are the moves after
+    /// the last stage that was reached.
+    ///
+    pub fn splits(&self) -> Vec<Split> {
+        let mut splits = vec![];
+        let mut state = self.start.clone();
+        let Ok(mut reached) = stage(&state) else {
+            return splits;
+        };
+        let (mut moves, mut since) = (0, 0.0);
+        for timed in &self.moves {
+            let Ok(next) = apply_moves(&state, &[timed.mv]) else {
+                break;
+            };
+            state = next;
+            moves += 1;
+            let Ok(now) = stage(&state) else {
+                break;
+            };
+            if now > reached {
+                // Stages skipped on the way, like an OLL skip, take no moves and no time
+                for skipped in Stage::ALL.into_iter().filter(|&s| Some(s) > reached && Some(s) <= now) {
+                    splits.push(Split { stage: skipped, moves, time: timed.time - since, end: timed.time });
+                    (moves, since) = (0, timed.time);
+                }
+                reached = now;
+            }
+        }
+        splits
+    }
+
+    pub fn to_json(&self) -> String {
+        let moves: Vec<Value> = self.moves.iter().map(|timed| json!([timed.mv.to_string(), timed.time.round()])).collect();
+        json!({ "start": self.start, "moves": moves }).to_string()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let value: Value = serde_json::from_str(json).map_err(|e| Error::Parse(format!("Invalid JSON: {}", e)))?;
+        let missing = |what: &str| Error::Parse(format!("Missing the {}", what));
+        let start = value.get("start").and_then(Value::as_str).ok_or_else(|| missing("start state"))?;
+        Cube::validate_facelet_str(start)?;
+        let mut moves = vec![];
+        for (i, timed) in value.get("moves").and_then(Value::as_array).ok_or_else(|| missing("moves"))?.iter().enumerate() {
+            let invalid = || Error::Parse(format!("Invalid move {}", i + 1));
+            let mv = timed.get(0).and_then(Value::as_str).ok_or_else(invalid)?;
+            let time = timed.get(1).and_then(Value::as_f64).ok_or_else(invalid)?;
+            moves.push(TimedMove { mv: mv.parse()?, time: time as f32 });
+        }
+        Ok(Self { start: start.to_string(), moves })
+    }
+}
+
+impl fmt::Display for Reconstruction {
+    /// A line of moves per stage with its split, then the totals.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let notation = |moves: &[TimedMove]| moves.iter().map(|timed| timed.mv.to_string()).collect::<Vec<_>>().join(" ");
+        let mut done = 0;
+        for split in self.splits() {
+            let moves = &self.moves[done..done + split.moves];
+            done += split.moves;
+            writeln!(
+                f,
+                "{} // {}: {} moves in {:.3}s, {:.2} TPS",
+                notation(moves), split.stage.name(), split.moves, split.time / 1000.0, split.tps(),
+            )?;
+        }
+        if done < self.moves.len() {
+            writeln!(f, "{}", notation(&self.moves[done..]))?;
+        }
+        write!(f, "// {} moves in {:.3}s, {:.2} TPS", self.moves.len(), self.duration() / 1000.0, self.tps())
+    }
+}
+
+///
+/// Records the moves a [Cube] applies, see [Cube::record].
+/// Times are the ones passed to [Cube::animate] when the moves finished.
+///
+pub(crate) struct Recorder {
+    start: String,
+    start_time: f32,
+    moves: Vec<TimedMove>,
+}
+
+impl Recorder {
+    pub(crate) fn new(start: String, start_time: f32) -> Self {
+        Self { start, start_time, moves: vec![] }
+    }
+
+    pub(crate) fn record(&mut self, mv: Move, time: f32) {
+        self.moves.push(TimedMove { mv, time: time - self.start_time });
+    }
+
+    pub(crate) fn finish(self) -> Reconstruction {
+        Reconstruction { start: self.start, moves: self.moves }
+    }
+}
+
+///
+/// Plays a [Reconstruction] back on a cube, queueing every move when it was made.
+///
+/// The cube is set to the start of the reconstruction on the first [Replay::update]. Moves are
+/// queued with [Cube::queue] as the time comes, so the cube should play them with
+/// [Playback::Fixed](super::Playback::Fixed) and a move time of at most [Replay::move_time],
+/// or they'll lag behind.
+///
+pub struct Replay {
+    reconstruction: Reconstruction,
+    /// How much faster than the recording to play, 2.0 takes half the time.
+    speed: f32,
+    started: Option<f32>,
+    next: usize,
+}
+
+impl Replay {
+    pub fn new(reconstruction: Reconstruction, speed: f32) -> Self {
+        Self { reconstruction, speed: speed.max(0.01), started: None, next: 0 }
+    }
+
+    /// Queue the moves that are due at `time`, in the same milliseconds as [Cube::animate].
+    pub fn update(&mut self, cube: &mut Cube, time: f32) -> Result<(), Error> {
+        let started = match self.started {
+            Some(started) => started,
+            None => {
+                cube.set_state(&self.reconstruction.start)?;
+                *self.started.insert(time)
+            }
+        };
+        let elapsed = (time - started) * self.speed;
+        let due = self.reconstruction.moves[self.next..].iter().take_while(|timed| timed.time <= elapsed).count();
+        cube.queue(self.reconstruction.moves[self.next..self.next + due].iter().map(|timed| timed.mv));
+        self.next += due;
+        Ok(())
+    }
+
+    ///
+    /// How long a move can take to animate for the replay to keep the pace of the recording, in
+    /// milliseconds: three quarters of the shortest time between two moves at the speed of the replay.
+    /// The rest leaves room for the frames a move waits to start and to finish.
+    ///
+    pub fn move_time(&self) -> f32 {
+        let mut last = 0.0;
+        let gaps = self.reconstruction.moves.iter().map(|timed| timed.time - std::mem::replace(&mut last, timed.time));
+        (gaps.fold(f32::INFINITY, f32::min) * 0.75 / self.speed).clamp(MIN_MOVE_TIME, MAX_MOVE_TIME)
+    }
+
+    /// Whether all the moves have been queued.
+    pub fn is_done(&self) -> bool {
+        self.next == self.reconstruction.moves.len()
+    }
+}
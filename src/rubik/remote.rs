@@ -0,0 +1,132 @@
+use std::collections::VecDeque;
+
+use three_d::Srgba;
+
+use super::{CubeAppearance, Move, MoveGroup, Penalty, Playback, Reconstruction, Session, StickerMask};
+
+/// Something to do to the cube of a running [RubikScene](super::RubikScene).
+#[derive(Clone, Debug)]
+pub enum CubeCommand {
+    Queue(Vec<Move>),
+    /// Queue moves of opposite faces that turn at the same time.
+    QueueGroup(MoveGroup),
+    /// Replace the state with a facelet string, see [Cube::from_facelet_str](super::Cube::from_facelet_str).
+    SetState(String),
+    /// Queue a random scramble made from the given seed.
+    Scramble(u64),
+    /// Queue the moves that bring the cube back to solved, if they're known.
+    Solve,
+    Pause,
+    Resume,
+    SetSpeed(f32),
+    /// Change how queued moves are paced, see [Playback](super::Playback).
+    SetPlayback(Playback),
+    /// Change the sticker colors, in the order of the facelet letters.
+    SetColors([Srgba; 6]),
+    SetAppearance(CubeAppearance),
+    /// Gray out the stickers that don't matter for a step, see [StickerMask].
+    SetMask(StickerMask),
+    /// Switch to painting the stickers, starting from the current state. See [FaceletEditor](super::FaceletEditor).
+    Edit,
+    /// Leave the editor, turning the painted stickers into the cube if they make a possible state.
+    Apply,
+    /// Switch to the speedcubing timer, see [Timer](super::Timer).
+    Time,
+    /// Leave the timer, keeping its session.
+    StopTiming,
+    /// Replace the solves of the timer, like with an imported session.
+    SetSession(Session),
+    /// Change the penalty of the last timed solve.
+    Penalize(Penalty),
+    /// Play a recorded solve back, scaling its speed by the given factor. See [Replay](super::Replay).
+    Replay(Reconstruction, f32),
+}
+
+///
+/// Shared between a [RubikScene](super::RubikScene) and whatever controls it, usually behind an `Rc<RefCell<_>>`.
+///
+/// Commands are picked up by the scene at the start of the next frame, and the scene
+/// writes back the state of the cube and calls the listeners when moves finish.
+///
+#[derive(Default)]
+pub struct CubeRemote {
+    commands: VecDeque<CubeCommand>,
+    state: String,
+    solution_known: bool,
+    readout: String,
+    session: Session,
+    reconstruction: Option<Reconstruction>,
+    move_listeners: Vec<Box<dyn FnMut(Move)>>,
+}
+
+impl CubeRemote {
+    pub fn send(&mut self, command: CubeCommand) {
+        self.commands.push_back(command);
+    }
+
+    /// The facelet string of the cube as of the last frame. In the editor unpainted stickers are `-`.
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    /// Whether [CubeCommand::Solve] would find the moves that solve the cube as of the last frame,
+    /// see [Cube::solution](super::Cube::solution).
+    pub fn solution_known(&self) -> bool {
+        self.solution_known
+    }
+
+    /// What the timer shows as of the last frame, see [Timer::readout](super::Timer::readout).
+    pub fn readout(&self) -> &str {
+        &self.readout
+    }
+
+    /// The solves of the timer as of the last frame.
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// The recording of the last timed solve.
+    pub fn reconstruction(&self) -> Option<&Reconstruction> {
+        self.reconstruction.as_ref()
+    }
+
+    /// Call `listener` every time a move finishes animating.
+    pub fn on_move_finished(&mut self, listener: impl FnMut(Move) + 'static) {
+        self.move_listeners.push(Box::new(listener));
+    }
+
+    pub(crate) fn take_commands(&mut self) -> VecDeque<CubeCommand> {
+        std::mem::take(&mut self.commands)
+    }
+
+    pub(crate) fn set_state(&mut self, state: String) {
+        self.state = state;
+    }
+
+    pub(crate) fn set_solution_known(&mut self, known: bool) {
+        self.solution_known = known;
+    }
+
+    pub(crate) fn set_readout(&mut self, readout: String) {
+        self.readout = readout;
+    }
+
+    pub(crate) fn set_session(&mut self, session: Session) {
+        self.session = session;
+    }
+
+    pub(crate) fn set_reconstruction(&mut self, reconstruction: Option<Reconstruction>) {
+        self.reconstruction = reconstruction;
+    }
+
+    /// Listeners are taken out while they run so they can send commands without a double borrow.
+    pub(crate) fn take_move_listeners(&mut self) -> Vec<Box<dyn FnMut(Move)>> {
+        std::mem::take(&mut self.move_listeners)
+    }
+
+    /// Put back the listeners from [CubeRemote::take_move_listeners], before any added in the meantime.
+    pub(crate) fn restore_move_listeners(&mut self, mut listeners: Vec<Box<dyn FnMut(Move)>>) {
+        listeners.append(&mut self.move_listeners);
+        self.move_listeners = listeners;
+    }
+}
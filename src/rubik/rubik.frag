@@ -3,21 +3,53 @@ in vec3 nor;
 in vec2 uvs;
 
 uniform vec2 stickerData;
+uniform vec4 bodyColor;
+uniform vec4 maskedColor;
+uniform float stickerless;
+uniform float opacity;
+
+#ifdef LIT
+in vec3 pos;
+
+uniform vec3 cameraPosition;
+// roughness, metallic
+uniform vec2 stickerSurface;
+uniform vec2 bodySurface;
+#endif
 
 layout (location = 0) out vec4 outColor;
 
-vec4 sticker(vec4 colors, vec2 uv) {
+// 1.0 where the sticker is, 0.0 where the plastic shows
+float sticker(vec4 colors, vec2 uv) {
+    // Faces without a color are inner faces, they are always plastic
+    if (colors.a < 0.5) {
+        return 0.0;
+    }
+    if (stickerless > 0.5) {
+        return 1.0;
+    }
     vec2 shifted = uv*2.0 - vec2(1, 1);
     if ((abs(shifted.x) < stickerData.x && abs(shifted.y) < stickerData.x + stickerData.y) ||
         (abs(shifted.x) < stickerData.x + stickerData.y && abs(shifted.y) < stickerData.x) ||
         dot(abs(shifted) - vec2(stickerData.x, stickerData.x), abs(shifted) - vec2(stickerData.x, stickerData.x)) < stickerData.y * stickerData.y) {
-        return colors;
+        return 1.0;
     }
-    return vec4(0.0, 0.0, 0.0, 1.0);
+    return 0.0;
 }
 
 void main() {
-    outColor = sticker(col, uvs);
-    // outColor = col;
+    float mask = sticker(col, uvs);
+    // Stickers ignored by a sticker mask come with a partly transparent color
+    vec4 color = col.a < 0.8 ? maskedColor : col;
+    vec4 surface = mix(bodyColor, color, mask);
+#ifdef LIT
+    vec2 surface_params = mix(bodySurface, stickerSurface, mask);
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+    outColor.rgb = calculate_lighting(cameraPosition, surface.rgb, pos, normal, surface_params.y, surface_params.x, 1.0);
+    outColor.rgb = tone_mapping(outColor.rgb);
+#else
+    outColor.rgb = surface.rgb;
+#endif
+    outColor.a = mix(bodyColor.a, 1.0, mask) * opacity;
     outColor.rgb = color_mapping(outColor.rgb);
 }
@@ -0,0 +1,241 @@
+use three_d::Srgba;
+use three_d_asset::{Texture2D as CpuTexture, TextureData};
+
+use super::{check_facelets, Cube, CubeAnimationOptions, COLOR_ORDER};
+use crate::Error;
+
+/// A sticker is doubtful when its color is further than this fraction of the distance to the next closest color.
+const DOUBTFUL_RATIO: f32 = 0.8;
+
+/// A color in the CIE L*a*b* space, where distances roughly match how different colors look.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl Lab {
+    /// Convert an sRGB color, assuming a D65 white point.
+    pub fn from_srgb(r: u8, g: u8, b: u8) -> Self {
+        let linear = |c: u8| {
+            let c = c as f32 / 255.0;
+            if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+        };
+        let (r, g, b) = (linear(r), linear(g), linear(b));
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+        let f = |t: f32| {
+            if t > 216.0 / 24389.0 { t.cbrt() } else { (24389.0 / 27.0 * t + 16.0) / 116.0 }
+        };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        Self {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+
+    ///
+    /// How different two colors look. Lightness counts for half, since it's what
+    /// changes the most between a shadowed and a lit sticker of the same color.
+    ///
+    pub fn distance(&self, other: &Lab) -> f32 {
+        let dl = (self.l - other.l) * 0.5;
+        let da = self.a - other.a;
+        let db = self.b - other.b;
+        (dl * dl + da * da + db * db).sqrt()
+    }
+}
+
+impl From<Srgba> for Lab {
+    fn from(color: Srgba) -> Self {
+        Self::from_srgb(color.r, color.g, color.b)
+    }
+}
+
+///
+/// Where a face of the cube is in a photo, in pixels from the top left corner.
+/// The face should fill the area, with the stickers in a 3 by 3 grid.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FaceGrid {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl FaceGrid {
+    /// A grid covering the largest square in the center of an image of the given size.
+    pub fn centered(width: u32, height: u32) -> Self {
+        let size = width.min(height);
+        Self {
+            x: (width - size) / 2,
+            y: (height - size) / 2,
+            width: size,
+            height: size,
+        }
+    }
+}
+
+/// The colors sampled from the nine stickers of one face, row by row from the top left.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FaceScan {
+    pub stickers: [Lab; 9],
+}
+
+///
+/// Sample the nine stickers of a face from an image with 8 bit RGB or RGBA pixels.
+///
+/// Only the middle half of every sticker is used, and the median of each channel is taken
+/// so reflections and the edges of the stickers don't throw the color off.
+///
+pub fn scan_face(image: &CpuTexture, grid: FaceGrid) -> Result<FaceScan, Error> {
+    let (pixel, len): (Box<dyn Fn(usize) -> [u8; 3]>, usize) = match &image.data {
+        TextureData::RgbU8(data) => (Box::new(move |i| data[i]), data.len()),
+        TextureData::RgbaU8(data) => (Box::new(move |i| [data[i][0], data[i][1], data[i][2]]), data.len()),
+        _ => return Err(Error::Validation("Expected an image with 8 bit RGB or RGBA pixels".to_string())),
+    };
+    if (image.width as usize).checked_mul(image.height as usize) != Some(len) {
+        return Err(Error::Validation(format!("Expected {}x{} pixels", image.width, image.height)));
+    }
+    if grid.width < 3 || grid.height < 3 {
+        return Err(Error::Validation("The face grid is too small".to_string()));
+    }
+    let fits = |start: u32, size: u32, end: u32| start.checked_add(size).is_some_and(|last| last <= end);
+    if !fits(grid.x, grid.width, image.width) || !fits(grid.y, grid.height, image.height) {
+        return Err(Error::Validation(format!(
+            "The face grid doesn't fit in the {}x{} image",
+            image.width, image.height
+        )));
+    }
+
+    let mut stickers = [Lab { l: 0.0, a: 0.0, b: 0.0 }; 9];
+    for (i, sticker) in stickers.iter_mut().enumerate() {
+        let (row, col) = ((i / 3) as u32, (i % 3) as u32);
+        // In 64 bits, a third of a grid as wide as the largest image still has to fit
+        let cell = |start: u32, size: u32, n: u32| {
+            let (start, size, n) = (start as u64, size as u64, n as u64);
+            let from = start + size * n / 3 + size / 12;
+            let to = (start + size * (n + 1) / 3 - size / 12).max(from + 1);
+            from as u32..to as u32
+        };
+        let mut channels: [Vec<u8>; 3] = Default::default();
+        for y in cell(grid.y, grid.height, row) {
+            for x in cell(grid.x, grid.width, col) {
+                let rgb = pixel(y as usize * image.width as usize + x as usize);
+                for (channel, value) in channels.iter_mut().zip(rgb) {
+                    channel.push(value);
+                }
+            }
+        }
+        let [r, g, b] = channels.map(|mut channel| {
+            channel.sort_unstable();
+            channel[channel.len() / 2]
+        });
+        *sticker = Lab::from_srgb(r, g, b);
+    }
+    Ok(FaceScan { stickers })
+}
+
+///
+/// Work out the facelet string of a cube from scans of its six faces.
+///
+/// The scans are in the order of the facelet string (L, U, F, D, R, B) and every face has to be
+/// photographed in the orientation the facelet string uses, see [Cube::from_facelet_str].
+///
+/// The palette is only used to tell which center is which color. The stickers are then matched
+/// against the centers as they appear in the photos, nine stickers to a color, which copes with
+/// lighting that makes the colors look nothing like the palette.
+///
+/// Fails naming the stickers whose color is too close to another one to be sure of, and when the
+/// colors read make a state a real cube can't be in, see [check_facelets].
+///
+pub fn facelets_from_scans(scans: &[FaceScan; 6], palette: &[Srgba; 6]) -> Result<String, Error> {
+    let palette = palette.map(Lab::from);
+    let centers = scans.map(|scan| scan.stickers[4]);
+
+    // Six centers against six colors, trying every pairing is cheap enough
+    let mut best: Option<(f32, [usize; 6])> = None;
+    for_each_permutation(&mut [0, 1, 2, 3, 4, 5], 6, &mut |colors| {
+        let cost = (0..6).map(|face| centers[face].distance(&palette[colors[face]])).sum::<f32>();
+        if best.is_none_or(|(best_cost, _)| cost < best_cost) {
+            best = Some((cost, *colors));
+        }
+    });
+    let (_, center_colors) = best.unwrap();
+    let mut references = [centers[0]; 6];
+    for (face, &color) in center_colors.iter().enumerate() {
+        references[color] = centers[face];
+    }
+
+    // Closest matches first, until every color has its nine stickers
+    let mut colors: [Option<usize>; 54] = [None; 54];
+    let mut left = [8; 6];
+    let mut candidates = Vec::with_capacity(48 * 6);
+    for (face, scan) in scans.iter().enumerate() {
+        colors[face * 9 + 4] = Some(center_colors[face]);
+        for (i, sticker) in scan.stickers.iter().enumerate().filter(|(i, _)| *i != 4) {
+            for (color, reference) in references.iter().enumerate() {
+                candidates.push((sticker.distance(reference), face * 9 + i, color));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+    for &(_, facelet, color) in &candidates {
+        if colors[facelet].is_none() && left[color] > 0 {
+            colors[facelet] = Some(color);
+            left[color] -= 1;
+        }
+    }
+
+    // A sticker is only trusted if its color is clearly closer than any other
+    let doubtful: Vec<String> = (0..54).filter(|&facelet| {
+        let distance = |other: bool| candidates.iter()
+            .filter(|&&(_, f, color)| f == facelet && (Some(color) == colors[facelet]) != other)
+            .map(|&(distance, _, _)| distance)
+            .fold(f32::INFINITY, f32::min);
+        facelet % 9 != 4 && distance(false) > distance(true) * DOUBTFUL_RATIO
+    }).map(facelet_name).collect();
+    if !doubtful.is_empty() {
+        return Err(Error::Validation(format!("Can't tell the colors of the stickers at {}", doubtful.join(", "))));
+    }
+
+    let facelets = colors.iter()
+        .map(|color| color.map(|color| COLOR_ORDER[color].to_char()))
+        .collect::<Option<String>>()
+        .ok_or_else(|| Error::Validation("Some stickers didn't get a color".to_string()))?;
+    check_facelets(&facelets)?;
+    Ok(facelets)
+}
+
+/// Build a cube from the scans of its six faces, see [facelets_from_scans].
+pub fn cube_from_scans(
+    scans: &[FaceScan; 6],
+    palette: &[Srgba; 6],
+    anim: CubeAnimationOptions,
+    ctx: &three_d::Context,
+) -> Result<Cube, Error> {
+    let facelets = facelets_from_scans(scans, palette)?;
+    Cube::from_facelet_str(facelets, anim, ctx)
+}
+
+/// A facelet by its face and its number on the face, row by row from 1, like `F3`.
+fn facelet_name(facelet: usize) -> String {
+    format!("{}{}", ['L', 'U', 'F', 'D', 'R', 'B'][facelet / 9], facelet % 9 + 1)
+}
+
+/// Heap's algorithm, calls `f` with every ordering of `items`.
+fn for_each_permutation(items: &mut [usize; 6], k: usize, f: &mut impl FnMut(&[usize; 6])) {
+    if k == 1 {
+        f(items);
+        return;
+    }
+    for_each_permutation(items, k - 1, f);
+    for i in 0..k - 1 {
+        items.swap(if k.is_multiple_of(2) { i } else { 0 }, k - 1);
+        for_each_permutation(items, k - 1, f);
+    }
+}
@@ -0,0 +1,178 @@
+use std::sync::OnceLock;
+
+use three_d::{Mat3, Matrix, SquareMatrix, Vec3};
+
+use super::algs::{normal, NORMALS};
+use super::{facelet_at, Cube, Move, FACELETS, ROT_XY_CW, ROT_XZ_CW, SOLVED};
+use crate::Error;
+
+/// Reflection through the plane between L and R, which swaps the two faces.
+const MIRROR: Mat3 = Mat3::new(
+    -1.0, 0.0, 0.0,
+    0.0, 1.0, 0.0,
+    0.0, 0.0, 1.0
+);
+
+///
+/// One of the 48 symmetries of the cube: the 24 ways of holding it, and the same mirrored.
+///
+/// A symmetry acts on a state by turning (or mirroring) the whole cube, then recoloring it so that
+/// the centers are back on their faces. The result is the same position seen from elsewhere:
+/// the algorithms that solve it are the original ones with their faces changed by [Symmetry::transform_moves].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symmetry(u8);
+
+struct SymmetryTable {
+    matrix: Mat3,
+    /// Face every face goes to.
+    faces: [usize; 6],
+    /// Facelet every facelet goes to.
+    facelets: [usize; 54],
+}
+
+fn table() -> &'static [SymmetryTable] {
+    static TABLE: OnceLock<Vec<SymmetryTable>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        // Every rotation is a product of quarter turns around two axes
+        let mut rotations = vec![Mat3::identity()];
+        let mut i = 0;
+        while i < rotations.len() {
+            for generator in [ROT_XY_CW, ROT_XZ_CW] {
+                let rotation = generator * rotations[i];
+                if !rotations.contains(&rotation) {
+                    rotations.push(rotation);
+                }
+            }
+            i += 1;
+        }
+        let reflections: Vec<Mat3> = rotations.iter().map(|&rotation| rotation * MIRROR).collect();
+        rotations.into_iter().chain(reflections).map(|matrix| {
+            let faces = std::array::from_fn(|face| {
+                let moved = matrix * normal(face);
+                NORMALS.iter().position(|&(x, y, z)| moved == Vec3::new(x as f32, y as f32, z as f32)).unwrap()
+            });
+            let facelets = std::array::from_fn(|facelet| {
+                let cubelet = FACELETS[facelet] as i32;
+                let position = Vec3::new((cubelet / 9 - 1) as f32, (1 - cubelet / 3 % 3) as f32, (cubelet % 3 - 1) as f32);
+                facelet_at(matrix * (position + normal(facelet / 9) * 0.5)).unwrap()
+            });
+            SymmetryTable { matrix, faces, facelets }
+        }).collect()
+    })
+}
+
+impl Symmetry {
+    pub const IDENTITY: Symmetry = Symmetry(0);
+
+    /// All 48 symmetries, the 24 rotations first.
+    pub fn all() -> impl Iterator<Item = Symmetry> {
+        (0..48).map(Symmetry)
+    }
+
+    /// The 24 symmetries that don't mirror the cube.
+    pub fn rotations() -> impl Iterator<Item = Symmetry> {
+        (0..24).map(Symmetry)
+    }
+
+    /// Position in [Symmetry::all], to index tables with.
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+
+    /// How the symmetry moves points around the center of the cube.
+    pub fn matrix(&self) -> Mat3 {
+        table()[self.index()].matrix
+    }
+
+    /// Whether the cube is mirrored, which turns clockwise moves into counter clockwise ones.
+    pub fn is_reflection(&self) -> bool {
+        self.0 >= 24
+    }
+
+    /// The face that `face` ends up on.
+    pub fn face(&self, face: usize) -> usize {
+        table()[self.index()].faces[face]
+    }
+
+    pub fn inverse(&self) -> Symmetry {
+        Self::find(self.matrix().transpose())
+    }
+
+    /// This symmetry followed by `other`.
+    pub fn then(&self, other: Symmetry) -> Symmetry {
+        Self::find(other.matrix() * self.matrix())
+    }
+
+    fn find(matrix: Mat3) -> Symmetry {
+        Symmetry(table().iter().position(|sym| sym.matrix == matrix).unwrap() as u8)
+    }
+
+    /// The moves that do to a transformed state what `moves` do to the original.
+    pub fn transform_moves(&self, moves: &[Move]) -> Vec<Move> {
+        moves.iter().map(|mv| {
+            let amount = if self.is_reflection() { 4 - mv.amount() } else { mv.amount() };
+            Move::from_face(self.face(mv.face()), amount).unwrap()
+        }).collect()
+    }
+
+    /// Transform a facelet string, keeping its colors.
+    pub fn transform(&self, facelets: &str) -> Result<String, Error> {
+        let labels = labels(facelets)?;
+        let centers: Vec<char> = facelets.chars().skip(4).step_by(9).collect();
+        Ok(self.transform_labels(&labels).iter().map(|&face| centers[face as usize]).collect())
+    }
+
+    fn transform_labels(&self, labels: &[u8; 54]) -> [u8; 54] {
+        let sym = &table()[self.index()];
+        let mut transformed = [0; 54];
+        for (facelet, &face) in labels.iter().enumerate() {
+            transformed[sym.facelets[facelet]] = sym.faces[face as usize] as u8;
+        }
+        transformed
+    }
+}
+
+/// The face of every sticker, told by the color of the centers.
+fn labels(facelets: &str) -> Result<[u8; 54], Error> {
+    Cube::validate_facelet_str(facelets)?;
+    let facelets = facelets.as_bytes();
+    let centers: [u8; 6] = std::array::from_fn(|face| facelets[face * 9 + 4]);
+    if (1..6).any(|face| centers[..face].contains(&centers[face])) {
+        return Err(Error::Validation("Expected every center to have a different color".to_string()));
+    }
+    Ok(std::array::from_fn(|i| centers.iter().position(|&c| c == facelets[i]).unwrap() as u8))
+}
+
+fn colors(labels: &[u8; 54]) -> String {
+    labels.iter().map(|&face| SOLVED.as_bytes()[face as usize * 9] as char).collect()
+}
+
+///
+/// The same representative for all the states that are a symmetry of each other, and the symmetry
+/// that turns `facelets` into it. The colors of the state don't matter, only which stickers share
+/// a color with which center, and the representative has the colors of [SOLVED].
+///
+/// Two states have the same canonical form if they are the same case seen from another side,
+/// or mirrored, or on a cube with another color scheme.
+///
+pub fn canonical(facelets: &str) -> Result<(String, Symmetry), Error> {
+    let labels = labels(facelets)?;
+    let (canonical, symmetry) = Symmetry::all()
+        .map(|symmetry| (symmetry.transform_labels(&labels), symmetry))
+        .min()
+        .unwrap();
+    Ok((colors(&canonical), symmetry))
+}
+
+/// A symmetry that turns state `a` into state `b`, if they are the same up to symmetry and colors.
+pub fn find_symmetry(a: &str, b: &str) -> Result<Option<Symmetry>, Error> {
+    let (a, b) = (labels(a)?, labels(b)?);
+    Ok(Symmetry::all().find(|symmetry| symmetry.transform_labels(&a) == b))
+}
+
+/// The symmetries that leave a state as it is, only the identity for most of them.
+pub fn symmetries_of(facelets: &str) -> Result<Vec<Symmetry>, Error> {
+    let labels = labels(facelets)?;
+    Ok(Symmetry::all().filter(|symmetry| symmetry.transform_labels(&labels) == labels).collect())
+}
@@ -0,0 +1,505 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{json, Value};
+
+use crate::Error;
+
+/// Inspection the WCA allows before a solve gets a +2, in milliseconds.
+const INSPECTION: f64 = 15000.0;
+/// Inspection after which the solve is a DNF.
+const INSPECTION_LIMIT: f64 = 17000.0;
+
+/// Header of the CSV files csTimer exports. `P.1` is the time of the only phase, without the penalty.
+const CSV_HEADER: &str = "No.;Time;Comment;Scramble;Date;P.1";
+
+/// A penalty given to a solve.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Penalty {
+    #[default]
+    None,
+    /// Two seconds are added to the time.
+    PlusTwo,
+    /// Did not finish, the solve counts as worse than any time.
+    Dnf,
+}
+
+/// The result of a solve or an average. A DNF is worse than any time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SolveTime {
+    /// In milliseconds.
+    Time(u32),
+    Dnf,
+}
+
+impl fmt::Display for SolveTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SolveTime::Time(ms) if ms >= 60000 => write!(f, "{}:{:02}.{:03}", ms / 60000, ms / 1000 % 60, ms % 1000),
+            SolveTime::Time(ms) => write!(f, "{}.{:03}", ms / 1000, ms % 1000),
+            SolveTime::Dnf => write!(f, "DNF"),
+        }
+    }
+}
+
+impl FromStr for SolveTime {
+    type Err = Error;
+
+    /// Parse a time like `12.345` or `1:02.345`. Anything starting with `DNF` is a DNF.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with("DNF") {
+            return Ok(SolveTime::Dnf);
+        }
+        let (minutes, seconds) = s.split_once(':').unwrap_or(("0", s));
+        let minutes: u32 = minutes.parse().map_err(|_| Error::Parse(format!("Invalid time {}", s)))?;
+        let seconds: f64 = seconds.parse().map_err(|_| Error::Parse(format!("Invalid time {}", s)))?;
+        if !(0.0..60.0).contains(&seconds) && minutes > 0 || seconds < 0.0 {
+            return Err(Error::Parse(format!("Invalid time {}", s)));
+        }
+        Ok(SolveTime::Time(minutes * 60000 + (seconds * 1000.0).round() as u32))
+    }
+}
+
+/// A timed solve.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solve {
+    /// What the timer showed, in milliseconds, without the penalty.
+    pub time: u32,
+    pub penalty: Penalty,
+    /// The scramble in standard notation.
+    pub scramble: String,
+    pub comment: String,
+    /// When the solve was done, in seconds since the Unix epoch.
+    pub date: u64,
+}
+
+impl Solve {
+    /// The time with the penalty applied.
+    pub fn result(&self) -> SolveTime {
+        match self.penalty {
+            Penalty::None => SolveTime::Time(self.time),
+            Penalty::PlusTwo => SolveTime::Time(self.time + 2000),
+            Penalty::Dnf => SolveTime::Dnf,
+        }
+    }
+}
+
+///
+/// Average of a few results the way the WCA does it: the best and worst 5% (at least one of each)
+/// don't count and the rest are averaged. If a DNF is left after trimming the average is a DNF.
+/// There is no average of fewer than 3 results.
+///
+pub fn average(results: &[SolveTime]) -> Option<SolveTime> {
+    if results.len() < 3 {
+        return None;
+    }
+    let trim = (results.len() * 5).div_ceil(100);
+    let mut sorted = results.to_vec();
+    sorted.sort();
+    let counting = &sorted[trim..sorted.len() - trim];
+    let mut total = 0u64;
+    for result in counting {
+        match result {
+            SolveTime::Time(ms) => total += *ms as u64,
+            SolveTime::Dnf => return Some(SolveTime::Dnf),
+        }
+    }
+    Some(SolveTime::Time((total as f64 / counting.len() as f64).round() as u32))
+}
+
+/// The numbers shown for a session, see [Session::stats].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub count: usize,
+    pub best: Option<SolveTime>,
+    pub mean: Option<SolveTime>,
+    /// Averages of the last 5, 12 and 100 solves.
+    pub ao5: Option<SolveTime>,
+    pub ao12: Option<SolveTime>,
+    pub ao100: Option<SolveTime>,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |time: Option<SolveTime>| time.map_or("-".to_string(), |time| time.to_string());
+        write!(
+            f,
+            "{} solves, best {}, mean {}, ao5 {}, ao12 {}, ao100 {}",
+            self.count, show(self.best), show(self.mean), show(self.ao5), show(self.ao12), show(self.ao100),
+        )
+    }
+}
+
+///
+/// The solves of a practice session, oldest first.
+///
+/// Sessions can be saved and loaded in the JSON and CSV formats of csTimer, so they can be moved
+/// between the two. Dates in CSV files are in UTC.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Session {
+    pub solves: Vec<Solve>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The best single.
+    pub fn best(&self) -> Option<SolveTime> {
+        self.solves.iter().map(Solve::result).min()
+    }
+
+    /// The mean of all the solves that weren't DNFs, or a DNF if none finished.
+    pub fn mean(&self) -> Option<SolveTime> {
+        if self.solves.is_empty() {
+            return None;
+        }
+        let times: Vec<u32> = self.solves.iter().filter_map(|solve| match solve.result() {
+            SolveTime::Time(ms) => Some(ms),
+            SolveTime::Dnf => None,
+        }).collect();
+        if times.is_empty() {
+            return Some(SolveTime::Dnf);
+        }
+        let total: u64 = times.iter().map(|&ms| ms as u64).sum();
+        Some(SolveTime::Time((total as f64 / times.len() as f64).round() as u32))
+    }
+
+    /// The [average] of the last `n` solves, if there are that many.
+    pub fn average(&self, n: usize) -> Option<SolveTime> {
+        let start = self.solves.len().checked_sub(n)?;
+        let results: Vec<SolveTime> = self.solves[start..].iter().map(Solve::result).collect();
+        average(&results)
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            count: self.solves.len(),
+            best: self.best(),
+            mean: self.mean(),
+            ao5: self.average(5),
+            ao12: self.average(12),
+            ao100: self.average(100),
+        }
+    }
+
+    /// Save in the format of csTimer's export, as its first session.
+    pub fn to_cstimer_json(&self) -> String {
+        let solves: Vec<Value> = self.solves.iter().map(|solve| {
+            let penalty = match solve.penalty {
+                Penalty::None => 0,
+                Penalty::PlusTwo => 2000,
+                Penalty::Dnf => -1,
+            };
+            json!([[penalty, solve.time], solve.scramble, solve.comment, solve.date])
+        }).collect();
+        let session_data = json!({ "1": { "name": 1, "opt": {}, "rank": 1 } });
+        json!({
+            "session1": solves,
+            "properties": { "sessionData": session_data.to_string() },
+        }).to_string()
+    }
+
+    /// Load an export of csTimer. Only the first of its sessions is read.
+    pub fn from_cstimer_json(json: &str) -> Result<Self, Error> {
+        let value: Value = serde_json::from_str(json).map_err(|e| Error::Parse(format!("Invalid JSON: {}", e)))?;
+        let object = value.as_object().ok_or_else(|| Error::Parse("Expected a JSON object".to_string()))?;
+        let (_, solves) = object.iter()
+            .filter_map(|(key, solves)| Some((key.strip_prefix("session")?.parse::<u32>().ok()?, solves)))
+            .min_by_key(|(number, _)| *number)
+            .ok_or_else(|| Error::Parse("No session found".to_string()))?;
+        let solves = solves.as_array().ok_or_else(|| Error::Parse("Expected the session to be an array".to_string()))?;
+        let mut session = Session::new();
+        for (i, solve) in solves.iter().enumerate() {
+            let invalid = || Error::Parse(format!("Invalid solve {}", i + 1));
+            let timing = solve.get(0).and_then(Value::as_array).ok_or_else(invalid)?;
+            let penalty = match timing.first().and_then(Value::as_i64).ok_or_else(invalid)? {
+                0 => Penalty::None,
+                -1 => Penalty::Dnf,
+                _ => Penalty::PlusTwo,
+            };
+            session.solves.push(Solve {
+                time: timing.get(1).and_then(Value::as_u64).ok_or_else(invalid)? as u32,
+                penalty,
+                scramble: solve.get(1).and_then(Value::as_str).unwrap_or_default().to_string(),
+                comment: solve.get(2).and_then(Value::as_str).unwrap_or_default().to_string(),
+                date: solve.get(3).and_then(Value::as_u64).unwrap_or_default(),
+            });
+        }
+        Ok(session)
+    }
+
+    /// Save in the format of csTimer's CSV export.
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{}\n", CSV_HEADER);
+        for (i, solve) in self.solves.iter().enumerate() {
+            let raw = SolveTime::Time(solve.time);
+            let time = match solve.penalty {
+                Penalty::None => raw.to_string(),
+                Penalty::PlusTwo => format!("{}+", solve.result()),
+                Penalty::Dnf => format!("DNF({})", raw),
+            };
+            let comment = solve.comment.replace([';', '\n'], " ");
+            csv.push_str(&format!("{};{};{};{};{};{}\n", i + 1, time, comment, solve.scramble, format_date(solve.date), raw));
+        }
+        csv
+    }
+
+    /// Load a CSV export of csTimer.
+    pub fn from_csv(csv: &str) -> Result<Self, Error> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        match lines.next() {
+            Some(header) if header.trim().starts_with("No.;Time;") => {}
+            _ => return Err(Error::Parse(format!("Expected the header {}", CSV_HEADER))),
+        }
+        let mut session = Session::new();
+        for (i, line) in lines.enumerate() {
+            let invalid = |what: &str| Error::Parse(format!("Invalid {} in solve {}", what, i + 1));
+            let fields: Vec<&str> = line.split(';').map(|field| field.trim().trim_matches('"')).collect();
+            if fields.len() < 5 {
+                return Err(invalid("line"));
+            }
+            let shown = fields[1];
+            let penalty = if shown.starts_with("DNF") {
+                Penalty::Dnf
+            } else if shown.ends_with('+') {
+                Penalty::PlusTwo
+            } else {
+                Penalty::None
+            };
+            // The raw time is in the phase column, or between the parentheses of a DNF
+            let raw = match (fields.get(5), penalty) {
+                (Some(phase), _) if !phase.is_empty() => phase.parse(),
+                (_, Penalty::Dnf) => shown.trim_start_matches("DNF(").trim_end_matches(')').parse(),
+                (_, Penalty::PlusTwo) => shown.trim_end_matches('+').parse().map(|time| match time {
+                    SolveTime::Time(ms) => SolveTime::Time(ms.saturating_sub(2000)),
+                    dnf => dnf,
+                }),
+                (_, Penalty::None) => shown.parse(),
+            };
+            let time = match raw {
+                Ok(SolveTime::Time(ms)) => ms,
+                _ => return Err(invalid("time")),
+            };
+            session.solves.push(Solve {
+                time,
+                penalty,
+                comment: fields[2].to_string(),
+                scramble: fields[3].to_string(),
+                date: parse_date(fields[4]).ok_or_else(|| invalid("date"))?,
+            });
+        }
+        Ok(session)
+    }
+
+    /// Load either format, telling them apart by the first character.
+    pub fn import(data: &str) -> Result<Self, Error> {
+        if data.trim_start().starts_with('{') {
+            Self::from_cstimer_json(data)
+        } else {
+            Self::from_csv(data)
+        }
+    }
+}
+
+/// Days since the Unix epoch of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// `YYYY-MM-DD hh:mm:ss` in UTC.
+fn format_date(date: u64) -> String {
+    let days = (date / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    let seconds = date % 86400;
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+fn parse_date(date: &str) -> Option<u64> {
+    let (day, time) = date.split_once(' ').unwrap_or((date, "00:00:00"));
+    let day: Vec<i64> = day.split('-').map(|n| n.parse().ok()).collect::<Option<_>>()?;
+    let time: Vec<i64> = time.split(':').map(|n| n.parse().ok()).collect::<Option<_>>()?;
+    let ([year, month, day], [hours, minutes, seconds]) = (day.as_slice(), time.as_slice()) else {
+        return None;
+    };
+    let days = days_from_civil(*year, *month, *day);
+    u64::try_from(days * 86400 + hours * 3600 + minutes * 60 + seconds).ok()
+}
+
+/// Seconds since the Unix epoch.
+pub(super) fn unix_time() -> u64 {
+    #[cfg(target_family = "wasm")]
+    {
+        (js_sys::Date::now() / 1000.0) as u64
+    }
+    #[cfg(not(target_family = "wasm"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs())
+    }
+}
+
+/// What a [Timer] is doing. Times are in milliseconds, on the clock passed to the timer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimerPhase {
+    /// Waiting for the next solve.
+    Ready,
+    Inspecting { since: f64 },
+    /// Solving, with the penalty already earned by inspecting too long.
+    Running { since: f64, penalty: Penalty },
+}
+
+///
+/// A speedcubing timer driven by the spacebar.
+///
+/// The first press starts the WCA inspection of 15 seconds, the second starts the solve and the
+/// third stops it. Inspecting longer gives a +2, and more than 17 seconds a DNF. When the cube is
+/// turned on the keyboard the first turn starts the solve, and the solve stops by itself once the cube
+/// is solved, at the time of the last turn. Finished solves are added to the [Session].
+///
+/// The timer has no clock of its own, the time is passed to every call.
+///
+pub struct Timer {
+    phase: TimerPhase,
+    /// Whether solves start with an inspection.
+    pub inspection: bool,
+    scramble: String,
+    last_turn: f64,
+    session: Session,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Timer {
+    pub fn new() -> Self {
+        Self {
+            phase: TimerPhase::Ready,
+            inspection: true,
+            scramble: String::new(),
+            last_turn: 0.0,
+            session: Session::new(),
+        }
+    }
+
+    pub fn phase(&self) -> TimerPhase {
+        self.phase
+    }
+
+    /// Whether a solve is being inspected or timed.
+    pub fn is_active(&self) -> bool {
+        self.phase != TimerPhase::Ready
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    pub fn set_session(&mut self, session: Session) {
+        self.session = session;
+    }
+
+    /// The scramble saved with the next solve.
+    pub fn set_scramble(&mut self, scramble: &str) {
+        self.scramble = scramble.to_string();
+    }
+
+    /// The spacebar was pressed. Returns the solve if this stopped one.
+    pub fn press(&mut self, time: f64) -> Option<&Solve> {
+        match self.phase {
+            TimerPhase::Ready if self.inspection => {
+                self.phase = TimerPhase::Inspecting { since: time };
+                None
+            }
+            TimerPhase::Ready | TimerPhase::Inspecting { .. } => {
+                self.start(time);
+                None
+            }
+            TimerPhase::Running { .. } => self.stop(time),
+        }
+    }
+
+    /// The cube was turned. The first turn after the inspection starts the solve.
+    pub fn turned(&mut self, time: f64) {
+        if let TimerPhase::Inspecting { .. } = self.phase {
+            self.start(time);
+        }
+        self.last_turn = time;
+    }
+
+    /// The cube is solved, stop at the time of the last turn. Returns the solve if one was running.
+    pub fn solved(&mut self) -> Option<&Solve> {
+        match self.phase {
+            TimerPhase::Running { .. } => self.stop(self.last_turn),
+            _ => None,
+        }
+    }
+
+    /// Drop the solve being inspected or timed, if there is one.
+    pub fn cancel(&mut self) {
+        self.phase = TimerPhase::Ready;
+    }
+
+    /// Change the penalty of the last solve.
+    pub fn penalize(&mut self, penalty: Penalty) {
+        if let Some(solve) = self.session.solves.last_mut() {
+            solve.penalty = penalty;
+        }
+    }
+
+    /// What the timer shows: the seconds of inspection left, the time of the solve so far or the last result.
+    pub fn readout(&self, time: f64) -> String {
+        match self.phase {
+            TimerPhase::Ready => self.session.solves.last().map_or(SolveTime::Time(0), Solve::result).to_string(),
+            TimerPhase::Inspecting { since } => match time - since {
+                elapsed if elapsed < INSPECTION => format!("{}", ((INSPECTION - elapsed) / 1000.0).ceil()),
+                elapsed if elapsed < INSPECTION_LIMIT => "+2".to_string(),
+                _ => "DNF".to_string(),
+            },
+            TimerPhase::Running { since, .. } => SolveTime::Time((time - since).max(0.0) as u32).to_string(),
+        }
+    }
+
+    fn start(&mut self, time: f64) {
+        let penalty = match self.phase {
+            TimerPhase::Inspecting { since } if time - since > INSPECTION_LIMIT => Penalty::Dnf,
+            TimerPhase::Inspecting { since } if time - since > INSPECTION => Penalty::PlusTwo,
+            _ => Penalty::None,
+        };
+        self.phase = TimerPhase::Running { since: time, penalty };
+    }
+
+    fn stop(&mut self, time: f64) -> Option<&Solve> {
+        let TimerPhase::Running { since, penalty } = self.phase else {
+            return None;
+        };
+        self.phase = TimerPhase::Ready;
+        self.session.solves.push(Solve {
+            time: (time - since).max(0.0).round() as u32,
+            penalty,
+            scramble: std::mem::take(&mut self.scramble),
+            comment: String::new(),
+            date: unix_time(),
+        });
+        self.session.solves.last()
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+
+use three_d::{Context, FrameInput, FrameOutput, RenderTarget, Viewport};
+
+use crate::WindowLike;
+
+///
+/// A visualization that can be hosted by eyecandy.
+///
+/// Scenes are created by the [SceneRegistry] with the graphics context and the initial viewport
+/// of the window, then [run] drives them every frame: first [Scene::resize] if the window changed size,
+/// then [Scene::update] and finally [Scene::render]. Idle scenes are only drawn again when an event arrives.
+///
+pub trait Scene {
+    /// Called when the viewport of the window changes size.
+    fn resize(&mut self, _viewport: Viewport) {}
+
+    /// Advance the scene by one frame. Events that are handled should be marked as such.
+    fn update(&mut self, frame_input: &mut FrameInput);
+
+    /// Draw the scene to the screen.
+    fn render(&mut self, screen: &RenderTarget);
+
+    ///
+    /// Whether nothing would change if the scene was drawn again, checked after [Scene::update].
+    /// While a scene is idle the window sleeps until the next event instead of redrawing continuously.
+    ///
+    fn is_idle(&self) -> bool {
+        false
+    }
+}
+
+/// Creates a scene from the graphics context and the initial viewport of the window.
+pub type SceneInit = Box<dyn Fn(&Context, Viewport) -> Box<dyn Scene>>;
+
+///
+/// All the scenes that can be shown, by name.
+/// The default registry contains every scene in eyecandy, new ones can be added with [SceneRegistry::register].
+///
+pub struct SceneRegistry {
+    scenes: BTreeMap<String, SceneInit>,
+}
+
+impl SceneRegistry {
+    /// A registry without any scenes.
+    pub fn empty() -> Self {
+        Self { scenes: BTreeMap::new() }
+    }
+
+    /// Add a scene, replacing the one with the same name if there is one.
+    pub fn register(
+        &mut self,
+        name: &str,
+        init: impl Fn(&Context, Viewport) -> Box<dyn Scene> + 'static,
+    ) {
+        self.scenes.insert(name.to_string(), Box::new(init));
+    }
+
+    /// Names of all the registered scenes, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.scenes.keys().map(|name| name.as_str())
+    }
+
+    /// Create the scene with the given name, if it exists.
+    pub fn create(&self, name: &str, window: &impl WindowLike) -> Option<Box<dyn Scene>> {
+        self.scenes.get(name).map(|init| init(&window.gl(), window.viewport()))
+    }
+}
+
+impl Default for SceneRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register("rubik", |context, viewport| {
+            Box::new(crate::rubik::RubikScene::new(context, viewport))
+        });
+        registry.register("editor", |context, viewport| {
+            Box::new(crate::rubik::RubikScene::new(context, viewport).editing())
+        });
+        registry.register("timer", |context, viewport| {
+            Box::new(crate::rubik::RubikScene::new(context, viewport).timing())
+        });
+        registry.register("cubes", |context, viewport| {
+            Box::new(crate::rubik::CubeGridScene::new(context, viewport))
+        });
+        registry
+    }
+}
+
+/// Build the render loop closure for a scene, for use with either window's render loop.
+pub fn run(mut scene: Box<dyn Scene>) -> impl 'static + FnMut(FrameInput) -> FrameOutput {
+    let mut viewport: Option<Viewport> = None;
+    move |mut frame_input| {
+        if viewport != Some(frame_input.viewport) {
+            scene.resize(frame_input.viewport);
+            viewport = Some(frame_input.viewport);
+        }
+        scene.update(&mut frame_input);
+        scene.render(&frame_input.screen());
+        FrameOutput {
+            wait_next_event: scene.is_idle(),
+            ..Default::default()
+        }
+    }
+}
@@ -1,18 +1,21 @@
 #![cfg(target_family = "wasm")]
 
-use std::sync::OnceLock;
-use three_d::SurfaceSettings;
-use winit::event_loop::EventLoop;
+use std::cell::RefCell;
+use std::rc::Rc;
+use three_d::{Srgba, SurfaceSettings};
 use wasm_bindgen::prelude::*;
 use web_sys::HtmlCanvasElement;
-use crate::rubik;
-use log::info;
+use crate::rubik::{
+    facelets_from_scans, scan_face, scramble, Cube, CubeAppearance, CubeCommand, CubeRemote, FaceGrid, FaceScan, MaskPreset,
+    Move, MoveGroup, Penalty, Playback, Reconstruction, RubikScene, Session,
+};
+use crate::scene::{self, SceneRegistry};
+use crate::Error;
+use log::{info, warn};
 
+pub mod runtime;
 pub mod window;
 
-static EVENT_LOOP_CREATED: OnceLock<()> = OnceLock::new();
-static mut _SHOULD_EXIT: bool = false;
-
 #[wasm_bindgen(start)]
 pub fn wasm_start() -> Result<(), JsValue> {
     console_log::init_with_level(log::Level::Debug).unwrap();
@@ -24,19 +27,598 @@ pub fn wasm_start() -> Result<(), JsValue> {
     Ok(())
 }
 
+/// Errors are thrown as JavaScript `Error`s.
+impl From<Error> for JsValue {
+    fn from(error: Error) -> Self {
+        js_sys::Error::new(&error.to_string()).into()
+    }
+}
+
+/// The scenes that can be bound to a canvas. The cube is controlled through `remote`.
+fn registry(remote: &Rc<RefCell<CubeRemote>>) -> SceneRegistry {
+    let mut registry = SceneRegistry::default();
+    let rubik_remote = remote.clone();
+    registry.register("rubik", move |context, viewport| {
+        Box::new(RubikScene::new(context, viewport).with_remote(rubik_remote.clone()))
+    });
+    let editor_remote = remote.clone();
+    registry.register("editor", move |context, viewport| {
+        Box::new(RubikScene::new(context, viewport).editing().with_remote(editor_remote.clone()))
+    });
+    let timer_remote = remote.clone();
+    registry.register("timer", move |context, viewport| {
+        Box::new(RubikScene::new(context, viewport).timing().with_remote(timer_remote.clone()))
+    });
+    registry
+}
+
+/// Names of the scenes that can be passed to [bind].
 #[wasm_bindgen]
-pub fn bind(canvas: HtmlCanvasElement) -> Result<(), JsValue> {
-    if let Err(_) = EVENT_LOOP_CREATED.set(()) {
-        return Err(JsValue::from_str("Event loop already created."));
+pub fn scenes() -> Vec<JsValue> {
+    registry(&Rc::default()).names().map(JsValue::from_str).collect()
+}
+
+///
+/// Start rendering the scene called `name` on the canvas.
+/// Any number of canvases can be bound at once. Once a scene is stopped its canvas can be bound again.
+/// The returned handle controls the cube, for scenes without one only stopping does something.
+///
+#[wasm_bindgen]
+pub fn bind(canvas: HtmlCanvasElement, name: &str) -> Result<CubeHandle, JsValue> {
+    let remote = Rc::new(RefCell::new(CubeRemote::default()));
+    let registry = registry(&remote);
+    let unknown = |name: &str| Error::Canvas(format!("Unknown scene '{}'.", name));
+    if !registry.names().any(|n| n == name) {
+        return Err(unknown(name).into());
+    }
+    let name = name.to_string();
+    let window = runtime::start(canvas, SurfaceSettings::default(), Box::new(move |window| {
+        info!("starting render loop for scene {}", name);
+        let scene = registry.create(&name, window).ok_or_else(|| unknown(&name))?;
+        Ok(Box::new(scene::run(scene)))
+    }));
+    Ok(CubeHandle { remote, window })
+}
+
+///
+/// Controls the cube of a scene started with [bind].
+/// Commands are applied on the next frame.
+///
+#[wasm_bindgen]
+pub struct CubeHandle {
+    remote: Rc<RefCell<CubeRemote>>,
+    window: runtime::WindowHandle,
+}
+
+#[wasm_bindgen]
+impl CubeHandle {
+    ///
+    /// Stop the scene, removing its event listeners from the canvas and releasing the graphics context.
+    /// Commands sent afterwards are ignored.
+    ///
+    pub fn stop(&self) {
+        self.window.stop();
+    }
+
+    #[wasm_bindgen(js_name = isStopped)]
+    pub fn is_stopped(&self) -> bool {
+        self.window.is_stopped()
+    }
+
+    ///
+    /// Throws the reason the scene stopped if it couldn't start, like a browser without WebGL 2.
+    /// The canvas is set up on the next frame, so this only tells once that has happened.
+    ///
+    pub fn check(&self) -> Result<(), JsValue> {
+        match self.window.error() {
+            Some(error) => Err(error.into()),
+            None => Ok(()),
+        }
+    }
+
+    ///
+    /// Limit the resolution of the canvas to `ratio` pixels per CSS pixel, 2 by default.
+    /// Lower values trade sharpness on high density screens for speed.
+    ///
+    #[wasm_bindgen(js_name = setMaxPixelRatio)]
+    pub fn set_max_pixel_ratio(&self, ratio: f64) {
+        self.window.set_max_pixel_ratio(ratio);
+    }
+
+    /// Queue moves in standard notation, like `"R U R' U'"`.
+    pub fn queue(&self, moves: &str) -> Result<(), JsValue> {
+        let moves = Move::parse_sequence(moves)?;
+        self.send(CubeCommand::Queue(moves));
+        Ok(())
+    }
+
+    /// Turn two opposite faces at the same time, like `"R L'"`.
+    #[wasm_bindgen(js_name = queueTogether)]
+    pub fn queue_together(&self, moves: &str) -> Result<(), JsValue> {
+        self.send(CubeCommand::QueueGroup(MoveGroup::parse(moves)?));
+        Ok(())
+    }
+
+    /// Replace the state of the cube with a 54 letter facelet string.
+    #[wasm_bindgen(js_name = setState)]
+    pub fn set_state(&self, facelets: &str) -> Result<(), JsValue> {
+        Cube::validate_facelet_str(facelets)?;
+        let mut remote = self.remote.borrow_mut();
+        remote.set_state(facelets.to_string());
+        remote.set_solution_known(facelets.as_bytes().chunks(9).all(|face| face.iter().all(|&c| c == face[0])));
+        remote.send(CubeCommand::SetState(facelets.to_string()));
+        self.window.wake();
+        Ok(())
+    }
+
+    /// The facelet string of the cube. Moves still animating aren't counted.
+    #[wasm_bindgen(js_name = getState)]
+    pub fn get_state(&self) -> String {
+        self.remote.borrow().state().to_string()
+    }
+
+    /// Queue a random 25 move scramble and return it.
+    pub fn scramble(&self) -> String {
+        let seed = (js_sys::Math::random() * u64::MAX as f64) as u64;
+        self.send(CubeCommand::Scramble(seed));
+        scramble(seed, 25).iter().map(|mv| mv.to_string()).collect::<Vec<_>>().join(" ")
+    }
+
+    ///
+    /// Queue the moves that solve the cube. Throws if they aren't known, which is the case
+    /// until the cube has been solved since its state was last set.
+    ///
+    pub fn solve(&self) -> Result<(), JsValue> {
+        if !self.remote.borrow().solution_known() {
+            return Err(Error::Validation("The solution is only known for cubes that were solved since their state was set.".to_string()).into());
+        }
+        self.send(CubeCommand::Solve);
+        Ok(())
+    }
+
+    pub fn pause(&self) {
+        self.send(CubeCommand::Pause);
+    }
+
+    pub fn resume(&self) {
+        self.send(CubeCommand::Resume);
+    }
+
+    /// Animation speed relative to the default, 2 plays moves twice as fast.
+    #[wasm_bindgen(js_name = setSpeed)]
+    pub fn set_speed(&self, speed: f32) {
+        self.send(CubeCommand::SetSpeed(speed));
+    }
+
+    ///
+    /// Keep up with moves made by hand: once more than `maxPending` moves wait, the one that is
+    /// turning snaps to its end, and moves play faster the more of them wait.
+    /// Without `maxPending` every move takes the same time again, which suits scripted moves.
+    ///
+    #[wasm_bindgen(js_name = setResponsive)]
+    pub fn set_responsive(&self, max_pending: Option<usize>) {
+        let playback = max_pending.map_or(Playback::Fixed, |max_pending| Playback::Responsive { max_pending });
+        self.send(CubeCommand::SetPlayback(playback));
+    }
+
+    /// Set the sticker colors from an array of 6 CSS hex colors like `"#1f44a6"`,
+    /// in the order of the facelet letters: blue, yellow, red, white, green, orange.
+    #[wasm_bindgen(js_name = setColors)]
+    pub fn set_colors(&self, colors: js_sys::Array) -> Result<(), JsValue> {
+        self.send(CubeCommand::SetColors(parse_palette(colors)?));
+        Ok(())
+    }
+
+    /// Paint the stickers of the cube by clicking them, starting from the current state.
+    pub fn edit(&self) {
+        self.send(CubeCommand::Edit);
+    }
+
+    /// Leave the editor and use the painted stickers as the state of the cube, if it's a possible one.
+    /// Check first with [check_facelets].
+    pub fn apply(&self) {
+        self.send(CubeCommand::Apply);
+    }
+
+    /// Change the physical look of the cube.
+    #[wasm_bindgen(js_name = setAppearance)]
+    pub fn set_appearance(&self, appearance: &Appearance) {
+        self.send(CubeCommand::SetAppearance(appearance.0));
+    }
+
+    ///
+    /// Gray out the stickers that don't matter for a step: `"full"`, `"Cross"`, `"F2L"`, `"OLL"`,
+    /// `"PLL"`, `"ZBLL"` or `"Centers"`, in any case.
+    ///
+    #[wasm_bindgen(js_name = setStickering)]
+    pub fn set_stickering(&self, stickering: &str) -> Result<(), JsValue> {
+        let preset: MaskPreset = stickering.parse()?;
+        self.send(CubeCommand::SetMask(preset.into()));
+        Ok(())
+    }
+
+    /// Time solves with the spacebar, see [RubikScene::time].
+    pub fn time(&self) {
+        self.send(CubeCommand::Time);
+    }
+
+    #[wasm_bindgen(js_name = stopTiming)]
+    pub fn stop_timing(&self) {
+        self.send(CubeCommand::StopTiming);
+    }
+
+    /// What the timer shows: the seconds of inspection left, the running time or the last result.
+    pub fn timer(&self) -> String {
+        self.remote.borrow().readout().to_string()
+    }
+
+    ///
+    /// Statistics of the timed solves, as an object with the `count` of solves and
+    /// the `best`, `mean`, `ao5`, `ao12` and `ao100` times formatted like `"12.345"` or `"DNF"`.
+    /// Averages there aren't enough solves for are `null`.
+    ///
+    pub fn stats(&self) -> js_sys::Object {
+        let stats = self.remote.borrow().session().stats();
+        let object = js_sys::Object::new();
+        let set = |key: &str, value: JsValue| {
+            js_sys::Reflect::set(&object, &JsValue::from_str(key), &value).unwrap();
+        };
+        set("count", JsValue::from(stats.count as u32));
+        let times = [("best", stats.best), ("mean", stats.mean), ("ao5", stats.ao5), ("ao12", stats.ao12), ("ao100", stats.ao100)];
+        for (key, time) in times {
+            set(key, time.map_or(JsValue::NULL, |time| JsValue::from_str(&time.to_string())));
+        }
+        object
+    }
+
+    /// Change the penalty of the last solve: `""` for none, `"+2"` or `"DNF"`.
+    pub fn penalize(&self, penalty: &str) -> Result<(), JsValue> {
+        let penalty = match penalty {
+            "" => Penalty::None,
+            "+2" => Penalty::PlusTwo,
+            "DNF" => Penalty::Dnf,
+            _ => return Err(Error::Parse(format!("Unknown penalty '{}'.", penalty)).into()),
+        };
+        self.send(CubeCommand::Penalize(penalty));
+        Ok(())
+    }
+
+    /// The timed solves in the `"json"` or `"csv"` export format of csTimer.
+    #[wasm_bindgen(js_name = exportSession)]
+    pub fn export_session(&self, format: &str) -> Result<String, JsValue> {
+        let remote = self.remote.borrow();
+        match format {
+            "json" => Ok(remote.session().to_cstimer_json()),
+            "csv" => Ok(remote.session().to_csv()),
+            _ => Err(Error::Parse(format!("Unknown format '{}'.", format)).into()),
+        }
+    }
+
+    /// Replace the timed solves with a session exported from csTimer, as JSON or CSV.
+    #[wasm_bindgen(js_name = importSession)]
+    pub fn import_session(&self, data: &str) -> Result<(), JsValue> {
+        let session = Session::import(data)?;
+        self.send(CubeCommand::SetSession(session));
+        Ok(())
+    }
+
+    ///
+    /// The recording of the last timed solve, as `"text"` with the moves and splits of every stage
+    /// or as `"json"` to replay it later. Nothing if no solve was recorded yet.
+    ///
+    pub fn reconstruction(&self, format: &str) -> Result<Option<String>, JsValue> {
+        let remote = self.remote.borrow();
+        let Some(reconstruction) = remote.reconstruction() else {
+            return Ok(None);
+        };
+        match format {
+            "text" => Ok(Some(reconstruction.to_string())),
+            "json" => Ok(Some(reconstruction.to_json())),
+            _ => Err(Error::Parse(format!("Unknown format '{}'.", format)).into()),
+        }
+    }
+
+    /// Play back a solve saved as JSON by [CubeHandle::reconstruction], `speed` times as fast as it was done.
+    pub fn replay(&self, json: &str, speed: f32) -> Result<(), JsValue> {
+        let reconstruction = Reconstruction::from_json(json)?;
+        self.send(CubeCommand::Replay(reconstruction, speed));
+        Ok(())
+    }
+
+    ///
+    /// Listen to events of the cube. The only event is `"moveFinished"`,
+    /// called with the move in standard notation every time one finishes animating.
+    ///
+    pub fn on(&self, event: &str, callback: js_sys::Function) -> Result<(), JsValue> {
+        match event {
+            "moveFinished" => {
+                self.remote.borrow_mut().on_move_finished(move |mv| {
+                    if let Err(e) = callback.call1(&JsValue::NULL, &JsValue::from_str(&mv.to_string())) {
+                        warn!("moveFinished listener threw: {:?}", e);
+                    }
+                });
+                Ok(())
+            }
+            _ => Err(Error::Parse(format!("Unknown event '{}'.", event)).into()),
+        }
+    }
+
+    fn send(&self, command: CubeCommand) {
+        self.remote.borrow_mut().send(command);
+        // The scene may be asleep waiting for events
+        self.window.wake();
+    }
+}
+
+/// Throws with the reason if a facelet string isn't a state a real cube can be in.
+#[wasm_bindgen(js_name = checkFacelets)]
+pub fn check_facelets(facelets: &str) -> Result<(), JsValue> {
+    Ok(crate::rubik::check_facelets(facelets)?)
+}
+
+/// Parse an array of 6 hex colors in the order of the facelet letters.
+fn parse_palette(colors: js_sys::Array) -> Result<[Srgba; 6], JsValue> {
+    if colors.length() != 6 {
+        return Err(Error::Parse("Expected 6 colors.".to_string()).into());
+    }
+    let mut palette = [Srgba::BLACK; 6];
+    for (i, color) in colors.iter().enumerate() {
+        palette[i] = color.as_string()
+            .and_then(|c| parse_hex_color(&c))
+            .ok_or_else(|| Error::Parse(format!("Invalid color {:?}", color)))?;
+    }
+    Ok(palette)
+}
+
+/// Parse a `#rrggbb` color, the `#` is optional.
+fn parse_hex_color(color: &str) -> Option<Srgba> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(Srgba::new_opaque(channel(0)?, channel(2)?, channel(4)?))
+}
+
+///
+/// Reads the state of a real cube from pictures of its faces, like frames of a webcam drawn to a canvas.
+/// Scan all six faces, then pass the result of `facelets()` to [CubeHandle::set_state].
+///
+#[wasm_bindgen]
+pub struct ColorScanner {
+    faces: [Option<FaceScan>; 6],
+    palette: [Srgba; 6],
+}
+
+#[wasm_bindgen]
+impl ColorScanner {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            faces: [None; 6],
+            palette: CubeAppearance::default().colors,
+        }
+    }
+
+    /// The colors of the cube being scanned, if they're not the default ones. Same format as [CubeHandle::set_colors].
+    #[wasm_bindgen(js_name = setColors)]
+    pub fn set_colors(&mut self, colors: js_sys::Array) -> Result<(), JsValue> {
+        self.palette = parse_palette(colors)?;
+        Ok(())
+    }
+
+    ///
+    /// Scan one face from RGBA pixels, like the data of `getImageData`.
+    /// `face` is the index of the face in the facelet string: L, U, F, D, R, B.
+    /// The face has to fill the square of side `size` with its top left corner at `x`, `y`.
+    ///
+    #[wasm_bindgen(js_name = scanFace)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn scan_face(
+        &mut self,
+        face: usize,
+        pixels: &[u8],
+        width: u32,
+        height: u32,
+        x: u32,
+        y: u32,
+        size: u32,
+    ) -> Result<(), JsValue> {
+        if face >= 6 {
+            return Err(Error::Validation("Faces go from 0 to 5.".to_string()).into());
+        }
+        let expected = (width as usize).checked_mul(height as usize).and_then(|pixels| pixels.checked_mul(4));
+        if expected != Some(pixels.len()) {
+            return Err(Error::Validation("Expected 4 bytes per pixel.".to_string()).into());
+        }
+        let image = three_d_asset::Texture2D {
+            data: three_d_asset::TextureData::RgbaU8(
+                pixels.chunks_exact(4).map(|p| [p[0], p[1], p[2], p[3]]).collect()
+            ),
+            width,
+            height,
+            ..Default::default()
+        };
+        let grid = FaceGrid { x, y, width: size, height: size };
+        self.faces[face] = Some(scan_face(&image, grid)?);
+        Ok(())
+    }
+
+    /// The facelet string of the scanned cube. Fails if a face is missing.
+    pub fn facelets(&self) -> Result<String, JsValue> {
+        let mut scans = Vec::with_capacity(6);
+        for (i, face) in self.faces.iter().enumerate() {
+            scans.push((*face).ok_or_else(|| Error::Validation(format!("Face {} hasn't been scanned.", i)))?);
+        }
+        let scans: [FaceScan; 6] = scans.try_into().unwrap();
+        Ok(facelets_from_scans(&scans, &self.palette)?)
+    }
+}
+
+impl Default for ColorScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///
+/// The physical look of the cube, for use from JavaScript.
+/// Create one with `new Appearance()`, change its properties and pass it to [CubeHandle::set_appearance].
+///
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Appearance(CubeAppearance);
+
+#[wasm_bindgen]
+impl Appearance {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn bevel(&self) -> f32 {
+        self.0.bevel
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_bevel(&mut self, bevel: f32) {
+        self.0.bevel = bevel;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn bevel_segments(&self) -> u32 {
+        self.0.bevel_segments
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_bevel_segments(&mut self, segments: u32) {
+        self.0.bevel_segments = segments;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn gap(&self) -> f32 {
+        self.0.gap
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_gap(&mut self, gap: f32) {
+        self.0.gap = gap;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn stickerless(&self) -> bool {
+        self.0.stickerless
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_stickerless(&mut self, stickerless: bool) {
+        self.0.stickerless = stickerless;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sticker_margin(&self) -> f32 {
+        self.0.sticker_margin
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_sticker_margin(&mut self, margin: f32) {
+        self.0.sticker_margin = margin;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sticker_radius(&self) -> f32 {
+        self.0.sticker_radius
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_sticker_radius(&mut self, radius: f32) {
+        self.0.sticker_radius = radius;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sticker_depth(&self) -> f32 {
+        self.0.sticker_depth
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_sticker_depth(&mut self, depth: f32) {
+        self.0.sticker_depth = depth;
+    }
+
+    /// Show the stickers of the L, D and B faces floating behind the cube.
+    #[wasm_bindgen(getter)]
+    pub fn hint_facelets(&self) -> bool {
+        self.0.hint_facelets
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_hint_facelets(&mut self, hint_facelets: bool) {
+        self.0.hint_facelets = hint_facelets;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn hint_distance(&self) -> f32 {
+        self.0.hint_distance
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_hint_distance(&mut self, distance: f32) {
+        self.0.hint_distance = distance;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn hint_opacity(&self) -> f32 {
+        self.0.hint_opacity
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_hint_opacity(&mut self, opacity: f32) {
+        self.0.hint_opacity = opacity;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sticker_roughness(&self) -> f32 {
+        self.0.sticker_roughness
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_sticker_roughness(&mut self, roughness: f32) {
+        self.0.sticker_roughness = roughness;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sticker_metallic(&self) -> f32 {
+        self.0.sticker_metallic
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_sticker_metallic(&mut self, metallic: f32) {
+        self.0.sticker_metallic = metallic;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn body_roughness(&self) -> f32 {
+        self.0.body_roughness
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_body_roughness(&mut self, roughness: f32) {
+        self.0.body_roughness = roughness;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn body_metallic(&self) -> f32 {
+        self.0.body_metallic
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_body_metallic(&mut self, metallic: f32) {
+        self.0.body_metallic = metallic;
+    }
+
+    /// Set the color of the plastic body, each channel from 0 to 255.
+    pub fn set_body_color(&mut self, r: u8, g: u8, b: u8) {
+        self.0.body_color = Srgba::new_opaque(r, g, b);
     }
-    let event_loop = EventLoop::new();
-    let window = window::Window::new(
-        canvas,
-        &event_loop,
-        SurfaceSettings::default()
-    );
-    info!("starting render loop");
-    let closure = rubik::run(&window);
-    window.start(event_loop, closure);
-    Ok(())
 }
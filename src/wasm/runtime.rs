@@ -0,0 +1,208 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use three_d::{FrameInput, FrameOutput, SurfaceSettings};
+use web_sys::HtmlCanvasElement;
+use winit::event::Event;
+use winit::event_loop::{EventLoop, EventLoopProxy, EventLoopWindowTarget};
+use winit::platform::web::EventLoopExtWebSys;
+
+use super::window::{Window, DEFAULT_MAX_PIXEL_RATIO};
+use crate::Error;
+
+/// The render callback of a window, see [crate::scene::run].
+pub type RenderLoop = Box<dyn FnMut(FrameInput) -> FrameOutput>;
+
+/// Creates the render loop once the window for it exists.
+pub type RenderInit = Box<dyn FnOnce(&Window) -> Result<RenderLoop, Error>>;
+
+struct PendingWindow {
+    canvas: HtmlCanvasElement,
+    surface_settings: SurfaceSettings,
+    init: RenderInit,
+    stopped: Rc<Cell<bool>>,
+    woken: Rc<Cell<bool>>,
+    max_pixel_ratio: Rc<Cell<f64>>,
+    error: Rc<RefCell<Option<Error>>>,
+}
+
+struct RunningWindow {
+    // The scene goes before the window, it holds on to the graphics context
+    render: RenderLoop,
+    window: Window,
+    stopped: Rc<Cell<bool>>,
+    /// Whether the window got an input event or was woken with [WindowHandle::wake] since its last frame.
+    woken: Rc<Cell<bool>>,
+    wait_next_event: bool,
+}
+
+thread_local! {
+    static PROXY: RefCell<Option<EventLoopProxy<()>>> = const { RefCell::new(None) };
+    static PENDING: RefCell<Vec<PendingWindow>> = const { RefCell::new(Vec::new()) };
+}
+
+///
+/// Stops a window started with [start].
+/// Stopping drops the window, which removes its event listeners from the canvas and releases the graphics context.
+///
+#[derive(Clone)]
+pub struct WindowHandle {
+    stopped: Rc<Cell<bool>>,
+    woken: Rc<Cell<bool>>,
+    max_pixel_ratio: Rc<Cell<f64>>,
+    error: Rc<RefCell<Option<Error>>>,
+}
+
+impl WindowHandle {
+    /// Stop rendering and tear the window down on the next iteration of the event loop.
+    pub fn stop(&self) {
+        self.stopped.set(true);
+        wake();
+    }
+
+    /// Render a frame of the window even if its scene is waiting for events, like after sending it a command.
+    pub fn wake(&self) {
+        self.woken.set(true);
+        wake();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.get()
+    }
+
+    /// Why the window couldn't be started, if it failed. It's stopped then.
+    pub fn error(&self) -> Option<Error> {
+        self.error.borrow().clone()
+    }
+
+    /// See [Window::set_max_pixel_ratio].
+    pub fn set_max_pixel_ratio(&self, ratio: f64) {
+        self.max_pixel_ratio.set(ratio);
+    }
+}
+
+///
+/// Render to `canvas` with the render loop made by `init`, starting the event loop if needed.
+///
+/// winit allows a single event loop per program, and on the web it can't be restarted once it exits.
+/// So every canvas on the page shares one loop that is started by the first call and never exits.
+/// The window is created on the next iteration of the event loop, so `init` runs later and
+/// failures to create the window or the render loop are reported by [WindowHandle::error].
+/// Dispatching a `__wasm_exit` event on the canvas stops the window like [WindowHandle::stop].
+///
+pub fn start(canvas: HtmlCanvasElement, surface_settings: SurfaceSettings, init: RenderInit) -> WindowHandle {
+    let stopped = Rc::new(Cell::new(false));
+    let woken = Rc::new(Cell::new(false));
+    let max_pixel_ratio = Rc::new(Cell::new(DEFAULT_MAX_PIXEL_RATIO));
+    let error = Rc::new(RefCell::new(None));
+    PENDING.with(|pending| pending.borrow_mut().push(PendingWindow {
+        canvas,
+        surface_settings,
+        init,
+        stopped: stopped.clone(),
+        woken: woken.clone(),
+        max_pixel_ratio: max_pixel_ratio.clone(),
+        error: error.clone(),
+    }));
+    if PROXY.with(|proxy| proxy.borrow().is_some()) {
+        wake();
+    } else {
+        let event_loop = EventLoop::new();
+        PROXY.with(|proxy| *proxy.borrow_mut() = Some(event_loop.create_proxy()));
+        spawn(event_loop);
+    }
+    WindowHandle { stopped, woken, max_pixel_ratio, error }
+}
+
+///
+/// Make the event loop run an iteration even if it is waiting for events.
+/// Windows whose scene is waiting for events only render if they were woken themselves, see [WindowHandle::wake].
+///
+pub(super) fn wake() {
+    PROXY.with(|proxy| {
+        if let Some(proxy) = &*proxy.borrow() {
+            // Only fails if the loop is gone, and then there's nothing to wake
+            let _ = proxy.send_event(());
+        }
+    });
+}
+
+fn spawn(event_loop: EventLoop<()>) {
+    let mut windows: Vec<RunningWindow> = Vec::new();
+    event_loop.spawn(move |event, target, control_flow| match &event {
+        Event::NewEvents(_) => {
+            // Stopped windows go first so a new one can take over their canvas
+            windows.retain(|running| !running.stopped.get());
+            create_pending(target, &mut windows);
+        }
+        Event::MainEventsCleared => {
+            // Hidden windows are parked until they can be seen again, which wakes the loop
+            for running in windows.iter().filter(|running| running.window.is_visible()) {
+                // Taking both flags, an idle window is only woken once
+                let woken = running.woken.take() | running.window.take_woken();
+                if woken || !running.wait_next_event {
+                    running.window.request_redraw();
+                }
+            }
+        }
+        Event::RedrawRequested(id) => {
+            if let Some(running) = windows.iter_mut().find(|running| running.window.id() == *id) {
+                if running.stopped.get() {
+                    return;
+                }
+                let frame_input = running.window.frame_input();
+                let frame_output = (running.render)(frame_input);
+                if frame_output.exit {
+                    running.stopped.set(true);
+                }
+                running.wait_next_event = frame_output.wait_next_event;
+            }
+        }
+        Event::RedrawEventsCleared => {
+            // The loop itself never exits, winit can't start another one
+            let idle = |running: &RunningWindow| {
+                running.wait_next_event || running.stopped.get() || !running.window.is_visible()
+            };
+            if windows.iter().all(idle) {
+                control_flow.set_wait();
+            } else {
+                control_flow.set_poll();
+            }
+        }
+        Event::WindowEvent { window_id, event } => {
+            if let Some(running) = windows.iter_mut().find(|running| running.window.id() == *window_id) {
+                running.window.handle_event(event);
+                running.woken.set(true);
+            }
+        }
+        _ => {}
+    });
+}
+
+fn create_pending(target: &EventLoopWindowTarget<()>, windows: &mut Vec<RunningWindow>) {
+    let pending = PENDING.with(|pending| std::mem::take(&mut *pending.borrow_mut()));
+    for PendingWindow { canvas, surface_settings, init, stopped, woken, max_pixel_ratio, error } in pending {
+        if stopped.get() {
+            continue;
+        }
+        let exit = stopped.clone();
+        let running = Window::new(canvas, target, surface_settings).and_then(|mut window| {
+            window.share_max_pixel_ratio(max_pixel_ratio);
+            window.on("__wasm_exit", move |_| {
+                log::info!("exit event triggered");
+                exit.set(true);
+                wake();
+            })?;
+            let render = init(&window)?;
+            Ok(RunningWindow { window, render, stopped: stopped.clone(), woken, wait_next_event: false })
+        });
+        match running {
+            Ok(running) => windows.push(running),
+            Err(e) => {
+                log::error!("{}", e);
+                *error.borrow_mut() = Some(e);
+                stopped.set(true);
+            }
+        }
+    }
+}
@@ -1,31 +1,66 @@
+use std::cell::Cell;
 use std::collections::HashMap;
+use std::rc::Rc;
 
-use three_d::{Context, FrameInput, FrameInputGenerator, FrameOutput, SurfaceSettings, Viewport, WindowedContext};
+use three_d::{Context, FrameInput, FrameInputGenerator, SurfaceSettings, Viewport, WindowedContext};
 use wasm_bindgen::closure::Closure;
-use wasm_bindgen::JsCast;
-use web_sys::HtmlCanvasElement;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    Document, HtmlCanvasElement, IntersectionObserver, IntersectionObserverEntry, ResizeObserver, ResizeObserverEntry
+};
 use winit::platform::web::WindowExtWebSys;
 use winit::platform::web::WindowBuilderExtWebSys;
 use winit::{
-    dpi::LogicalSize, event, event_loop::EventLoop, window::WindowBuilder
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize}, event, event_loop::EventLoopWindowTarget, window::{WindowBuilder, WindowId}
 };
 
+use crate::Error;
+
 type JSEventListener = Closure<dyn FnMut(web_sys::Event)>;
 
+/// Pixel ratio used unless changed with [Window::set_max_pixel_ratio].
+pub const DEFAULT_MAX_PIXEL_RATIO: f64 = 2.0;
+
+/// Longest time step in milliseconds a frame reports, so coming back from a hidden tab doesn't skip the animations ahead.
+const MAX_ELAPSED_TIME: f64 = 100.0;
+
 ///
 /// The threedweb window. Uses winit as a backend.
 ///
+/// The window follows the size the page lays the canvas out at. The drawing buffer of the canvas
+/// is resized to match, using the `devicePixelRatio` of the screen up to [Window::set_max_pixel_ratio].
+///
 pub struct Window {
     pub(super) window: winit::window::Window,
     pub(super) event_listeners: HashMap<String, JSEventListener>,
     pub(super) gl: WindowedContext,
-    pub(super) fig: FrameInputGenerator
+    pub(super) fig: FrameInputGenerator,
+    /// Size of the canvas in CSS pixels, kept up to date by the resize observer.
+    css_size: Rc<Cell<(f64, f64)>>,
+    max_pixel_ratio: Rc<Cell<f64>>,
+    /// The pixel ratio the drawing buffer currently has.
+    pixel_ratio: f64,
+    /// Inline `width` and `height` of the canvas before winit overwrote them.
+    style: (String, String),
+    resize_observer: ResizeObserver,
+    _resize_callback: Closure<dyn FnMut(js_sys::Array)>,
+    /// Whether any part of the canvas is in the viewport of the page.
+    on_screen: Rc<Cell<bool>>,
+    intersection_observer: IntersectionObserver,
+    _intersection_callback: Closure<dyn FnMut(js_sys::Array)>,
+    document: Document,
+    visibility_listener: JSEventListener,
+    /// Time cut from the frames that took too long, taken out of the accumulated time too.
+    time_skipped: f64,
+    /// Set by the observers when the canvas needs a new frame even if the scene is waiting for events.
+    woken: Rc<Cell<bool>>,
 }
 
 impl Window {
     ///
     /// Create a new window.
-    /// Requires an existing [HtmlCanvasElement] to bind to and an event loop.
+    /// Requires an existing [HtmlCanvasElement] to bind to and the target of the running event loop.
+    /// Windows are normally created through [start](super::runtime::start), which takes care of that.
     ///
     /// It is recommended to disable the `contextmenu` event on the canvas element with JavaScript
     /// before passing it to the handler. This way if you use the right click for anything in your
@@ -33,52 +68,108 @@ impl Window {
     /// and calling `preventDefault` on the event object.
     ///
     /// Use [SurfaceSettings::default] as the surface settings if you're not sure what it's for.
+    /// Fails if the browser can't give the canvas a WebGL context or its observers.
     ///
     pub fn new(
         canvas: HtmlCanvasElement,
-        event_loop: &EventLoop<()>,
+        target: &EventLoopWindowTarget<()>,
         surface_settings: SurfaceSettings
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let size = (canvas.width(), canvas.height());
+        let style = canvas.style();
+        let inline_size = (
+            style.get_property_value("width").unwrap_or_default(),
+            style.get_property_value("height").unwrap_or_default(),
+        );
         let builder = {
             WindowBuilder::new()
                 .with_canvas(Some(canvas))
                 .with_inner_size::<LogicalSize<u32>>(LogicalSize::from(size))
                 .with_prevent_default(true) // allows for custom controls in rendering
         };
-        let window = builder.build(&event_loop).unwrap();
-        let mut event_listeners: HashMap<String, JSEventListener> = HashMap::new();
-        // Create custom exit event to stop program on demand
-        let exit_listener = Closure::new(move |_| {
-            unsafe {
-                super::_SHOULD_EXIT = true;
+        let window = builder.build(target).map_err(|e| Error::Canvas(e.to_string()))?;
+        let context = WindowedContext::from_winit_window(&window, surface_settings)
+            .map_err(|e| Error::Context(e.to_string()))?;
+        let frame_input_generator = FrameInputGenerator::from_winit_window(&window);
+
+        let canvas = window.canvas();
+        let woken = Rc::new(Cell::new(false));
+        let css_size = Rc::new(Cell::new((size.0 as f64, size.1 as f64)));
+        let observed_size = css_size.clone();
+        let resized = woken.clone();
+        let resize_callback = Closure::new(move |entries: js_sys::Array| {
+            if let Some(entry) = entries.iter().last() {
+                let rect = entry.unchecked_into::<ResizeObserverEntry>().content_rect();
+                observed_size.set((rect.width(), rect.height()));
+                resized.set(true);
+                super::runtime::wake();
             }
-            log::info!("exit event triggered");
         });
-        // Register event on canvas element
-        window.canvas()
-            .add_event_listener_with_callback("__wasm_exit", exit_listener.as_ref().unchecked_ref())
-            .expect("Failed to add exit event to canvas");
-        event_listeners.insert("__wasm_exit".to_string(), exit_listener);
-        let context =
-            WindowedContext::from_winit_window(&window, surface_settings).unwrap();
-        let frame_input_generator = FrameInputGenerator::from_winit_window(&window);
-        Self {
+        let resize_observer = ResizeObserver::new(resize_callback.as_ref().unchecked_ref())
+            .map_err(|e| js_error("Can't observe the size of the canvas", e))?;
+        resize_observer.observe(&canvas);
+
+        let on_screen = Rc::new(Cell::new(true));
+        let intersecting = on_screen.clone();
+        let scrolled_in = woken.clone();
+        let intersection_callback = Closure::new(move |entries: js_sys::Array| {
+            if let Some(entry) = entries.iter().last() {
+                let visible = entry.unchecked_into::<IntersectionObserverEntry>().is_intersecting();
+                intersecting.set(visible);
+                if visible {
+                    scrolled_in.set(true);
+                    super::runtime::wake();
+                }
+            }
+        });
+        let intersection_observer = IntersectionObserver::new(intersection_callback.as_ref().unchecked_ref())
+            .map_err(|e| js_error("Can't observe the visibility of the canvas", e))?;
+        intersection_observer.observe(&canvas);
+
+        let document = web_sys::window().and_then(|w| w.document())
+            .ok_or_else(|| Error::Canvas("There is no document".to_string()))?;
+        let shown = woken.clone();
+        let visibility_listener: JSEventListener = Closure::new(move |_| {
+            shown.set(true);
+            super::runtime::wake();
+        });
+        document
+            .add_event_listener_with_callback("visibilitychange", visibility_listener.as_ref().unchecked_ref())
+            .map_err(|e| js_error("Can't listen to the visibility of the page", e))?;
+
+        let mut window = Self {
             window,
             gl: context,
-            event_listeners,
-            fig: frame_input_generator
-        }
+            event_listeners: HashMap::new(),
+            fig: frame_input_generator,
+            css_size,
+            max_pixel_ratio: Rc::new(Cell::new(DEFAULT_MAX_PIXEL_RATIO)),
+            pixel_ratio: 0.0,
+            style: inline_size,
+            resize_observer,
+            _resize_callback: resize_callback,
+            on_screen,
+            intersection_observer,
+            _intersection_callback: intersection_callback,
+            document,
+            visibility_listener,
+            time_skipped: 0.0,
+            woken,
+        };
+        window.restore_style();
+        window.update_size();
+        Ok(window)
     }
 
     /// Register an event listener and attach it to the canvas element.
     /// It can be triggered by javascript on the page.
-    pub fn on(&mut self, event: &str, listener: impl FnMut(web_sys::Event) + 'static) {
+    pub fn on(&mut self, event: &str, listener: impl FnMut(web_sys::Event) + 'static) -> Result<(), Error> {
         let listener = Closure::new(listener);
         self.window.canvas()
             .add_event_listener_with_callback(event, listener.as_ref().unchecked_ref())
-            .unwrap();
+            .map_err(|e| js_error(&format!("Can't listen to {} events", event), e))?;
         self.event_listeners.insert(event.to_string(), listener);
+        Ok(())
     }
 
     /// Returns the current viewport of the window in physical pixels (the size of the screen returned from [FrameInput::screen]).
@@ -95,44 +186,135 @@ impl Window {
     /// Get the current logical size of the window.
     pub fn size(&self) -> (u32, u32) {
         self.window.inner_size()
-            .to_logical::<f64>(self.window.scale_factor())
+            .to_logical::<f64>(self.pixel_ratio)
             .into()
     }
 
-    pub fn start<F>(
-        mut self, // Not a reference because event loop can only be started once
-        event_loop: EventLoop<()>,
-        mut render_callback: F
-    ) where F: 'static + FnMut(FrameInput) -> FrameOutput {
-        event_loop.run(move |event, _, control_flow| match &event {
-            event::Event::LoopDestroyed => {
-                // Remove all event listeners
-                self.event_listeners.iter().for_each(|(event, listener)| {
-                    self.window.canvas()
-                        .remove_event_listener_with_callback(event, listener.as_ref().unchecked_ref())
-                        .unwrap();
-                });
-            }
-            event::Event::MainEventsCleared => {
-                self.window.request_redraw();
+    ///
+    /// Limit the resolution of the drawing buffer to `ratio` physical pixels per CSS pixel.
+    /// High density screens go up to 3 or 4, which is a lot of pixels to shade for little visible difference.
+    ///
+    pub fn set_max_pixel_ratio(&self, ratio: f64) {
+        self.max_pixel_ratio.set(ratio);
+    }
+
+    /// Share the max pixel ratio with something that outlives the window, like a handle from JavaScript.
+    pub(super) fn share_max_pixel_ratio(&mut self, ratio: Rc<Cell<f64>>) {
+        self.max_pixel_ratio = ratio;
+    }
+
+    /// The id winit uses for the events of this window.
+    pub fn id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    /// Whether the canvas was resized or came back into view since the last call.
+    pub(super) fn take_woken(&self) -> bool {
+        self.woken.take()
+    }
+
+    /// Whether the canvas can be seen: the tab is in the foreground and the canvas isn't scrolled away.
+    pub fn is_visible(&self) -> bool {
+        self.on_screen.get() && !self.document.hidden()
+    }
+
+    pub(super) fn request_redraw(&self) {
+        self.window.request_redraw();
+    }
+
+    pub(super) fn handle_event(&mut self, event: &event::WindowEvent) {
+        // winit positions are in device pixels, which don't match the drawing buffer if the pixel ratio is capped
+        let scale = self.pixel_ratio / self.window.scale_factor();
+        let rescale = |position: &PhysicalPosition<f64>| PhysicalPosition::new(position.x * scale, position.y * scale);
+        match event {
+            // The size is managed by the window itself, winit would override the layout of the page
+            event::WindowEvent::Resized(_) | event::WindowEvent::ScaleFactorChanged { .. } => {
+                self.restore_style();
             }
-            event::Event::RedrawRequested(_) => {
-                let frame_input = self.fig.generate(&self.gl);
-                let frame_output = render_callback(frame_input);
-                let should_exit = unsafe { super::_SHOULD_EXIT };
-                if frame_output.exit || should_exit {
-                    control_flow.set_exit();
-                } else if frame_output.wait_next_event {
-                    control_flow.set_wait();
-                } else {
-                    control_flow.set_poll();
-                    self.window.request_redraw();
-                }
+            #[allow(deprecated)]
+            event::WindowEvent::CursorMoved { device_id, position, modifiers } => {
+                self.fig.handle_winit_window_event(&event::WindowEvent::CursorMoved {
+                    device_id: *device_id,
+                    position: rescale(position),
+                    modifiers: *modifiers,
+                });
             }
-            event::Event::WindowEvent { ref event, .. } => {
-                self.fig.handle_winit_window_event(event);
+            event::WindowEvent::Touch(touch) => {
+                self.fig.handle_winit_window_event(&event::WindowEvent::Touch(event::Touch {
+                    location: rescale(&touch.location),
+                    ..*touch
+                }));
             }
-            _ => {}
+            _ => self.fig.handle_winit_window_event(event),
+        }
+    }
+
+    pub(super) fn frame_input(&mut self) -> FrameInput {
+        self.update_size();
+        let mut frame_input = self.fig.generate(&self.gl);
+        if frame_input.elapsed_time > MAX_ELAPSED_TIME {
+            self.time_skipped += frame_input.elapsed_time - MAX_ELAPSED_TIME;
+            frame_input.elapsed_time = MAX_ELAPSED_TIME;
+        }
+        frame_input.accumulated_time -= self.time_skipped;
+        frame_input
+    }
+
+    /// Resize the drawing buffer if the layout size, the device pixel ratio or the max pixel ratio changed.
+    fn update_size(&mut self) {
+        let (css_width, css_height) = self.css_size.get();
+        let ratio = self.window.scale_factor().min(self.max_pixel_ratio.get()).max(0.1);
+        let size = PhysicalSize::new(
+            ((css_width * ratio).round() as u32).max(1),
+            ((css_height * ratio).round() as u32).max(1),
+        );
+        let canvas = self.window.canvas();
+        if ratio == self.pixel_ratio && size.width == canvas.width() && size.height == canvas.height() {
+            return;
+        }
+        canvas.set_width(size.width);
+        canvas.set_height(size.height);
+        self.pixel_ratio = ratio;
+        let mut new_inner_size = size;
+        self.fig.handle_winit_window_event(&event::WindowEvent::ScaleFactorChanged {
+            scale_factor: ratio,
+            new_inner_size: &mut new_inner_size,
         });
     }
+
+    /// Put back the inline size of the canvas, winit sets it to fixed pixels whenever it resizes the canvas.
+    fn restore_style(&self) {
+        let style = self.window.canvas().style();
+        for (property, value) in [("width", &self.style.0), ("height", &self.style.1)] {
+            let result = if value.is_empty() {
+                style.remove_property(property).map(|_| ())
+            } else {
+                style.set_property(property, value)
+            };
+            if let Err(e) = result {
+                log::warn!("Can't restore the {} of the canvas: {:?}", property, e);
+            }
+        }
+    }
+}
+
+impl Drop for Window {
+    /// Removes the listeners from the canvas. The winit window and the graphics context go with it,
+    /// which leaves the canvas free to be bound again.
+    fn drop(&mut self) {
+        self.resize_observer.disconnect();
+        self.intersection_observer.disconnect();
+        // Removing listeners only fails for arguments that aren't functions, there's nothing to do about it here
+        let _ = self.document
+            .remove_event_listener_with_callback("visibilitychange", self.visibility_listener.as_ref().unchecked_ref());
+        for (event, listener) in &self.event_listeners {
+            let _ = self.window.canvas()
+                .remove_event_listener_with_callback(event, listener.as_ref().unchecked_ref());
+        }
+    }
+}
+
+/// A canvas error from an exception the browser threw.
+fn js_error(what: &str, exception: JsValue) -> Error {
+    Error::Canvas(format!("{}: {:?}", what, exception))
 }
@@ -0,0 +1,125 @@
+use eyecandy::rubik::{
+    apply_moves, cases, parse_alg, recognize, recognize_masked, AlgSet, MaskPreset, Move, StickerMask, SOLVED,
+};
+use eyecandy::Error;
+
+mod common;
+
+use common::moves;
+
+fn notation(moves: &[Move]) -> String {
+    moves.iter().map(|mv| mv.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+///
+/// Whether what `set` solves is solved, up to a turn of U: the first two layers for F2L, then the
+/// orientation of the last layer for OLL, its corners for COLL and the whole cube for the rest.
+///
+fn is_done(set: AlgSet, facelets: &str) -> bool {
+    let first_two_layers = |i: usize| i / 9 == 3 || i / 9 != 1 && i % 9 >= 3;
+    let must_match = |i: usize| match set {
+        AlgSet::F2l => first_two_layers(i),
+        AlgSet::Oll => first_two_layers(i) || i / 9 == 1,
+        AlgSet::Coll => first_two_layers(i) || i / 9 == 1 || (i % 9).is_multiple_of(2),
+        AlgSet::Pll | AlgSet::Zbll => true,
+    };
+    (0..4).any(|auf| {
+        let turned = apply_moves(facelets, &vec![Move::U; auf]).unwrap().into_bytes();
+        (0..54).filter(|&i| must_match(i)).all(|i| turned[i] == SOLVED.as_bytes()[i])
+    })
+}
+
+#[test]
+fn algorithms_are_parsed_into_face_turns() {
+    assert_eq!(parse_alg("R U R' U'").unwrap(), moves("R U R' U'"));
+    assert_eq!(parse_alg("R3 U2'").unwrap(), moves("R' U2"));
+    assert_eq!(parse_alg("(R U) [R' U']").unwrap(), moves("R U R' U'"));
+    assert_eq!(parse_alg("x y z x' z' y'").unwrap(), vec![]);
+
+    // Wide turns move the opposite face and rotate the cube, r is like L x
+    assert_eq!(parse_alg("r U r'").unwrap(), moves("L F L'"));
+    assert_eq!(parse_alg("Rw U Rw'").unwrap(), parse_alg("r U r'").unwrap());
+    // Rotations remap the faces that come after them
+    assert_eq!(parse_alg("y R U R'").unwrap(), moves("B U B'"));
+    assert_eq!(parse_alg("z U").unwrap(), moves("L"));
+    // Slices turn both faces around them, E is like U D' y'
+    assert_eq!(parse_alg("E R").unwrap(), moves("U D' F"));
+    assert_eq!(parse_alg("M2 U M2 U2 M2 U M2").unwrap(), parse_alg("R2 L2 D R2 L2 U2 R2 L2 D R2 L2").unwrap());
+
+    // The checkerboard puts the opposite color on the edges of every face
+    let checkerboard = apply_moves(SOLVED, &parse_alg("M2 E2 S2").unwrap()).unwrap().into_bytes();
+    for face in 0..6 {
+        let stickers = &checkerboard[face * 9..face * 9 + 9];
+        assert!([0, 2, 6, 8].iter().all(|&i| stickers[i] == stickers[4]));
+        assert!([1, 3, 5, 7].iter().all(|&i| stickers[i] != stickers[4] && stickers[i] == stickers[1]));
+    }
+
+    assert!(matches!(parse_alg("R Q"), Err(Error::Parse(_))));
+}
+
+#[test]
+fn database_sizes() {
+    let sizes = AlgSet::ALL.map(|set| cases(set).len());
+    assert_eq!(sizes, [41, 57, 21, 40, 144]);
+}
+
+#[test]
+fn every_case_is_recognized_and_solved_by_its_algorithm() {
+    for set in AlgSet::ALL {
+        // F2L pairs can be in any slot, the last layer is always on top
+        let rotations = if set == AlgSet::F2l { 4 } else { 1 };
+        for case in cases(set) {
+            for y in 0..rotations {
+                for pre_auf in 0..4 {
+                    let setup = format!("{} {} {}", "y ".repeat(y), notation(&case.setup()), "U ".repeat(pre_auf));
+                    let facelets = apply_moves(SOLVED, &parse_alg(&setup).unwrap()).unwrap();
+                    let found = recognize(&facelets).unwrap();
+                    let matched = found.iter().find(|m| m.case.set == set)
+                        .unwrap_or_else(|| panic!("{} isn't recognized after {}", case, setup));
+                    assert_eq!(matched.case.name, case.name, "{} recognized as {} after {}", case, matched.case, setup);
+
+                    // The algorithm solves the case held the way the match says
+                    let solution = format!("{}{}{}", "y ".repeat(matched.y as usize), "U ".repeat(matched.auf as usize), case.algs[0]);
+                    let solved = apply_moves(&facelets, &parse_alg(&solution).unwrap()).unwrap();
+                    assert!(is_done(set, &solved), "{} isn't solved by {} after {}", case, solution, setup);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn every_algorithm_of_a_case_solves_it() {
+    for set in AlgSet::ALL {
+        for case in cases(set) {
+            for alg in case.algs {
+                // Other algorithms may start from another turn of U than the main one
+                let solves = (0..4).any(|pre_auf| {
+                    let alg = parse_alg(&format!("{}{}", "U ".repeat(pre_auf), alg)).unwrap();
+                    is_done(set, &apply_moves(&case.facelets(), &alg).unwrap())
+                });
+                assert!(solves, "{} isn't solved by {}", case, alg);
+            }
+        }
+    }
+}
+
+#[test]
+fn oll_cases_are_recognized_whatever_the_permutation() {
+    let oll = StickerMask::preset(MaskPreset::Oll);
+    // A Y perm and a turn of U before each case, which the OLL stickering hides
+    let permuted = "F R U' R' U' R U R' F' R U R' U' R' F R F' U";
+    for case in cases(AlgSet::Oll) {
+        for pre_auf in 0..4 {
+            let setup = format!("{} {} {}", permuted, notation(&case.setup()), "U ".repeat(pre_auf));
+            let facelets = apply_moves(SOLVED, &parse_alg(&setup).unwrap()).unwrap();
+            let found = recognize_masked(&facelets, &oll).unwrap();
+            let matched = found.iter().find(|m| m.case.set == AlgSet::Oll)
+                .unwrap_or_else(|| panic!("{} isn't recognized after {}", case, setup));
+            assert_eq!(matched.case.name, case.name, "{} recognized as {} after {}", case, matched.case, setup);
+            let solution = format!("{}{}", "U ".repeat(matched.auf as usize), case.algs[0]);
+            let oriented = apply_moves(&facelets, &parse_alg(&solution).unwrap()).unwrap();
+            assert!(is_done(AlgSet::Oll, &oriented), "{} isn't oriented by {} after {}", case, solution, setup);
+        }
+    }
+}
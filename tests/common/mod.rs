@@ -0,0 +1,64 @@
+// Every test crate includes this module but only uses some of it
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use eyecandy::rubik::Move;
+use khronos_egl as egl;
+
+/// `EGL_PLATFORM_SURFACELESS_MESA`, rendering without a display or a window.
+const PLATFORM_SURFACELESS: egl::Enum = 0x31DD;
+
+///
+/// A graphics context that doesn't need a window or a display, like Mesa's software renderer on a
+/// server. Nothing if EGL or the surfaceless platform aren't there, the tests using it are skipped then.
+///
+/// Drawing has to go to a render target of its own, there's no screen.
+///
+pub fn context() -> Option<three_d::Context> {
+    let egl = match unsafe { egl::DynamicInstance::<egl::EGL1_5>::load_required() } {
+        Ok(egl) => egl,
+        Err(e) => return skip(&e),
+    };
+    let display = unsafe { egl.get_platform_display(PLATFORM_SURFACELESS, egl::DEFAULT_DISPLAY, &[egl::ATTRIB_NONE]) };
+    let display = match display {
+        Ok(display) => display,
+        Err(e) => return skip(&e),
+    };
+    let attributes = [egl::SURFACE_TYPE, egl::PBUFFER_BIT, egl::RENDERABLE_TYPE, egl::OPENGL_BIT, egl::NONE];
+    let context = egl.initialize(display)
+        .and_then(|_| egl.bind_api(egl::OPENGL_API))
+        .and_then(|_| egl.choose_first_config(display, &attributes))
+        .and_then(|config| {
+            let config = config.ok_or(egl::Error::BadConfig)?;
+            let version = [
+                egl::CONTEXT_MAJOR_VERSION, 3,
+                egl::CONTEXT_MINOR_VERSION, 3,
+                egl::CONTEXT_OPENGL_PROFILE_MASK, egl::CONTEXT_OPENGL_CORE_PROFILE_BIT,
+                egl::NONE,
+            ];
+            let context = egl.create_context(display, config, None, &version)?;
+            egl.make_current(display, None, None, Some(context))
+        });
+    if let Err(e) = context {
+        return skip(&e);
+    }
+    let gl = unsafe {
+        three_d::context::Context::from_loader_function(|name| {
+            egl.get_proc_address(name).map_or(std::ptr::null(), |f| f as *const _)
+        })
+    };
+    // The context stays current on this thread for as long as the test runs
+    std::mem::forget(egl);
+    three_d::Context::from_gl_context(Arc::new(gl)).ok()
+}
+
+/// The moves of a sequence in standard notation, which the test knows is valid.
+pub fn moves(notation: &str) -> Vec<Move> {
+    Move::parse_sequence(notation).unwrap()
+}
+
+fn skip<T>(error: &dyn std::fmt::Display) -> Option<T> {
+    eprintln!("No headless graphics context, skipping: {}", error);
+    None
+}
@@ -0,0 +1,104 @@
+use eyecandy::rubik::{analyze, apply_moves, check_facelets, scramble, Corner, Cubie, CubieCube, Edge, Move, SOLVED};
+use eyecandy::Error;
+
+mod common;
+
+use common::moves;
+
+#[test]
+fn cubies_match_the_facelets() {
+    for seed in 1..20 {
+        let scramble = scramble(seed, 25);
+        let facelets = apply_moves(SOLVED, &scramble).unwrap();
+        let cube = CubieCube::from_facelets(&facelets).unwrap();
+        assert_eq!(cube, CubieCube::from_moves(&scramble));
+        assert_eq!(cube.to_facelets(), facelets);
+        assert!(cube.is_solvable());
+        assert!((cube * cube.inverse()).is_solved());
+    }
+    // A single swapped pair of edges can't be solved
+    let mut swapped = CubieCube::SOLVED;
+    swapped.ep.swap(0, 1);
+    assert!(!swapped.is_solvable());
+    assert!(CubieCube::from_moves(&moves("R U R' U' R' F R2 U' R' U' R U R' F'")).is_solvable());
+}
+
+fn rejected(facelets: &str) -> String {
+    check_facelets(facelets).unwrap_err().to_string()
+}
+
+#[test]
+fn impossible_states_are_rejected() {
+    let scrambled = apply_moves(SOLVED, &scramble(7, 25)).unwrap();
+    assert_eq!(check_facelets(SOLVED), Ok(()));
+    assert_eq!(check_facelets(&scrambled), Ok(()));
+
+    let mut twisted = CubieCube::SOLVED;
+    twisted.co[0] = 1;
+    assert_eq!(rejected(&twisted.to_facelets()), "A corner is twisted");
+
+    let mut flipped = CubieCube::SOLVED;
+    flipped.eo[0] = 1;
+    assert_eq!(rejected(&flipped.to_facelets()), "An edge is flipped");
+
+    let mut swapped = CubieCube::SOLVED;
+    swapped.ep.swap(0, 1);
+    assert_eq!(rejected(&swapped.to_facelets()), "Two pieces are swapped");
+    let mut swapped = CubieCube::SOLVED;
+    swapped.cp.swap(0, 1);
+    assert_eq!(rejected(&swapped.to_facelets()), "Two pieces are swapped");
+
+    // Swapping two stickers of a corner mirrors it, the stickers of the twisted corner are the ones to swap
+    let corner: Vec<usize> = twisted.to_facelets().bytes().zip(SOLVED.bytes())
+        .enumerate().filter(|(_, (a, b))| a != b).map(|(i, _)| i).collect();
+    let mut mirrored = SOLVED.as_bytes().to_vec();
+    mirrored.swap(corner[0], corner[1]);
+    assert_eq!(rejected(&String::from_utf8(mirrored).unwrap()), "The corner at URF is mirrored");
+
+    // Right number of every color and the same parity, but a corner and an edge are there twice
+    let repeated = "GBBBBBBBBYYYYYYYYYRRRRRORRRWWWWWWWWWGGGBGGGGGOOROOOOOO";
+    assert_eq!(rejected(repeated), "The piece YRG is there twice");
+    assert_eq!(CubieCube::from_facelets(repeated), Err(Error::Validation("There are two URF corners".to_string())));
+    let mut doubled = CubieCube::SOLVED;
+    doubled.ep[0] = doubled.ep[1];
+    assert_eq!(doubled.check_solvable(), Err(Error::Validation("A piece is there twice".to_string())));
+}
+
+#[test]
+fn states_compose() {
+    let (a, b) = (moves("R U R' U'"), moves("F2 D B'"));
+    let both: Vec<Move> = a.iter().chain(&b).copied().collect();
+    assert_eq!(CubieCube::from_moves(&a) * CubieCube::from_moves(&b), CubieCube::from_moves(&both));
+    assert_eq!(CubieCube::from_moves(&a).inverse(), CubieCube::from_moves(&moves("U R U' R'")));
+}
+
+#[test]
+fn orders_of_algorithms() {
+    assert_eq!(analyze(&moves("R U")).order, 105);
+    assert_eq!(analyze(&moves("R U R' U'")).order, 6);
+    assert_eq!(analyze(&moves("R")).order, 4);
+    assert_eq!(analyze(&[]).order, 1);
+}
+
+#[test]
+fn cycles_of_algorithms() {
+    // The T permutation swaps two corners and two edges
+    let t_perm = analyze(&moves("R U R' U' R' F R2 U' R' U' R U R' F'"));
+    assert_eq!(t_perm.order, 2);
+    assert_eq!(t_perm.cycles.len(), 2);
+    assert!(t_perm.cycles.iter().all(|cycle| cycle.pieces.len() == 2 && cycle.orientation == 0));
+    assert_eq!(t_perm.affected.len(), 4);
+    // Sune twists and swaps the corners and cycles three edges
+    let sune = analyze(&moves("R U R' U R U2 R'"));
+    assert_eq!(sune.order, 6);
+    assert_eq!(sune.cycles.iter().filter(|cycle| cycle.orientation != 0).count(), 2);
+    assert_eq!(sune.cycles.last().unwrap().to_string(), "(UR UB UL)");
+    // A corner commutator touches more pieces than it affects
+    let commutator = analyze(&moves("R U R' D R U' R' D'"));
+    assert_eq!(commutator.cycles.len(), 1);
+    assert_eq!(commutator.cycles[0].pieces.len(), 3);
+    assert!(commutator.touched.len() > commutator.affected.len());
+    assert!(commutator.touched.contains(&Cubie::Edge(Edge::Ur)));
+    assert!(!commutator.affected.contains(&Cubie::Edge(Edge::Ur)));
+    assert!(commutator.affected.contains(&Cubie::Corner(Corner::Urf)));
+}
@@ -0,0 +1,104 @@
+use eyecandy::control::SmoothOrbitControlSettings;
+use eyecandy::rubik::{
+    alg_cubing_url, apply_moves, from_kociemba, parse_alg_url, scramble, to_kociemba, twizzle_url, CubeAnimationOptions, Move,
+    MoveGroup, RubikMaterial, SOLVED,
+};
+use eyecandy::Error;
+use three_d::Srgba;
+
+#[test]
+fn moves_round_trip_through_json() {
+    let json = serde_json::to_string(&Move::ALL).unwrap();
+    assert_eq!(json, r#"["L","L'","L2","R","R'","R2","U","U'","U2","D","D'","D2","F","F'","F2","B","B'","B2"]"#);
+    assert_eq!(serde_json::from_str::<Vec<Move>>(&json).unwrap(), Move::ALL);
+    assert!(serde_json::from_str::<Move>(r#""X""#).is_err());
+}
+
+#[test]
+fn animation_options_round_trip_through_json() {
+    let options = CubeAnimationOptions { move_time: 300.0, move_smoothing: 1.5 };
+    let json = serde_json::to_string(&options).unwrap();
+    assert_eq!(serde_json::from_str::<CubeAnimationOptions>(&json).unwrap(), options);
+    // Missing fields keep their defaults
+    let partial: CubeAnimationOptions = serde_json::from_str(r#"{"move_time": 500.0}"#).unwrap();
+    assert_eq!(partial.move_time, 500.0);
+    assert_eq!(partial.move_smoothing, CubeAnimationOptions::default().move_smoothing);
+}
+
+#[test]
+fn control_settings_round_trip_through_json() {
+    let mut settings = SmoothOrbitControlSettings { min_zoom: 4.0, ..Default::default() };
+    settings.scroll_params.zeta = 0.5;
+    let json = serde_json::to_string(&settings).unwrap();
+    assert_eq!(serde_json::from_str::<SmoothOrbitControlSettings>(&json).unwrap(), settings);
+}
+
+#[test]
+fn material_round_trips_through_json() {
+    let material = RubikMaterial {
+        margin: 0.1,
+        stickerless: true,
+        body_color: Srgba::new_opaque(20, 30, 40),
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&material).unwrap();
+    let loaded: RubikMaterial = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    assert_eq!(loaded.body_color, material.body_color);
+}
+
+#[test]
+fn kociemba_strings_round_trip() {
+    assert_eq!(to_kociemba(SOLVED).unwrap(), "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB");
+    for seed in 1..20 {
+        let state = apply_moves(SOLVED, &scramble(seed, 25)).unwrap();
+        assert_eq!(from_kociemba(&to_kociemba(&state).unwrap()).unwrap(), state);
+    }
+    // A known state from Kociemba's solver: the cube after R
+    let r = apply_moves(SOLVED, &[Move::R]).unwrap();
+    assert_eq!(to_kociemba(&r).unwrap(), "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB");
+    assert!(from_kociemba("UUUUUUUUU").is_err());
+}
+
+#[test]
+fn move_groups_turn_opposite_faces() {
+    let group = MoveGroup::parse("R L'").unwrap();
+    assert_eq!(group.moves().collect::<Vec<_>>(), [Move::R, Move::LP]);
+    assert_eq!(group.to_string(), "R L'");
+    assert_eq!(MoveGroup::from(Move::U2).moves().collect::<Vec<_>>(), [Move::U2]);
+    // Both orders give the same state, which is why they can turn together
+    assert_eq!(apply_moves(SOLVED, &[Move::R, Move::LP]), apply_moves(SOLVED, &[Move::LP, Move::R]));
+    assert!(matches!(MoveGroup::parse("R U"), Err(Error::Validation(_))));
+    assert!(MoveGroup::parse("R R'").is_err());
+    assert!(MoveGroup::parse("U D F").is_err());
+    assert!(MoveGroup::parse("").is_err());
+}
+
+#[test]
+fn errors_tell_bad_input_from_impossible_states() {
+    assert_eq!(Move::parse_sequence("R U X"), Err(Error::Parse("Invalid move X".to_string())));
+    assert!(matches!(apply_moves(&SOLVED[..53], &[]), Err(Error::Parse(_))));
+    let two_reds = SOLVED.replacen('B', "R", 1);
+    let error = apply_moves(&two_reds, &[]).unwrap_err();
+    assert!(matches!(error, Error::Validation(_)));
+    assert_eq!(error.to_string(), "Expected 9 stickers of B, found 8");
+}
+
+#[test]
+fn alg_urls_round_trip() {
+    let setup = Move::parse_sequence("R U R' U' F2").unwrap();
+    let alg = Move::parse_sequence("F2 U R U' R'").unwrap();
+    let url = alg_cubing_url(&setup, &alg);
+    assert_eq!(url, "https://alg.cubing.net/?setup=R_U_R-_U-_F2&alg=F2_U_R_U-_R-");
+    assert_eq!(parse_alg_url(&url).unwrap(), (setup.clone(), alg.clone()));
+    assert_eq!(parse_alg_url(&twizzle_url(&setup, &alg)).unwrap(), (setup, alg));
+}
+
+#[test]
+fn alg_urls_from_the_sites_are_read() {
+    let (setup, alg) = parse_alg_url("https://alg.cubing.net/?alg=R_U_R-_U-%0AR-_F_R2&setup=").unwrap();
+    assert!(setup.is_empty());
+    assert_eq!(alg, Move::parse_sequence("R U R' U' R' F R2").unwrap());
+    let (setup, _) = parse_alg_url("https://alpha.twizzle.net/edit/?setup-alg=R%27+U2&puzzle=3x3x3").unwrap();
+    assert_eq!(setup, Move::parse_sequence("R' U2").unwrap());
+}
@@ -0,0 +1,35 @@
+use eyecandy::rubik::{apply_moves, Cube, CubeAnimationOptions, CubeAppearance, Move, SOLVED};
+
+mod common;
+
+/// The L, D and B faces of a facelet string, which the hint facelets show.
+fn hidden_faces(facelets: &str) -> String {
+    [&facelets[0..9], &facelets[27..36], &facelets[45..54]].concat()
+}
+
+#[test]
+fn hints_show_the_stickers_on_the_hidden_faces() {
+    let Some(context) = common::context() else { return };
+    let mut cube = Cube::solved(CubeAnimationOptions::default(), &context);
+    assert_eq!(cube.hint_facelets(), None);
+    cube.set_appearance(CubeAppearance { hint_facelets: true, ..Default::default() });
+    assert_eq!(cube.hint_facelets(), Some(hidden_faces(SOLVED)));
+
+    // R takes the yellow stickers of U to the back and the orange ones of B to the bottom
+    cube.apply_moves(Move::parse_sequence("R").unwrap()).unwrap();
+    let hints = cube.hint_facelets().unwrap();
+    assert_eq!(hints, "BBBBBBBBBWWOWWOWWOYOOYOOYOO");
+    assert_eq!(hints, hidden_faces(&cube.facelet_str()));
+
+    // Also for moves that played, and with every piece moved around
+    let moves = Move::parse_sequence("U' F2 L D' B R2 U F' D2").unwrap();
+    cube.queue(moves.iter().copied());
+    let mut time = 0.0;
+    while !cube.is_idle() {
+        time += 10.0;
+        cube.animate(time).unwrap();
+    }
+    let expected = apply_moves(SOLVED, &Move::parse_sequence("R U' F2 L D' B R2 U F' D2").unwrap()).unwrap();
+    assert_eq!(cube.facelet_str(), expected);
+    assert_eq!(cube.hint_facelets(), Some(hidden_faces(&expected)));
+}
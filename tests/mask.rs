@@ -0,0 +1,75 @@
+use eyecandy::rubik::{
+    apply_moves, find_case, home_facelets, recognize, recognize_masked, scramble, AlgSet, CubieCube, MaskPreset,
+    OptimalSolver, StickerMask, StickerStyle, SOLVED,
+};
+
+mod common;
+
+use common::moves;
+
+#[test]
+fn presets() {
+    assert!(StickerMask::preset(MaskPreset::Full).is_full());
+    let cross = StickerMask::preset(MaskPreset::Cross);
+    assert!(!cross.is_full());
+    // The center and the edges of D
+    assert_eq!(cross.style(31), StickerStyle::Regular);
+    assert_eq!(cross.style(28), StickerStyle::Regular);
+    assert_eq!(cross.style(27), StickerStyle::Ignored);
+    assert_eq!(cross.style(10), StickerStyle::Ignored);
+    let oll = StickerMask::preset(MaskPreset::Oll);
+    assert_eq!(oll.style(9), StickerStyle::Regular);
+    assert_eq!(oll.style(0), StickerStyle::Ignored);
+    assert_eq!(oll.style(27), StickerStyle::Dim);
+    for preset in MaskPreset::ALL {
+        assert_eq!(preset.name().to_lowercase().parse::<MaskPreset>(), Ok(preset));
+    }
+    assert!("Roux".parse::<MaskPreset>().is_err());
+}
+
+#[test]
+fn stickers_are_followed_by_their_pieces() {
+    assert_eq!(home_facelets(SOLVED).unwrap(), std::array::from_fn(|facelet| facelet));
+    let turned = apply_moves(SOLVED, &moves("R U F'")).unwrap();
+    let homes = home_facelets(&turned).unwrap();
+    for (facelet, &home) in homes.iter().enumerate() {
+        assert_eq!(turned.as_bytes()[facelet], SOLVED.as_bytes()[home]);
+    }
+    assert!(home_facelets(&SOLVED.replace('R', "O")).is_err());
+
+    let cross = StickerMask::preset(MaskPreset::Cross);
+    assert!(cross.is_solved(&apply_moves(SOLVED, &moves("U R U' R'")).unwrap()).unwrap());
+    assert!(!cross.is_solved(&apply_moves(SOLVED, &moves("R")).unwrap()).unwrap());
+    let masked = cross.apply(&turned).unwrap();
+    assert_eq!(masked.chars().filter(|&c| c != '-').count(), 6 + 8);
+}
+
+#[test]
+fn masked_stickers_are_left_out_of_recognition() {
+    // Sune with the last layer permuted, which the OLL mask doesn't look at
+    let case = find_case(AlgSet::Oll, "27").unwrap();
+    let facelets = apply_moves(SOLVED, &[moves("R U R' U' R' F R2 U' R' U' R U R' F'"), case.setup()].concat()).unwrap();
+    let oll = StickerMask::preset(MaskPreset::Oll);
+    let found = recognize_masked(&facelets, &oll).unwrap();
+    assert!(found.iter().any(|m| m.case.set == AlgSet::Oll && m.case.name == case.name));
+    assert!(found.iter().all(|m| m.case.set != AlgSet::F2l));
+    assert_eq!(
+        recognize_masked(&facelets, &StickerMask::FULL).unwrap().iter().map(|m| m.case.name).collect::<Vec<_>>(),
+        recognize(&facelets).unwrap().iter().map(|m| m.case.name).collect::<Vec<_>>(),
+    );
+}
+
+#[test]
+fn masked_solves_only_solve_what_counts() {
+    let cross = StickerMask::preset(MaskPreset::Cross);
+    let solver = OptimalSolver::new().table_depth(4).mask(cross);
+    for seed in 1..4 {
+        let cube = CubieCube::from_moves(&scramble(seed, 25));
+        let solution = &solver.solve(&cube, |_| {}).unwrap()[0];
+        // Every cross takes at most 8 moves
+        assert!(solution.len() <= 8);
+        assert!(cross.is_solved(&cube.apply(solution).to_facelets()).unwrap());
+    }
+    let solved_cross = CubieCube::from_moves(&moves("R U R' U'"));
+    assert_eq!(solver.solve(&solved_cross, |_| {}).unwrap(), vec![vec![]]);
+}
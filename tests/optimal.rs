@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+use eyecandy::rubik::{apply_moves, scramble, CubieCube, Metric, OptimalSolver, SOLVED};
+use eyecandy::Error;
+
+mod common;
+
+use common::moves;
+
+/// Shallow tables keep the tests quick, the solutions are optimal all the same.
+fn solver() -> OptimalSolver {
+    OptimalSolver::new().table_depth(4)
+}
+
+#[test]
+fn solutions_are_optimal() {
+    let solver = solver();
+    assert_eq!(solver.solve(&CubieCube::SOLVED, |_| {}).unwrap(), vec![vec![]]);
+    assert_eq!(solver.solve(&CubieCube::from_moves(&moves("R U")), |_| {}).unwrap(), vec![moves("U' R'")]);
+    // Opposite faces commute, the solution is only given in one order
+    assert_eq!(solver.solve(&CubieCube::from_moves(&moves("L R")), |_| {}).unwrap(), vec![moves("L' R'")]);
+    for seed in 1..4 {
+        let scramble = scramble(seed, 6);
+        let cube = CubieCube::from_moves(&scramble);
+        let solution = &solver.solve(&cube, |_| {}).unwrap()[0];
+        assert!(solution.len() <= scramble.len());
+        assert!(cube.apply(solution).is_solved());
+    }
+}
+
+#[test]
+fn all_optimal_solutions_up_to_a_limit() {
+    // Turns of opposite faces commute, so there are several ways to undo these
+    let cube = CubieCube::from_moves(&moves("R2 L2 U2 D2"));
+    let solutions = solver().max_solutions(100).solve(&cube, |_| {}).unwrap();
+    assert!(solutions.len() > 1);
+    for solution in &solutions {
+        assert_eq!(solution.len(), 4);
+        assert!(cube.apply(solution).is_solved());
+    }
+    let limited = solver().max_solutions(2).threads(1).solve(&cube, |_| {}).unwrap();
+    assert_eq!(limited.len(), 2);
+}
+
+#[test]
+fn quarter_turns_count_half_turns_twice() {
+    let cube = CubieCube::from_moves(&moves("R2 U"));
+    let solution = &solver().metric(Metric::QuarterTurn).solve(&cube, |_| {}).unwrap()[0];
+    assert_eq!(Metric::QuarterTurn.length(solution), 3);
+    assert!(cube.apply(solution).is_solved());
+}
+
+#[test]
+fn progress_and_cancellation() {
+    let solver = solver();
+    let mut depths = vec![];
+    let facelets = apply_moves(SOLVED, &moves("R U F")).unwrap();
+    let cube = CubieCube::from_facelets(&facelets).unwrap();
+    solver.solve(&cube, |progress| depths.push(progress.depth)).unwrap();
+    assert_eq!(depths.first(), Some(&3));
+    assert!(depths.windows(2).all(|pair| pair[0] <= pair[1]));
+    solver.cancel_token().cancel();
+    assert_eq!(solver.solve(&cube, |_| {}), Err(Error::Cancelled));
+    // Cancelling stops one solve, the next one finds the solution
+    assert_eq!(solver.solve(&cube, |_| {}), Ok(vec![moves("F' U' R'")]));
+    // Also when cancelled halfway through
+    let token = solver.cancel_token();
+    let deeper = CubieCube::from_facelets(&apply_moves(SOLVED, &moves("R U F D")).unwrap()).unwrap();
+    assert_eq!(solver.solve(&deeper, |progress| if progress.done > 0.5 { token.cancel() }), Err(Error::Cancelled));
+    assert_eq!(solver.solve(&deeper, |_| {}), Ok(vec![moves("D' F' U' R'")]));
+    let mut twisted = CubieCube::SOLVED;
+    twisted.co[0] = 1;
+    assert!(matches!(OptimalSolver::new().solve(&twisted, |_| {}), Err(Error::Validation(_))));
+}
+
+#[test]
+fn states_with_repeated_pieces_are_rejected() {
+    // Every color nine times and the parities right, but no way to solve it
+    let repeated = "GBBBBBBBBYYYYYYYYYRRRRRORRRWWWWWWWWWGGGBGGGGGOOROOOOOO";
+    assert!(matches!(solver().solve_facelets(repeated), Err(Error::Validation(_))));
+    let mut doubled = CubieCube::SOLVED;
+    doubled.cp[0] = doubled.cp[1];
+    assert!(matches!(solver().solve(&doubled, |_| {}), Err(Error::Validation(_))));
+}
+
+#[test]
+fn building_the_databases_can_be_cancelled() {
+    // The full databases take a minute or more to build
+    let solver = OptimalSolver::new();
+    let token = solver.cancel_token();
+    let start = Instant::now();
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(Duration::from_millis(200));
+            token.cancel();
+        });
+        assert_eq!(solver.prepare(), Err(Error::Cancelled));
+    });
+    assert!(start.elapsed() < Duration::from_secs(10), "Cancelled after {:?}", start.elapsed());
+    assert!(!token.is_cancelled());
+}
+
+/// Builds the whole databases, scanning the tables once the searched states get too many.
+#[test]
+#[ignore = "takes minutes without optimizations, run with --release --ignored"]
+fn full_databases_give_optimal_solutions() {
+    let full = OptimalSolver::new();
+    full.prepare().unwrap();
+    for seed in 1..4 {
+        let cube = CubieCube::from_moves(&scramble(seed, 8));
+        let solution = &full.solve(&cube, |_| {}).unwrap()[0];
+        assert!(cube.apply(solution).is_solved());
+        assert_eq!(solution.len(), solver().solve(&cube, |_| {}).unwrap()[0].len());
+    }
+}
@@ -0,0 +1,106 @@
+use eyecandy::rubik::{Cube, CubeAnimationOptions, Move, MoveGroup, Playback};
+
+mod common;
+
+/// A cube with R U F D waiting to turn, 300 ms a move.
+fn queued(context: &three_d::Context, playback: Playback) -> Cube {
+    let mut cube = Cube::solved(CubeAnimationOptions { move_time: 300.0, ..Default::default() }, context);
+    cube.set_playback(playback);
+    cube.queue(Move::parse_sequence("R U F D").unwrap().into_iter());
+    cube
+}
+
+/// Animate at `time`, returning the move that finished.
+fn finished(cube: &mut Cube, time: f32) -> Option<MoveGroup> {
+    cube.animate(time).unwrap()
+}
+
+#[test]
+fn responsive_playback_catches_up_with_input() {
+    let fixed = Playback::Fixed;
+    assert!(!fixed.snaps(100));
+    assert_eq!(fixed.pace(100), 1.0);
+
+    let responsive = Playback::Responsive { max_pending: 2 };
+    assert!(!responsive.snaps(2));
+    assert!(responsive.snaps(3));
+    assert_eq!(responsive.pace(0), 1.0);
+    assert!(responsive.pace(2) > responsive.pace(1));
+}
+
+#[test]
+fn fixed_moves_take_the_move_time() {
+    let Some(context) = common::context() else { return };
+    let mut cube = queued(&context, Playback::Fixed);
+    // Each move starts on the frame after the last one finished
+    let mut start = 0.0;
+    for mv in [Move::R, Move::U, Move::F, Move::D] {
+        assert_eq!(finished(&mut cube, start), None);
+        assert_eq!(finished(&mut cube, start + 300.0), None);
+        assert_eq!(finished(&mut cube, start + 301.0), Some(mv.into()));
+        start += 302.0;
+    }
+    assert!(cube.is_idle());
+}
+
+#[test]
+fn responsive_moves_snap_and_speed_up() {
+    let Some(context) = common::context() else { return };
+    // With three moves waiting behind R, more than two, R snaps on the next frame
+    let mut cube = queued(&context, Playback::Responsive { max_pending: 2 });
+    assert_eq!(finished(&mut cube, 0.0), None);
+    assert_eq!(finished(&mut cube, 1.0), Some(Move::R.into()));
+    // Two left behind U are few enough to let it turn, four times faster than R would have
+    assert_eq!(finished(&mut cube, 2.0), None);
+    assert_eq!(finished(&mut cube, 100.0), None);
+    assert_eq!(finished(&mut cube, 103.0), Some(Move::U.into()));
+
+    // Later moves take longer as fewer are left waiting behind them
+    let mut cube = queued(&context, Playback::Responsive { max_pending: 10 });
+    let mut start = 0.0;
+    for (mv, move_time) in [(Move::R, 75.0), (Move::U, 100.0), (Move::F, 150.0), (Move::D, 300.0)] {
+        assert_eq!(finished(&mut cube, start), None);
+        assert_eq!(finished(&mut cube, start + move_time - 1.0), None);
+        assert_eq!(finished(&mut cube, start + move_time + 1.0), Some(mv.into()));
+        start += move_time + 2.0;
+    }
+}
+
+#[test]
+fn changing_the_playback_resets_the_pace() {
+    let Some(context) = common::context() else { return };
+    let mut cube = queued(&context, Playback::Responsive { max_pending: 10 });
+    assert_eq!(finished(&mut cube, 0.0), None);
+    // R started four times as fast, but plays at the move time once the playback is fixed
+    cube.set_playback(Playback::Fixed);
+    assert_eq!(finished(&mut cube, 100.0), None);
+    assert_eq!(finished(&mut cube, 299.0), None);
+    assert_eq!(finished(&mut cube, 301.0), Some(Move::R.into()));
+}
+
+#[test]
+fn the_solution_is_known_once_the_cube_was_solved() {
+    let Some(context) = common::context() else { return };
+    let mut cube = Cube::solved(CubeAnimationOptions::default(), &context);
+    cube.apply_moves(Move::parse_sequence("R U R' U' R").unwrap()).unwrap();
+    assert_eq!(cube.solution(), Some(Move::parse_sequence("R' U R U' R'").unwrap()));
+    // Turns of the same face are merged, and solving the cube again drops the moves that led there
+    cube.apply_moves(Move::parse_sequence("R'").unwrap()).unwrap();
+    assert_eq!(cube.solution(), Some(Move::parse_sequence("U R U' R'").unwrap()));
+    cube.apply_moves(Move::parse_sequence("R U R' U' R U R' U' R U R' U' R U R' U' R U R' U'").unwrap()).unwrap();
+    assert!(cube.is_solved());
+    assert_eq!(cube.solution(), Some(vec![]));
+
+    let state = cube.facelet_str();
+    cube.apply_moves([Move::F]).unwrap();
+    let scrambled = cube.facelet_str();
+    cube.set_state(&scrambled).unwrap();
+    assert_eq!(cube.solution(), None);
+    cube.apply_moves([Move::U]).unwrap();
+    assert_eq!(cube.solution(), None);
+    cube.apply_moves(Move::parse_sequence("U' F'").unwrap()).unwrap();
+    assert_eq!(cube.facelet_str(), state);
+    assert_eq!(cube.solution(), Some(vec![]));
+    cube.apply_moves([Move::L]).unwrap();
+    assert_eq!(cube.solution(), Some(vec![Move::L.inverse()]));
+}
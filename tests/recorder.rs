@@ -0,0 +1,133 @@
+use eyecandy::rubik::{
+    apply_moves, find_case, stage, AlgSet, Cube, CubeAnimationOptions, Move, Reconstruction, Replay, Split, Stage, TimedMove,
+    SOLVED,
+};
+
+mod common;
+
+use common::moves;
+
+fn inverse(moves: &[Move]) -> Vec<Move> {
+    moves.iter().rev().map(Move::inverse).collect()
+}
+
+/// Finishing an F2L pair, a Sune and a T perm, a move every 100 ms.
+fn solve() -> Reconstruction {
+    let solution = moves("R U' R' R U R' U R U2 R' R U R' U' R' F R2 U' R' U' R U R' F'");
+    Reconstruction {
+        start: apply_moves(SOLVED, &inverse(&solution)).unwrap(),
+        moves: solution.iter().enumerate().map(|(i, &mv)| TimedMove { mv, time: (i + 1) as f32 * 100.0 }).collect(),
+    }
+}
+
+#[test]
+fn stages_are_found_on_any_face() {
+    assert_eq!(stage(SOLVED).unwrap(), Some(Stage::Pll));
+    let t_perm = find_case(AlgSet::Pll, "T").unwrap();
+    assert_eq!(stage(&t_perm.facelets()).unwrap(), Some(Stage::Oll));
+    let sune = find_case(AlgSet::Oll, "27").unwrap();
+    assert_eq!(stage(&sune.facelets()).unwrap(), Some(Stage::F2l));
+    assert_eq!(stage(&apply_moves(SOLVED, &moves("R U R'")).unwrap()).unwrap(), Some(Stage::Cross));
+    // Turning D leaves the first two layers from U solved, and D is still all one color
+    assert_eq!(stage(&apply_moves(SOLVED, &moves("D")).unwrap()).unwrap(), Some(Stage::Oll));
+    assert_eq!(stage(&apply_moves(SOLVED, &moves("R U F' L2 D B")).unwrap()).unwrap(), None);
+    assert!(stage("").is_err());
+    assert!(stage(&SOLVED[..53]).is_err());
+    assert!(stage(&SOLVED.replace("B", "X")).is_err());
+}
+
+#[test]
+fn splits_follow_the_stages() {
+    let solve = solve();
+    assert_eq!(stage(&solve.start).unwrap(), Some(Stage::Cross));
+    assert_eq!(solve.splits(), vec![
+        Split { stage: Stage::F2l, moves: 3, time: 300.0, end: 300.0 },
+        Split { stage: Stage::Oll, moves: 7, time: 700.0, end: 1000.0 },
+        Split { stage: Stage::Pll, moves: 14, time: 1400.0, end: 2400.0 },
+    ]);
+    assert_eq!(solve.duration(), 2400.0);
+    assert_eq!(solve.tps(), 10.0);
+    assert_eq!(solve.splits()[1].tps(), 10.0);
+    assert_eq!(solve.end(), SOLVED);
+
+    // Skipped stages take no moves and no time
+    let oll_skip = [&solve.moves[..3], &solve.moves[10..]].concat();
+    let solution: Vec<Move> = oll_skip.iter().map(|timed| timed.mv).collect();
+    let oll_skip = Reconstruction {
+        start: apply_moves(SOLVED, &inverse(&solution)).unwrap(),
+        moves: oll_skip.iter().enumerate().map(|(i, timed)| TimedMove { mv: timed.mv, time: (i + 1) as f32 * 100.0 }).collect(),
+    };
+    assert_eq!(oll_skip.splits(), vec![
+        Split { stage: Stage::F2l, moves: 3, time: 300.0, end: 300.0 },
+        Split { stage: Stage::Oll, moves: 0, time: 0.0, end: 300.0 },
+        Split { stage: Stage::Pll, moves: 14, time: 1400.0, end: 1700.0 },
+    ]);
+    // Moves after the last stage reached aren't in a split
+    let unfinished = Reconstruction { start: solve.start.clone(), moves: solve.moves[..5].to_vec() };
+    assert_eq!(unfinished.splits().len(), 1);
+    let invalid = Reconstruction { start: String::new(), moves: solve.moves.clone() };
+    assert_eq!(invalid.splits(), vec![]);
+    assert_eq!(Reconstruction { start: SOLVED.to_string(), moves: vec![] }.tps(), 0.0);
+}
+
+#[test]
+fn reconstructions_are_shown_a_stage_per_line() {
+    let mut solve = solve();
+    solve.moves.push(TimedMove { mv: Move::U, time: 2500.0 });
+    assert_eq!(solve.to_string(), "\
+R U' R' // F2L: 3 moves in 0.300s, 10.00 TPS
+R U R' U R U2 R' // OLL: 7 moves in 0.700s, 10.00 TPS
+R U R' U' R' F R2 U' R' U' R U R' F' // PLL: 14 moves in 1.400s, 10.00 TPS
+U
+// 25 moves in 2.500s, 10.00 TPS");
+}
+
+#[test]
+fn reconstructions_round_trip_through_json() {
+    let solve = solve();
+    let json = solve.to_json();
+    assert!(json.contains(r#"["R'",300.0]"#), "{}", json);
+    assert_eq!(Reconstruction::from_json(&json).unwrap(), solve);
+    assert!(Reconstruction::from_json("{}").is_err());
+    assert!(Reconstruction::from_json(r#"{"start": "nope", "moves": []}"#).is_err());
+    assert!(Reconstruction::from_json(&json.replace("\"R'\"", "\"Q\"")).is_err());
+}
+
+#[test]
+fn replays_keep_the_pace_of_the_recording() {
+    let solve = solve();
+    assert_eq!(Replay::new(solve.clone(), 1.0).move_time(), 75.0);
+    assert_eq!(Replay::new(solve.clone(), 3.0).move_time(), 25.0);
+    assert_eq!(Replay::new(solve.clone(), 100.0).move_time(), 16.0);
+
+    let Some(context) = common::context() else { return };
+    for speed in [1.0, 2.0] {
+        let mut cube = Cube::solved(CubeAnimationOptions::default(), &context);
+        let mut replay = Replay::new(solve.clone(), speed);
+        cube.set_speed(cube.move_time() / replay.move_time());
+        replay.update(&mut cube, 0.0).unwrap();
+        assert_eq!(cube.facelet_str(), solve.start);
+        cube.animate(0.0).unwrap();
+        cube.record();
+
+        // A frame every 10 ms until everything has played
+        let mut time = 0.0;
+        while !replay.is_done() || !cube.is_idle() {
+            time += 10.0;
+            replay.update(&mut cube, time).unwrap();
+            cube.animate(time).unwrap();
+            assert!(time < 10000.0, "The replay doesn't end");
+        }
+        let replayed = cube.stop_recording().unwrap();
+        assert_eq!(replayed.end(), SOLVED);
+        // Every move finishes a move time and a frame or two after it was due
+        for (played, recorded) in replayed.moves.iter().zip(&solve.moves) {
+            assert_eq!(played.mv, recorded.mv);
+            let lag = played.time - recorded.time / speed;
+            assert!(
+                (0.0..=replay.move_time() + 20.0).contains(&lag),
+                "{:?} played at {} for {}", played.mv, played.time, recorded.time,
+            );
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use eyecandy::rubik::{apply_moves, facelets_from_scans, scan_face, CubeAppearance, FaceGrid, FaceScan, Move, SOLVED};
+use eyecandy::Error;
+use three_d_asset::{Texture2D, TextureData};
+
+///
+/// A binary PPM of the six faces side by side, in the order of the facelet string. The fixtures
+/// are synthetic images, flat sticker colors with added noise, not photos of a real cube.
+///
+fn fixture(name: &str) -> Texture2D {
+    let bytes = std::fs::read(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap();
+    let mut header = bytes.splitn(5, |b| b.is_ascii_whitespace());
+    assert_eq!(header.next(), Some(&b"P6"[..]));
+    let mut number = || std::str::from_utf8(header.next().unwrap()).unwrap().parse::<u32>().unwrap();
+    let (width, height) = (number(), number());
+    assert_eq!(number(), 255);
+    let pixels = &bytes[bytes.len() - (width * height * 3) as usize..];
+    Texture2D {
+        data: TextureData::RgbU8(pixels.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect()),
+        width,
+        height,
+        ..Default::default()
+    }
+}
+
+fn scan(name: &str) -> Result<String, Error> {
+    let image = fixture(name);
+    let size = image.height;
+    let scans: Vec<FaceScan> = (0..6)
+        .map(|face| scan_face(&image, FaceGrid { x: face * size, y: 0, width: size, height: size }).unwrap())
+        .collect();
+    facelets_from_scans(&scans.try_into().unwrap(), &CubeAppearance::default().colors)
+}
+
+fn scrambled() -> String {
+    apply_moves(SOLVED, &Move::parse_sequence("R U2 F' L D B2 R' U F2 D' L2 B").unwrap()).unwrap()
+}
+
+#[test]
+fn synthetic_images_are_read() {
+    assert_eq!(scan("scan_solved.ppm").unwrap(), SOLVED);
+    assert_eq!(scan("scan_scrambled.ppm").unwrap(), scrambled());
+    // Dim and orange, nothing like the palette, but the stickers still match their centers
+    assert_eq!(scan("scan_low_light.ppm").unwrap(), scrambled());
+}
+
+#[test]
+fn doubtful_and_impossible_scans_are_rejected() {
+    let image = fixture("scan_scrambled.ppm");
+    let size = image.height;
+    let mut scans: Vec<FaceScan> = (0..6)
+        .map(|face| scan_face(&image, FaceGrid { x: face * size, y: 0, width: size, height: size }).unwrap())
+        .collect();
+    let palette = CubeAppearance::default().colors;
+
+    // A sticker halfway between the red and the orange center can't be told
+    let (a, b) = (scans[2].stickers[4], scans[5].stickers[4]);
+    let mut blurred = scans.clone();
+    blurred[0].stickers[0].l = (a.l + b.l) / 2.0;
+    blurred[0].stickers[0].a = (a.a + b.a) / 2.0;
+    blurred[0].stickers[0].b = (a.b + b.b) / 2.0;
+    let error = facelets_from_scans(&blurred.try_into().unwrap(), &palette).unwrap_err();
+    assert!(error.to_string().contains("L1"), "{}", error);
+
+    // Two stickers of an edge swapped read fine, but make a flipped edge
+    let facelets = scrambled().into_bytes();
+    assert_ne!(facelets[9 + 7], facelets[18 + 1]);
+    let (su, sf) = (scans[1].stickers[7], scans[2].stickers[1]);
+    scans[1].stickers[7] = sf;
+    scans[2].stickers[1] = su;
+    assert_eq!(
+        facelets_from_scans(&scans.try_into().unwrap(), &palette),
+        Err(Error::Validation("An edge is flipped".to_string()))
+    );
+}
+
+#[test]
+fn bad_images_and_grids_are_rejected() {
+    let image = fixture("scan_solved.ppm");
+    let tiny = FaceGrid { x: 0, y: 0, width: 2, height: 2 };
+    assert!(matches!(scan_face(&image, tiny), Err(Error::Validation(_))));
+    let outside = FaceGrid { x: image.width - 10, y: 0, width: 36, height: 36 };
+    assert!(matches!(scan_face(&image, outside), Err(Error::Validation(_))));
+    let overflowing = FaceGrid { x: u32::MAX, y: u32::MAX, width: 36, height: 36 };
+    assert!(matches!(scan_face(&image, overflowing), Err(Error::Validation(_))));
+
+    let small = Texture2D { data: TextureData::RgbU8(vec![[0, 0, 0]; 4]), width: 2, height: 2, ..Default::default() };
+    assert!(matches!(scan_face(&small, FaceGrid::centered(2, 2)), Err(Error::Validation(_))));
+    let short = Texture2D { data: TextureData::RgbU8(vec![[0, 0, 0]; 10]), width: 36, height: 36, ..Default::default() };
+    assert!(matches!(scan_face(&short, FaceGrid::centered(36, 36)), Err(Error::Validation(_))));
+}
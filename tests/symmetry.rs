@@ -0,0 +1,58 @@
+use eyecandy::rubik::{apply_moves, canonical, find_symmetry, scramble, symmetries_of, Move, Symmetry, SOLVED};
+
+#[test]
+fn there_are_48_symmetries() {
+    let symmetries: Vec<Symmetry> = Symmetry::all().collect();
+    assert_eq!(symmetries.len(), 48);
+    assert_eq!(symmetries.iter().filter(|symmetry| symmetry.is_reflection()).count(), 24);
+    for &a in &symmetries {
+        assert_eq!(a.then(a.inverse()), Symmetry::IDENTITY);
+        for &b in &symmetries {
+            assert!(symmetries.contains(&a.then(b)));
+        }
+    }
+    assert_eq!(symmetries_of(SOLVED).unwrap().len(), 48);
+}
+
+#[test]
+fn moves_and_states_transform_alike() {
+    for seed in 1..10 {
+        let moves = scramble(seed, 20);
+        let state = apply_moves(SOLVED, &moves).unwrap();
+        for symmetry in Symmetry::all() {
+            let transformed = apply_moves(SOLVED, &symmetry.transform_moves(&moves)).unwrap();
+            assert_eq!(symmetry.transform(&state).unwrap(), transformed);
+        }
+    }
+}
+
+#[test]
+fn canonical_forms_ignore_orientation_mirroring_and_colors() {
+    let state = apply_moves(SOLVED, &scramble(7, 25)).unwrap();
+    let (form, _) = canonical(&state).unwrap();
+    for symmetry in Symmetry::all() {
+        assert_eq!(canonical(&symmetry.transform(&state).unwrap()).unwrap().0, form);
+    }
+    let recolored: String = state.chars().map(|c| match c {
+        'B' => 'G',
+        'G' => 'B',
+        c => c,
+    }).collect();
+    assert_eq!(canonical(&recolored).unwrap().0, form);
+    let other = apply_moves(SOLVED, &scramble(8, 25)).unwrap();
+    assert_ne!(canonical(&other).unwrap().0, form);
+}
+
+#[test]
+fn same_case_in_another_orientation() {
+    let state = |alg: &str| apply_moves(SOLVED, &Move::parse_sequence(alg).unwrap()).unwrap();
+    let sune = state("R U R' U R U2 R'");
+    // Performed from the back
+    let rotated = find_symmetry(&sune, &state("L U L' U L U2 L'")).unwrap().unwrap();
+    assert!(!rotated.is_reflection());
+    // With the left hand
+    let mirrored = find_symmetry(&sune, &state("L' U' L U' L' U2 L")).unwrap().unwrap();
+    assert!(mirrored.is_reflection());
+    assert!(find_symmetry(&sune, SOLVED).unwrap().is_none());
+    assert!(find_symmetry(&sune, &state("R U R' U'")).unwrap().is_none());
+}
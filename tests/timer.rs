@@ -0,0 +1,161 @@
+use eyecandy::rubik::{average, Penalty, Session, Solve, SolveTime, Timer, TimerPhase};
+
+/// An export of csTimer with a plain solve, a +2 and a DNF.
+const CSTIMER_JSON: &str = r#"{"session1":[[[0,10123],"R U R' F2 D L2 B' U2 F","",1700000000],[[2000,9876],"L' D2 B R2 U' F L2 D'","felt fast",1700000100],[[-1,15000],"B2 U R' D F2 L U2 R2","",1700000200]],"properties":{"sessionData":"{\"1\":{\"name\":1,\"opt\":{},\"rank\":1,\"stat\":[3,1,11999],\"date\":[1700000000,1700000200]}}"}}"#;
+
+/// The same session as csTimer's CSV export.
+const CSTIMER_CSV: &str = "No.;Time;Comment;Scramble;Date;P.1
+1;10.123;;R U R' F2 D L2 B' U2 F;2023-11-14 22:13:20;10.123
+2;11.876+;felt fast;L' D2 B R2 U' F L2 D';2023-11-14 22:15:00;9.876
+3;DNF(15.000);;B2 U R' D F2 L U2 R2;2023-11-14 22:16:40;15.000
+";
+
+fn times(ms: &[u32]) -> Vec<SolveTime> {
+    ms.iter().map(|&ms| SolveTime::Time(ms)).collect()
+}
+
+fn solve(time: u32, penalty: Penalty) -> Solve {
+    Solve { time, penalty, scramble: String::new(), comment: String::new(), date: 0 }
+}
+
+#[test]
+fn times_are_shown_and_parsed() {
+    assert_eq!(SolveTime::Time(9876).to_string(), "9.876");
+    assert_eq!(SolveTime::Time(62345).to_string(), "1:02.345");
+    assert_eq!(SolveTime::Dnf.to_string(), "DNF");
+    assert_eq!("1:02.345".parse(), Ok(SolveTime::Time(62345)));
+    assert_eq!("DNF(12.000)".parse(), Ok(SolveTime::Dnf));
+    assert!("1:75.000".parse::<SolveTime>().is_err());
+    assert!(SolveTime::Time(u32::MAX) < SolveTime::Dnf);
+}
+
+#[test]
+fn averages_trim_the_best_and_worst() {
+    assert_eq!(average(&times(&[10000, 12000])), None);
+    assert_eq!(average(&times(&[10000, 12000, 11000, 9000, 13000])), Some(SolveTime::Time(11000)));
+    // A single DNF is the worst result and is trimmed, a second one counts
+    let mut results = times(&[10000, 11000, 9000, 13000]);
+    results.push(SolveTime::Dnf);
+    assert_eq!(average(&results), Some(SolveTime::Time(11333)));
+    results[0] = SolveTime::Dnf;
+    assert_eq!(average(&results), Some(SolveTime::Dnf));
+
+    // 5% of 12 rounds up to one result trimmed at each end
+    let twelve = times(&[8000, 20000, 10000, 10000, 10000, 10000, 10000, 10000, 10000, 10000, 10000, 13000]);
+    assert_eq!(average(&twelve), Some(SolveTime::Time(10300)));
+
+    // Of 100 results 5 are trimmed at each end, so 5 DNFs still make an average and 6 don't
+    let mut hundred = times(&[10000; 95]);
+    hundred.extend([SolveTime::Dnf; 5]);
+    assert_eq!(average(&hundred), Some(SolveTime::Time(10000)));
+    hundred[0] = SolveTime::Dnf;
+    assert_eq!(average(&hundred), Some(SolveTime::Dnf));
+}
+
+#[test]
+fn session_stats_count_penalties() {
+    let mut session = Session::new();
+    assert_eq!(session.best(), None);
+    assert_eq!(session.mean(), None);
+    session.solves.push(solve(12000, Penalty::Dnf));
+    assert_eq!(session.mean(), Some(SolveTime::Dnf));
+    session.solves.push(solve(9000, Penalty::PlusTwo));
+    session.solves.push(solve(10500, Penalty::None));
+    assert_eq!(session.solves[1].result(), SolveTime::Time(11000));
+    assert_eq!(session.best(), Some(SolveTime::Time(10500)));
+    // DNFs are left out of the mean
+    assert_eq!(session.mean(), Some(SolveTime::Time(10750)));
+    assert_eq!(session.average(5), None);
+    session.solves.push(solve(11500, Penalty::None));
+    session.solves.push(solve(12500, Penalty::None));
+
+    let stats = session.stats();
+    assert_eq!(stats.count, 5);
+    assert_eq!(stats.ao5, Some(SolveTime::Time(11667)));
+    assert_eq!(stats.ao12, None);
+    assert_eq!(
+        stats.to_string(),
+        "5 solves, best 10.500, mean 11.375, ao5 11.667, ao12 -, ao100 -"
+    );
+}
+
+#[test]
+fn cstimer_exports_are_read() {
+    let session = Session::import(CSTIMER_JSON).unwrap();
+    assert_eq!(session.solves.len(), 3);
+    assert_eq!(session.solves[0], Solve {
+        time: 10123,
+        penalty: Penalty::None,
+        scramble: "R U R' F2 D L2 B' U2 F".to_string(),
+        comment: String::new(),
+        date: 1700000000,
+    });
+    assert_eq!(session.solves[1].penalty, Penalty::PlusTwo);
+    assert_eq!(session.solves[1].result(), SolveTime::Time(11876));
+    assert_eq!(session.solves[1].comment, "felt fast");
+    assert_eq!(session.solves[2].penalty, Penalty::Dnf);
+    assert_eq!(session.solves[2].time, 15000);
+
+    // Both formats hold the same solves
+    assert_eq!(Session::import(CSTIMER_CSV).unwrap(), session);
+}
+
+#[test]
+fn sessions_round_trip_through_cstimer_formats() {
+    let session = Session::import(CSTIMER_JSON).unwrap();
+    assert_eq!(session.to_csv(), CSTIMER_CSV);
+    assert_eq!(Session::from_csv(&session.to_csv()).unwrap(), session);
+    assert_eq!(Session::from_cstimer_json(&session.to_cstimer_json()).unwrap(), session);
+
+    // Dates before the epoch can't be saved, leap days can
+    let mut leap = Session::new();
+    leap.solves.push(Solve { date: 951782400, ..solve(10000, Penalty::None) });
+    assert!(leap.to_csv().contains(";2000-02-29 00:00:00;"));
+    assert_eq!(Session::from_csv(&leap.to_csv()).unwrap(), leap);
+    assert!(Session::from_csv("No.;Time;Comment;Scramble;Date;P.1\n1;10.000;;;1969-12-31 23:59:59;10.000").is_err());
+    assert!(Session::import("not a session").is_err());
+}
+
+#[test]
+fn inspection_gives_penalties() {
+    let mut timer = Timer::new();
+    timer.press(0.0);
+    assert_eq!(timer.phase(), TimerPhase::Inspecting { since: 0.0 });
+    assert_eq!(timer.readout(1000.0), "14");
+    assert_eq!(timer.readout(15500.0), "+2");
+    assert_eq!(timer.readout(17500.0), "DNF");
+    timer.press(14999.0);
+    assert_eq!(timer.phase(), TimerPhase::Running { since: 14999.0, penalty: Penalty::None });
+    assert_eq!(timer.readout(16233.0), "1.234");
+    assert_eq!(timer.press(24999.0).map(Solve::result), Some(SolveTime::Time(10000)));
+
+    timer.press(0.0);
+    timer.press(15500.0);
+    assert_eq!(timer.press(25500.0).map(Solve::result), Some(SolveTime::Time(12000)));
+
+    timer.press(0.0);
+    timer.press(17001.0);
+    assert_eq!(timer.press(27001.0).map(Solve::result), Some(SolveTime::Dnf));
+    assert_eq!(timer.session().solves.len(), 3);
+}
+
+#[test]
+fn turns_start_and_stop_the_timer() {
+    let mut timer = Timer::new();
+    timer.set_scramble("R U");
+    timer.press(0.0);
+    timer.turned(3000.0);
+    assert!(matches!(timer.phase(), TimerPhase::Running { since: 3000.0, .. }));
+    timer.turned(8000.0);
+    // Stops at the last turn, not when the solved state was noticed
+    let solve = timer.solved().unwrap();
+    assert_eq!((solve.time, solve.scramble.as_str()), (5000, "R U"));
+    assert_eq!(timer.solved(), None);
+
+    timer.penalize(Penalty::PlusTwo);
+    assert_eq!(timer.readout(0.0), "7.000");
+    timer.press(0.0);
+    timer.cancel();
+    assert!(!timer.is_active());
+    assert_eq!(timer.session().solves.len(), 1);
+}